@@ -6,9 +6,24 @@ use ratatui::widgets::Row;
 use tachyonfx::EffectManager;
 
 use crate::theme::{Theme, ThemeManager};
-use crate::types::SortCategory;
+use crate::columns::{default_columns, normalize_columns, ColumnKind, ColumnSetting, SecondaryKey, MAX_WIDTH, MIN_WIDTH};
+use crate::process_stat::ProcessStatMonitor;
 use crate::constants::SWEEP_DURATION_MS;
 use crate::system_info::ProcessCache;
+use crate::process_net::ProcessNetUsage;
+use crate::process_io::ProcessIoUsage;
+use crate::net_interfaces::NetworkMonitor;
+use crate::disk_stats::DiskMonitor;
+use crate::memory_stats::{MemoryMonitor, ProcessMemoryCache};
+use crate::pressure::PressureMonitor;
+use crate::sensors::SensorMonitor;
+use crate::cpu_times::CpuTimeMonitor;
+use crate::gpu::GpuMonitor;
+use crate::cgroups::{CgroupMonitor, CgroupSummary};
+use crate::signals::{freeze_or_resume, SignalFollowup, SignalTargets, SIGNALS};
+use crate::process_control::ControlDialog;
+use crate::process_details::ProcessDetails;
+use crate::threads::{ThreadSort, ThreadView};
 use crate::event::{KeyEvent, KeyCode};
 
 use std::collections::{HashMap, HashSet};
@@ -34,6 +49,8 @@ pub struct TreeItem {
 pub enum SearchType {
     Name,
     Pid,
+    Cgroup, // "ct:" prefix, container name/id, systemd unit or cgroup path
+    Frozen, // "frozen:" prefix, stopped (state T) processes, optionally by name
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -43,6 +60,9 @@ pub struct UserSettings {
     pub default_usage_view: String, // "cpu" or "gpu"
     pub default_theme: String,
     pub show_info_on_start: bool,
+    // process table columns in display order, hidden ones included. older configs get the default set
+    #[serde(default = "default_columns")]
+    pub columns: Vec<ColumnSetting>,
 }
 
 impl Default for UserSettings {
@@ -53,6 +73,7 @@ impl Default for UserSettings {
             default_usage_view: "cpu".to_string(),
             default_theme: "DarkPurple".to_string(),
             show_info_on_start: false,
+            columns: default_columns(),
         }
     }
 }
@@ -74,9 +95,14 @@ pub struct AppState {
     pub effects: EffectManager<()>,
     pub pause_overlay: bool,
     pub refresh_interval: Duration,
-    pub selected_process: usize,
-    pub sort_category: SortCategory,
-    pub current_interface: &'static str,
+    pub selected_process: usize, // row of the selected pid in the current order, see sync_selection
+    pub selected_pid: Option<u32>, // None until the next frame picks it up from selected_process
+    pub selected_name: String,
+    pub follow_selected: bool, // `w` scrolls along with the selected process when a re-sort moves it
+    pub sort_column: ColumnKind,
+    pub sort_descending: bool,
+    pub sort_secondary: SecondaryKey, // breaks ties in the sort column, the pid comes last either way
+    pub current_interface: String,
     pub show_info: bool,
     pub current_disk_index: usize,
     pub current_gpu_index: usize,
@@ -86,6 +112,14 @@ pub struct AppState {
 
     // Cache
     pub process_cache: ProcessCache,
+    pub net_usage: ProcessNetUsage, // per-process sockets and byte rates, refreshed with the system
+    pub io_usage: ProcessIoUsage,
+    pub process_stats: ProcessStatMonitor, // nice/priority/state/shared memory for the optional columns
+    pub net_monitor: NetworkMonitor,
+    pub disk_monitor: DiskMonitor,
+    pub disk_panel_visible: bool,
+    pub memory_monitor: MemoryMonitor,
+    pub process_memory: ProcessMemoryCache, // smaps_rollup of the selected process only
 
     /*
         ONLY used if the optimized version of the function is used
@@ -110,19 +144,40 @@ pub struct AppState {
     pub disk_area: Rect,
     pub terminal_area: Rect,
     pub cpu_usage_area: Rect,
+    pub cpu_average_area: Rect,
 
     // Theme management
     pub theme_manager: ThemeManager,
 
     // Search
     pub search_active: bool,
+    pub search_editing: bool, // keys go into the query, Enter locks the filter so the action keys work on it
     pub search_query: String,
-    pub filtered_processes: Vec<usize>,
+    pub filtered_processes: HashSet<u32>, // pids matching the search, so a re-sort can't shift them onto other rows
     pub search_cache_valid: bool,
 
     // Popup for errors/warnings
     pub popup_visible: bool,
     pub popup_message: String,
+    pub popup_is_error: bool, // false for plain notices (signal results etc.)
+
+    // Signal menu from `k`, ←/→ picks what it's aimed at (row, subtree, tagged set, search matches)
+    pub signal_menu_visible: bool,
+    pub signal_selected: usize,
+    pub signal_confirm: bool,
+    pub signal_choices: Vec<SignalTargets>,
+    pub signal_choice: usize,
+    pub signal_followups: Vec<SignalFollowup>,
+
+    // tagged pids for batch kill/signal/renice, kept across searches and view switches
+    pub tagged: HashSet<u32>,
+
+    // nice / i/o priority / affinity dialog from `m`
+    pub control_dialog: Option<ControlDialog>,
+
+    // details pane from `f`, re-read every refresh. the scroll counts unwrapped lines
+    pub details: Option<ProcessDetails>,
+    pub details_scroll: usize,
 
     // Pause menu
     pub pause_menu_selected: usize,
@@ -133,6 +188,9 @@ pub struct AppState {
 
     // Settings panel
     pub settings_panel_visible: bool,
+    // column chooser from `C`, edits user_settings.columns in place and saves on close
+    pub columns_panel_visible: bool,
+    pub columns_selected: usize,
     pub settings_selected_index: usize,
     pub user_settings: UserSettings,
 
@@ -141,13 +199,40 @@ pub struct AppState {
     pub daemon_selected_index: usize,
     pub daemon_settings: DaemonSettings,
     pub available_services: Vec<String>,
+    pub available_groups: Vec<String>,
+    pub daemon_config_path: PathBuf,
+    pub daemon_status_message: String,
+
+    // Service manager (integrated mode only, talks to the supervisor thread)
+    pub supervisor: Option<crate::daemon::SupervisorHandle>,
+    pub service_manager_visible: bool,
+    pub service_selected_index: usize,
+    pub service_snapshots: Vec<crate::daemon::ServiceSnapshot>,
+    pub service_snapshots_updated: Instant,
+    pub service_snapshot_error: Option<String>,
 
     // GPU cache
-    pub gpu_info_cache: Vec<crate::system_info::GpuInfo>,
-    pub gpu_cache_last_update: Instant,
+    pub gpu: GpuMonitor,
 
     // GPU view toggle
     pub gpu_usage_view: bool,
+
+    // PSI + load averages, shown in place of the cpu average line when toggled
+    pub pressure: PressureMonitor,
+    pub pressure_view: bool,
+
+    pub sensors: SensorMonitor,
+    pub sensors_panel_visible: bool,
+
+    pub cpu_times: CpuTimeMonitor,
+
+    // cgroup/container per process and the per-group totals for the grouped view
+    pub cgroups: CgroupMonitor,
+    pub cgroup_summaries: Vec<CgroupSummary>,
+    pub group_view: bool,
+    // threads of one process from `T`, replaces the process table like the grouped view
+    pub thread_view: Option<ThreadView>,
+    pub group_selected: usize,
 }
 
 
@@ -161,8 +246,13 @@ impl AppState {
             pause_overlay: false,
             refresh_interval: Duration::from_millis(2000),
             selected_process: 0,
-            sort_category: SortCategory::CpuPerCore,
-            current_interface: "eth0",
+            selected_pid: None,
+            selected_name: String::new(),
+            follow_selected: false,
+            sort_column: ColumnKind::Cpu,
+            sort_descending: true,
+            sort_secondary: SecondaryKey::Pid,
+            current_interface: "eth0".to_string(),
             show_info: false,
             current_disk_index: 0,
             current_gpu_index: 0,
@@ -170,6 +260,14 @@ impl AppState {
             visible_rows: 0,
             switch_interface_at: Instant::now() + Duration::from_millis(SWEEP_DURATION_MS),
             process_cache: ProcessCache::new(),
+            net_usage: ProcessNetUsage::new(),
+            io_usage: ProcessIoUsage::new(),
+            process_stats: ProcessStatMonitor::new(),
+            net_monitor: NetworkMonitor::new(),
+            disk_monitor: DiskMonitor::new(),
+            disk_panel_visible: false,
+            memory_monitor: MemoryMonitor::new(),
+            process_memory: ProcessMemoryCache::new(),
             
             // Row Cache
             cached_rows: Vec::with_capacity(50), // Pre-allocate for typical screen size -> May not be the best method
@@ -191,19 +289,34 @@ impl AppState {
             disk_area: Rect::default(),
             terminal_area: Rect::default(),
             cpu_usage_area: Rect::default(),
+            cpu_average_area: Rect::default(),
 
             // Themes
             theme_manager: ThemeManager::new(),
 
             // Search
             search_active: false,
+            search_editing: false,
             search_query: String::new(),
-            filtered_processes: Vec::new(),
+            filtered_processes: HashSet::new(),
             search_cache_valid: false,
 
             // Popup
             popup_visible: false,
             popup_message: String::new(),
+            popup_is_error: true,
+
+            // Signal menu
+            signal_menu_visible: false,
+            signal_selected: 0,
+            signal_confirm: false,
+            signal_choices: Vec::new(),
+            signal_choice: 0,
+            signal_followups: Vec::new(),
+            tagged: HashSet::new(),
+            control_dialog: None,
+            details: None,
+            details_scroll: 0,
 
             // Pause menu
             pause_menu_selected: 0,
@@ -214,6 +327,8 @@ impl AppState {
 
             // Settings panel
             settings_panel_visible: false,
+            columns_panel_visible: false,
+            columns_selected: 0,
             settings_selected_index: 0,
             user_settings: UserSettings::default(),
 
@@ -222,13 +337,33 @@ impl AppState {
             daemon_selected_index: 0,
             daemon_settings: DaemonSettings::default(),
             available_services: Vec::new(),
+            available_groups: Vec::new(),
+            daemon_config_path: crate::daemon::default_config_path(),
+            daemon_status_message: String::new(),
+
+            // Service manager
+            supervisor: None,
+            service_manager_visible: false,
+            service_selected_index: 0,
+            service_snapshots: Vec::new(),
+            service_snapshots_updated: Instant::now(),
+            service_snapshot_error: None,
 
             // GPU cache (will be populated on first render)
-            gpu_info_cache: Vec::new(),
-            gpu_cache_last_update: Instant::now(),
+            gpu: GpuMonitor::new(),
 
             // GPU view toggle
             gpu_usage_view: false,
+            pressure: PressureMonitor::new(),
+            pressure_view: false,
+            sensors: SensorMonitor::new(),
+            sensors_panel_visible: false,
+            cpu_times: CpuTimeMonitor::new(),
+            cgroups: CgroupMonitor::new(),
+            cgroup_summaries: Vec::new(),
+            group_view: false,
+            thread_view: None,
+            group_selected: 0,
         }
     }
     // methods to for tree shit
@@ -293,24 +428,161 @@ impl AppState {
         self.rows_cache_valid = false;
     }
     pub fn cycle_sort_left(&mut self) {
-        self.sort_category = self.sort_category.previous();
-        self.invalidate_rows_cache();
+        self.cycle_sort(false);
     }
 
     pub fn cycle_sort_right(&mut self) {
-        self.sort_category = self.sort_category.next();
-        self.invalidate_rows_cache()
+        self.cycle_sort(true);
+    }
+
+    // step through the visible columns in display order
+    fn cycle_sort(&mut self, forward: bool) {
+        let visible: Vec<ColumnKind> = self.visible_columns().iter().map(|c| c.kind).collect();
+        let current = visible.iter().position(|kind| *kind == self.sort_column).unwrap_or(0);
+        let step = if forward { 1 } else { visible.len().saturating_sub(1) };
+        let next = (current + step) % visible.len().max(1);
+        if let Some(kind) = visible.get(next) {
+            self.set_sort_column(*kind);
+        }
+        self.invalidate_sort();
+    }
+
+    // the order changed, everything derived from the sorted list has to be rebuilt
+    pub fn invalidate_sort(&mut self) {
+        self.process_cache.invalidate();
+        self.invalidate_search_cache();
+        self.invalidate_rows_cache();
+    }
+
+    // a new column starts in its natural direction (numbers biggest first, text a-z)
+    fn set_sort_column(&mut self, kind: ColumnKind) {
+        self.sort_column = kind;
+        self.sort_descending = kind.descending();
     }
 
-    pub fn switch_to_loopback(&mut self) {
-        if Instant::now() >= self.switch_interface_at && self.current_interface != "lo" {
-            self.current_interface = "lo";
+    pub fn toggle_sort_direction(&mut self) {
+        self.sort_descending = !self.sort_descending;
+        self.invalidate_sort();
+    }
+
+    pub fn toggle_secondary_sort(&mut self) {
+        self.sort_secondary = self.sort_secondary.toggle();
+        self.invalidate_sort();
+    }
+
+    pub fn visible_columns(&self) -> Vec<ColumnSetting> {
+        self.user_settings.columns.iter().filter(|c| c.visible).cloned().collect()
+    }
+
+    // a hidden column can't stay the sort key, cpu if it's shown, otherwise whatever comes first
+    fn ensure_sort_column_visible(&mut self) {
+        let visible = self.visible_columns();
+        if visible.iter().any(|c| c.kind == self.sort_column) {
+            return;
         }
+        let kind = if visible.iter().any(|c| c.kind == ColumnKind::Cpu) {
+            ColumnKind::Cpu
+        } else {
+            visible.first().map_or(ColumnKind::Pid, |c| c.kind)
+        };
+        self.set_sort_column(kind);
     }
 
-    pub fn switch_to_ethernet(&mut self) {
-        if Instant::now() >= self.switch_interface_at && self.current_interface != "eth0" {
-            self.current_interface = "eth0";
+    // Columns panel
+    pub fn open_columns_panel(&mut self) {
+        self.columns_panel_visible = true;
+        self.columns_selected = 0;
+    }
+
+    pub fn close_columns_panel(&mut self) {
+        self.columns_panel_visible = false;
+        if let Err(e) = self.save_user_settings() {
+            self.show_popup(format!("Couldn't save the column layout: {}", e));
+        }
+    }
+
+    pub fn columns_up(&mut self) {
+        self.columns_selected = self.columns_selected.saturating_sub(1);
+    }
+
+    pub fn columns_down(&mut self) {
+        if self.columns_selected + 1 < self.user_settings.columns.len() {
+            self.columns_selected += 1;
+        }
+    }
+
+    pub fn column_toggle_visible(&mut self) {
+        let visible_count = self.user_settings.columns.iter().filter(|c| c.visible).count();
+        let Some(column) = self.user_settings.columns.get_mut(self.columns_selected) else {
+            return;
+        };
+        // keep at least one column on screen
+        if column.visible && visible_count == 1 {
+            return;
+        }
+        column.visible = !column.visible;
+        self.ensure_sort_column_visible();
+        self.invalidate_rows_cache();
+    }
+
+    pub fn column_resize(&mut self, delta: i16) {
+        if let Some(column) = self.user_settings.columns.get_mut(self.columns_selected) {
+            column.width = column.width.saturating_add_signed(delta).clamp(MIN_WIDTH, MAX_WIDTH);
+            self.invalidate_rows_cache();
+        }
+    }
+
+    // swap with the neighbour, the selection moves along with the column
+    pub fn column_move(&mut self, up: bool) {
+        let i = self.columns_selected;
+        let j = if up { i.checked_sub(1) } else { Some(i + 1) };
+        let Some(j) = j.filter(|j| *j < self.user_settings.columns.len()) else {
+            return;
+        };
+        self.user_settings.columns.swap(i, j);
+        self.columns_selected = j;
+        self.invalidate_rows_cache();
+    }
+
+    pub fn column_sort_selected(&mut self) {
+        if let Some(kind) = self.user_settings.columns.get(self.columns_selected).filter(|c| c.visible).map(|c| c.kind) {
+            self.set_sort_column(kind);
+            self.invalidate_sort();
+        }
+    }
+
+    pub fn previous_interface(&mut self) {
+        self.cycle_interface(false);
+    }
+
+    pub fn next_interface(&mut self) {
+        self.cycle_interface(true);
+    }
+
+    fn cycle_interface(&mut self, forward: bool) {
+        if Instant::now() < self.switch_interface_at {
+            return;
+        }
+        if let Some(name) = self.net_monitor.cycle(&self.current_interface, forward) {
+            self.current_interface = name;
+        }
+    }
+
+    // falls back to the first non-loopback interface when the configured one isn't on this machine (or went away)
+    pub fn refresh_network(&mut self, networks: &sysinfo::Networks, elapsed_secs: f64) {
+        self.net_monitor.refresh(networks, elapsed_secs);
+        if self.net_monitor.get(&self.current_interface).is_some() {
+            return;
+        }
+        if let Some(name) = self.net_monitor.preferred() {
+            self.current_interface = name;
+        }
+    }
+
+    pub fn cycle_default_interface(&mut self, forward: bool) {
+        if let Some(name) = self.net_monitor.cycle(&self.user_settings.default_interface, forward) {
+            self.user_settings.default_interface = name;
+            self.apply_user_settings();
         }
     }
 
@@ -331,12 +603,22 @@ impl AppState {
             self.current_disk_index -= 1;
         }
     }
-    pub fn next_disk(&mut self, max_disks: usize) {
-        if self.current_disk_index + 1 < max_disks {
+    pub fn next_disk(&mut self) {
+        if self.current_disk_index + 1 < self.disk_monitor.len() {
             self.current_disk_index += 1;
         }
     }
 
+    // disks get unmounted too (usb sticks), keep the index pointing at something real
+    pub fn refresh_disks(&mut self, disks: &sysinfo::Disks, elapsed_secs: f64) {
+        self.disk_monitor.refresh(disks, elapsed_secs);
+        self.current_disk_index = self.current_disk_index.min(self.disk_monitor.len().saturating_sub(1));
+    }
+
+    pub fn toggle_disk_panel(&mut self) {
+        self.disk_panel_visible = !self.disk_panel_visible;
+    }
+
     pub fn previous_gpu(&mut self) {
         if self.current_gpu_index > 0 {
             self.current_gpu_index -= 1;
@@ -353,14 +635,83 @@ impl AppState {
         self.gpu_usage_view = !self.gpu_usage_view;
     }
 
-    pub fn update_gpu_cache_if_needed(&mut self) {
-        // Only update GPU info every 5 seconds to avoid performance hit
-        if self.gpu_info_cache.is_empty() || self.gpu_cache_last_update.elapsed() > Duration::from_secs(5) {
-            self.gpu_info_cache = crate::system_info::get_gpu_info();
-            self.gpu_cache_last_update = Instant::now();
+    pub fn toggle_pressure_view(&mut self) {
+        self.pressure_view = !self.pressure_view;
+    }
+
+    pub fn toggle_sensors_panel(&mut self) {
+        self.sensors_panel_visible = !self.sensors_panel_visible;
+    }
+
+    pub fn refresh_cgroups(&mut self, system: &sysinfo::System) {
+        self.cgroups.refresh(system);
+        self.cgroup_summaries = self.cgroups.summarize(system);
+        self.group_selected = self.group_selected.min(self.cgroup_summaries.len().saturating_sub(1));
+    }
+
+    pub fn refresh_process_stats(&mut self, system: &sysinfo::System) {
+        let needed = self.visible_columns().iter().any(|c| c.kind.needs_proc_stat());
+        self.process_stats.refresh(system, needed);
+    }
+
+    pub fn toggle_group_view(&mut self) {
+        self.group_view = !self.group_view;
+        self.group_selected = 0;
+        self.thread_view = None;
+    }
+
+    // Thread view
+    pub fn open_thread_view(&mut self, pid: u32, name: String) {
+        match ThreadView::open(pid, name) {
+            Ok(view) => {
+                self.thread_view = Some(view);
+                self.group_view = false;
+            }
+            Err(e) => self.show_popup(format!("Can't list threads of {}: {}", pid, e)),
+        }
+    }
+
+    pub fn close_thread_view(&mut self) {
+        self.thread_view = None;
+    }
+
+    pub fn refresh_threads(&mut self) {
+        if let Some(view) = self.thread_view.as_mut() {
+            view.refresh();
         }
     }
 
+    pub fn thread_sort_cycle(&mut self, forward: bool) {
+        if let Some(view) = self.thread_view.as_mut() {
+            let sort: ThreadSort = if forward { view.sort.next() } else { view.sort.previous() };
+            view.set_sort(sort);
+        }
+    }
+
+    pub fn group_view_up(&mut self) {
+        self.group_selected = self.group_selected.saturating_sub(1);
+    }
+
+    pub fn group_view_down(&mut self) {
+        if self.group_selected + 1 < self.cgroup_summaries.len() {
+            self.group_selected += 1;
+        }
+    }
+
+    // leave the grouped view with the process list filtered down to the selected group
+    pub fn search_selected_group(&mut self) {
+        let Some(summary) = self.cgroup_summaries.get(self.group_selected) else {
+            return;
+        };
+        self.search_query = format!("ct:{}", summary.group);
+        self.search_active = true;
+        self.search_editing = false;
+        self.search_cache_valid = false;
+        self.reset_selection();
+        self.group_view = false;
+        self.invalidate_rows_cache();
+    }
+
     // Settings panel control
     pub fn open_settings_panel(&mut self) {
         self.settings_panel_visible = true;
@@ -388,12 +739,8 @@ impl AppState {
         let ms = self.user_settings.refresh_interval_ms.clamp(100, 10_000);
         self.refresh_interval = Duration::from_millis(ms);
 
-        // Interface (only eth0/lo recognized for now)
-        self.current_interface = if self.user_settings.default_interface.to_lowercase() == "lo" {
-            "lo"
-        } else {
-            "eth0"
-        };
+        // Interface, checked against the real list on the next network refresh
+        self.current_interface = self.user_settings.default_interface.clone();
 
         // Usage view
         self.gpu_usage_view = self.user_settings.default_usage_view.to_lowercase() == "gpu";
@@ -405,6 +752,11 @@ impl AppState {
 
         // Show info toggle
         self.show_info = self.user_settings.show_info_on_start;
+
+        // Columns
+        normalize_columns(&mut self.user_settings.columns);
+        self.ensure_sort_column_visible();
+        self.invalidate_rows_cache();
     }
 
     pub fn settings_path() -> Option<PathBuf> {
@@ -457,13 +809,13 @@ impl AppState {
 
     pub fn toggle_search(&mut self){
         self.search_active = !self.search_active;
+        self.search_editing = self.search_active;
         if !self.search_active {
             //reset all vars
             self.search_query.clear();
             self.filtered_processes.clear();
             self.search_cache_valid = false;
-            self.selected_process = 0;
-            self.scroll_offset = 0;
+            self.reset_selection();
         }
 
         self.invalidate_rows_cache();
@@ -477,8 +829,7 @@ impl AppState {
         if self.search_active {
             self.search_query.push(c);
             self.search_cache_valid = false;
-            self.selected_process = 0;
-            self.scroll_offset = 0;
+            self.reset_selection();
             self.invalidate_rows_cache();
         }
     }
@@ -487,8 +838,7 @@ impl AppState {
         if self.search_active && !self.search_query.is_empty() {
             self.search_query.pop();
             self.search_cache_valid = false;
-            self.selected_process = 0;
-            self.scroll_offset = 0;
+            self.reset_selection();
             self.invalidate_rows_cache();
         }
     }
@@ -502,11 +852,15 @@ impl AppState {
     }
 
     pub fn handle_search_input(&mut self, key: KeyEvent) -> bool {
-        if !self.search_active{
+        if !self.search_active || !self.search_editing {
             return false;
         }
 
         match key.code {
+            KeyCode::Enter if !self.is_search_empty() => {
+                self.search_editing = false;
+                return true;
+            }
             KeyCode::Char(c) => {
                 if c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ' ' | ':') {
                     self.add_search_char(c);
@@ -527,8 +881,7 @@ impl AppState {
                 if !self.search_query.is_empty(){
                     self.search_query.clear();
                     self.search_cache_valid = false;
-                    self.selected_process = 0;
-                    self.scroll_offset = 0;
+                    self.reset_selection();
                     self.invalidate_rows_cache();
                 } else {
                     return false;
@@ -544,6 +897,10 @@ impl AppState {
     pub fn get_search_type(&self) -> SearchType {
         if self.search_query.starts_with("pid:") {
             SearchType::Pid
+        } else if self.search_query.starts_with("ct:") {
+            SearchType::Cgroup
+        } else if self.search_query.starts_with("frozen:") {
+            SearchType::Frozen
         } else {
             SearchType::Name
         }
@@ -552,6 +909,10 @@ impl AppState {
     pub fn get_search_value(&self) -> &str {
         if self.search_query.starts_with("pid:") {
             &self.search_query[4..]
+        } else if self.search_query.starts_with("ct:") {
+            &self.search_query[3..]
+        } else if self.search_query.starts_with("frozen:") {
+            &self.search_query[7..]
         } else {
             &self.search_query
         }
@@ -559,9 +920,347 @@ impl AppState {
 
     pub fn show_popup(&mut self, message: String) {
         self.popup_visible = true;
+        self.popup_is_error = true;
         self.popup_message = message;
     }
 
+    pub fn show_notice(&mut self, message: String) {
+        self.show_popup(message);
+        self.popup_is_error = false;
+    }
+
+    // Signal menu controls
+    pub fn open_signal_menu(&mut self, mut choices: Vec<SignalTargets>) {
+        choices.retain(|choice| !choice.targets.is_empty());
+        if choices.is_empty() {
+            return;
+        }
+        self.signal_menu_visible = true;
+        self.signal_selected = 0; // SIGTERM
+        self.signal_confirm = false;
+        self.signal_choices = choices;
+        self.signal_choice = 0;
+    }
+
+    pub fn close_signal_menu(&mut self) {
+        self.signal_menu_visible = false;
+        self.signal_confirm = false;
+        self.signal_choices.clear();
+    }
+
+    pub fn signal_target_cycle(&mut self, forward: bool) {
+        let len = self.signal_choices.len().max(1);
+        self.signal_choice = if forward { (self.signal_choice + 1) % len } else { (self.signal_choice + len - 1) % len };
+    }
+
+    pub fn signal_targets(&self) -> Option<&SignalTargets> {
+        self.signal_choices.get(self.signal_choice)
+    }
+
+    pub fn signal_menu_up(&mut self) {
+        self.signal_selected = self.signal_selected.saturating_sub(1);
+    }
+
+    pub fn signal_menu_down(&mut self) {
+        if self.signal_selected + 1 < SIGNALS.len() {
+            self.signal_selected += 1;
+        }
+    }
+
+    pub fn send_selected_signal(&mut self) {
+        let (targets, at_once) = match self.signal_choices.get_mut(self.signal_choice) {
+            Some(choice) => (std::mem::take(&mut choice.targets), choice.at_once),
+            None => return,
+        };
+        let signal = &SIGNALS[self.signal_selected.min(SIGNALS.len() - 1)];
+        self.close_signal_menu();
+
+        let (sent, failures) = crate::signals::send_to_all(targets, signal, at_once);
+
+        // failures right away, whether the rest actually exited comes from the followup
+        let total = sent.len() + failures.len();
+        if !failures.is_empty() {
+            let summary = if total == 1 {
+                format!("Failed to send {} to {}", signal.name, failures[0])
+            } else {
+                format!("{} sent to {} of {} processes, failed:\n{}", signal.name, sent.len(), total, failures.join("\n"))
+            };
+            self.show_popup(summary);
+        } else if !signal.terminates {
+            let summary = match sent.as_slice() {
+                [(pid, name)] => format!("Sent {} to {} ({})", signal.name, name, pid),
+                _ => format!("Sent {} to {} processes", signal.name, sent.len()),
+            };
+            self.show_notice(summary);
+        }
+        if signal.terminates && !sent.is_empty() {
+            self.signal_followups.push(SignalFollowup {
+                targets: sent,
+                signal_name: signal.name,
+                sent_at: Instant::now(),
+            });
+        }
+
+        self.invalidate_tree_cache();
+        self.invalidate_sort();
+    }
+
+    // Process control dialog
+    pub fn open_control_dialog(&mut self, pid: u32, name: String, num_cpus: usize) {
+        match ControlDialog::open(pid, name, num_cpus) {
+            Ok(dialog) => self.control_dialog = Some(dialog),
+            Err(e) => self.show_popup(format!("Can't read settings of {}: {}", pid, e)),
+        }
+    }
+
+    pub fn close_control_dialog(&mut self) {
+        self.control_dialog = None;
+    }
+
+    pub fn apply_control_dialog(&mut self) {
+        let Some(dialog) = self.control_dialog.take() else {
+            return;
+        };
+        let results = dialog.apply();
+        if results.is_empty() {
+            return;
+        }
+
+        let lines: Vec<String> = results
+            .iter()
+            .map(|(change, result)| match result {
+                Ok(()) => change.clone(),
+                Err(e) => format!("{} failed, {}", change, e),
+            })
+            .collect();
+        let message = format!("{} ({}):\n{}", dialog.name, dialog.pid, lines.join("\n"));
+        if results.iter().any(|(_, result)| result.is_err()) {
+            self.show_popup(message);
+        } else {
+            self.show_notice(message);
+        }
+        self.invalidate_sort();
+    }
+
+    // Details pane
+    pub fn open_details(&mut self, system: &sysinfo::System, pid: u32) {
+        match ProcessDetails::read(system, pid) {
+            Some(details) => {
+                self.details = Some(details);
+                self.details_scroll = 0;
+            }
+            None => self.show_popup(format!("Process {} is gone", pid)),
+        }
+    }
+
+    pub fn close_details(&mut self) {
+        self.details = None;
+    }
+
+    // keeps the last reading around once the process exits so it can still be looked at
+    pub fn refresh_details(&mut self, system: &sysinfo::System) {
+        let Some(details) = self.details.as_mut() else {
+            return;
+        };
+        if details.exited {
+            return;
+        }
+        match ProcessDetails::read(system, details.pid) {
+            Some(fresh) => *details = fresh,
+            None => details.exited = true,
+        }
+        self.details_scroll = self.details_scroll.min(details.line_count().saturating_sub(1));
+    }
+
+    pub fn details_scroll_by(&mut self, delta: isize) {
+        let Some(details) = &self.details else {
+            return;
+        };
+        let last = details.line_count().saturating_sub(1);
+        self.details_scroll = self.details_scroll.saturating_add_signed(delta).min(last);
+    }
+
+    // jump to the start of the next/previous section, wrapping around
+    pub fn details_jump_section(&mut self, forward: bool) {
+        let Some(details) = &self.details else {
+            return;
+        };
+        let offsets = details.section_offsets();
+        let current = details.section_at(self.details_scroll);
+        let target = if forward {
+            (current + 1) % offsets.len()
+        } else if self.details_scroll > offsets[current] {
+            // like a music player, back goes to the start of the section first
+            current
+        } else {
+            (current + offsets.len() - 1) % offsets.len()
+        };
+        self.details_scroll = offsets[target];
+    }
+
+    // Freezing
+    pub fn freeze_or_resume(&mut self, targets: Vec<(u32, String)>) {
+        if targets.is_empty() {
+            return;
+        }
+        let total = targets.len();
+        let (signal, sent, failures) = freeze_or_resume(targets);
+        let verb = if signal.number == libc::SIGCONT { "Resumed" } else { "Froze" };
+        let done = match sent.as_slice() {
+            [(pid, name)] if total == 1 => format!("{} {} ({}) with {}", verb, name, pid, signal.name),
+            _ => format!("{} {} of {} processes with {}", verb, sent.len(), total, signal.name),
+        };
+
+        if failures.is_empty() {
+            self.show_notice(done);
+        } else {
+            self.show_popup(format!("{}, failed:\n{}", done, failures.join("\n")));
+        }
+        self.invalidate_sort();
+    }
+
+    // a key moved the highlight, the pid under it is picked up on the next frame
+    pub fn select_row(&mut self, index: usize) {
+        self.selected_process = index;
+        self.selected_pid = None;
+    }
+
+    fn reset_selection(&mut self) {
+        self.select_row(0);
+        self.scroll_offset = 0;
+    }
+
+    pub fn toggle_follow(&mut self) {
+        self.follow_selected = !self.follow_selected;
+        self.invalidate_rows_cache();
+    }
+
+    /*
+        called with the list that's about to be drawn. the selection is a pid, the index is just where it sits right now,
+        so a re-sort moves the highlight along with the process instead of leaving it on whatever took its row
+        (and `k` hits the process you picked). following also scrolls to keep it on screen
+     */
+    pub fn sync_selection(&mut self, system: &sysinfo::System, processes: &[&sysinfo::Process]) {
+        if let Some(pid) = self.selected_pid {
+            if let Some(index) = processes.iter().position(|p| p.pid().as_u32() == pid) {
+                self.selected_process = index;
+            } else {
+                // gone, the row below moves up into the same spot. a followed process is worth a notice,
+                // a plain selection just moves on quietly
+                self.selected_process = self.selected_process.min(processes.len().saturating_sub(1));
+                if self.follow_selected {
+                    let reason = if system.process(Pid::from_u32(pid)).is_some() { "no longer matches the search" } else { "exited" };
+                    let mut message = format!("Followed process {} (PID {}) {}, stopped following", self.selected_name, pid, reason);
+                    if let Some(process) = processes.get(self.selected_process) {
+                        message.push_str(&format!("\nnow selected: {} (PID {})", process.name().to_string_lossy(), process.pid()));
+                    }
+                    self.show_notice(message);
+                }
+                self.follow_selected = false;
+                self.selected_pid = None;
+            }
+        }
+
+        self.selected_process = self.selected_process.min(processes.len().saturating_sub(1));
+        if let Some(process) = processes.get(self.selected_process) {
+            self.selected_pid = Some(process.pid().as_u32());
+            self.selected_name = process.name().to_string_lossy().to_string();
+        }
+
+        if self.follow_selected {
+            self.scroll_to_selected();
+        }
+    }
+
+    // the least scrolling that puts the selected row on screen
+    pub fn scroll_to_selected(&mut self) {
+        if self.visible_rows == 0 {
+            return;
+        }
+        if self.selected_process < self.scroll_offset {
+            self.scroll_offset = self.selected_process;
+        } else if self.selected_process >= self.scroll_offset + self.visible_rows {
+            self.scroll_offset = self.selected_process + 1 - self.visible_rows;
+        }
+    }
+
+    // `Z` swaps the current search for the frozen filter and back
+    pub fn toggle_frozen_filter(&mut self) {
+        if self.search_active && self.get_search_type() == SearchType::Frozen {
+            self.toggle_search();
+            return;
+        }
+        self.search_query = "frozen:".to_string();
+        self.search_active = true;
+        self.search_editing = false;
+        self.search_cache_valid = false;
+        self.reset_selection();
+        self.invalidate_rows_cache();
+    }
+
+    // Tagging
+    pub fn toggle_tag(&mut self, pid: u32) {
+        if !self.tagged.remove(&pid) {
+            self.tagged.insert(pid);
+        }
+        self.invalidate_rows_cache();
+    }
+
+    pub fn tag_all(&mut self, pids: impl IntoIterator<Item = u32>) {
+        self.tagged.extend(pids);
+        self.invalidate_rows_cache();
+    }
+
+    pub fn clear_tags(&mut self) {
+        self.tagged.clear();
+        self.invalidate_rows_cache();
+    }
+
+    pub fn is_tagged(&self, pid: u32) -> bool {
+        self.tagged.contains(&pid)
+    }
+
+    // pids get reused, forget tags of processes that are gone
+    pub fn prune_tags(&mut self, system: &sysinfo::System) {
+        let before = self.tagged.len();
+        self.tagged.retain(|pid| system.process(Pid::from_u32(*pid)).is_some());
+        if self.tagged.len() != before {
+            self.invalidate_rows_cache();
+        }
+    }
+
+    // (pid, name) of every tagged process, in pid order so batch summaries read the same every time
+    pub fn tagged_targets(&self, system: &sysinfo::System) -> Vec<(u32, String)> {
+        let mut targets: Vec<(u32, String)> = self.tagged
+            .iter()
+            .filter_map(|pid| {
+                system.process(Pid::from_u32(*pid)).map(|p| (*pid, p.name().to_string_lossy().to_string()))
+            })
+            .collect();
+        targets.sort();
+        targets
+    }
+
+    // call once per frame, reports processes that exited (or didn't) after a signal, one popup at a time
+    pub fn poll_signal_followups(&mut self) {
+        if self.popup_visible || self.signal_followups.is_empty() {
+            return;
+        }
+
+        let mut messages = Vec::new();
+        self.signal_followups.retain(|followup| match followup.check() {
+            Some(message) => {
+                messages.push(message);
+                false
+            }
+            None => true,
+        });
+
+        if !messages.is_empty() {
+            self.show_notice(messages.join("\n"));
+        }
+    }
+
     pub fn dismiss_popup(&mut self) {
         self.popup_visible = false;
         self.popup_message.clear();
@@ -609,6 +1308,7 @@ impl AppState {
     pub fn open_daemon_panel(&mut self) {
         self.daemon_panel_visible = true;
         self.daemon_selected_index = 0;
+        self.daemon_status_message.clear();
         // Load available services from config
         let _ = self.load_available_services();
     }
@@ -630,28 +1330,23 @@ impl AppState {
     }
 
     pub fn load_available_services(&mut self) -> Result<(), String> {
-        // Load services from daemon config file
-        let daemon_config_path = dirs::config_dir()
-            .ok_or("Unable to resolve config directory")?
-            .join("r-top")
-            .join("services.toml");
-
         self.available_services.clear();
+        self.available_groups.clear();
 
-        if daemon_config_path.exists() {
-            let content = std::fs::read_to_string(&daemon_config_path)
+        // Load services from daemon config file
+        if self.daemon_config_path.exists() {
+            let configs = crate::daemon::read_service_configs(&self.daemon_config_path)
                 .map_err(|e| format!("Read daemon config failed: {}", e))?;
-            
-            // Parse TOML and extract service names
-            if let Ok(toml_val) = content.parse::<toml::Value>() {
-                if let Some(services) = toml_val.get("services").and_then(|v| v.as_array()) {
-                    for service in services {
-                        if let Some(name) = service.get("name").and_then(|n| n.as_str()) {
-                            self.available_services.push(name.to_string());
-                        }
+
+            for config in &configs {
+                self.available_services.push(config.name.clone());
+                for group in &config.groups {
+                    if !self.available_groups.contains(group) {
+                        self.available_groups.push(group.clone());
                     }
                 }
             }
+            self.available_groups.sort();
         }
 
         // Add "None" as first option
@@ -662,6 +1357,127 @@ impl AppState {
         Ok(())
     }
 
+    // panel rows are laid out as: services, groups, Save, Reset
+    pub fn daemon_panel_len(&self) -> usize {
+        self.available_services.len() + self.available_groups.len() + 2
+    }
+
+    pub fn selected_daemon_group(&self) -> Option<&String> {
+        self.daemon_selected_index
+            .checked_sub(self.available_services.len())
+            .and_then(|i| self.available_groups.get(i))
+    }
+
+    pub fn run_daemon_group_action(&mut self, action: crate::daemon::ServiceAction) {
+        let Some(group) = self.selected_daemon_group().cloned() else {
+            return;
+        };
+
+        self.dispatch_daemon_command(crate::daemon::DaemonCommand {
+            action,
+            target: crate::daemon::ControlTarget::Group(group),
+        });
+    }
+
+    // Service manager controls
+    pub fn open_service_manager(&mut self) {
+        self.service_manager_visible = true;
+        self.service_selected_index = 0;
+        self.daemon_status_message.clear();
+        self.refresh_service_snapshots();
+    }
+
+    pub fn close_service_manager(&mut self) {
+        self.service_manager_visible = false;
+    }
+
+    pub fn service_manager_up(&mut self) {
+        if self.service_selected_index > 0 {
+            self.service_selected_index -= 1;
+        }
+    }
+
+    pub fn service_manager_down(&mut self) {
+        if self.service_selected_index + 1 < self.service_snapshots.len() {
+            self.service_selected_index += 1;
+        }
+    }
+
+    pub fn refresh_service_snapshots(&mut self) {
+        // in-process supervisor publishes its own snapshots, otherwise ask a standalone daemon over the socket
+        let result = match &self.supervisor {
+            Some(handle) if handle.is_running() => Ok(handle.snapshot()),
+            Some(_) => Err("supervisor thread has exited".to_string()),
+            None => crate::daemon::query_service_status(),
+        };
+
+        match result {
+            Ok(snapshots) => {
+                self.service_snapshots = snapshots;
+                self.service_snapshot_error = None;
+            }
+            Err(e) => self.service_snapshot_error = Some(e),
+        }
+        self.service_selected_index = self.service_selected_index.min(self.service_snapshots.len().saturating_sub(1));
+        self.service_snapshots_updated = Instant::now();
+    }
+
+    pub fn update_service_snapshots_if_needed(&mut self) {
+        // the in-process snapshot is just a mutex read, the socket round trip is cheap but not free so once a second
+        if self.supervisor.is_some() || self.service_snapshots_updated.elapsed() >= Duration::from_secs(1) {
+            self.refresh_service_snapshots();
+        }
+    }
+
+    // send a command to whichever supervisor we can reach, results from the in-process one arrive later
+    fn dispatch_daemon_command(&mut self, command: crate::daemon::DaemonCommand) {
+        let action = command.action;
+        let result = match &self.supervisor {
+            Some(handle) => handle.send(command).map(|_| format!("{} requested...", action.as_str())),
+            None => crate::daemon::send_control_command(&command),
+        };
+
+        match result {
+            Ok(msg) => self.daemon_status_message = msg,
+            Err(e) => {
+                self.daemon_status_message.clear();
+                self.show_popup(format!("Daemon {} failed: {}", action.as_str(), e));
+            }
+        }
+    }
+
+    // call once per frame, picks up results of commands sent to the supervisor thread
+    pub fn poll_supervisor_responses(&mut self) {
+        let Some(handle) = &self.supervisor else {
+            return;
+        };
+
+        let mut errors = Vec::new();
+        while let Some(response) = handle.try_response() {
+            match response {
+                Ok(msg) => self.daemon_status_message = msg,
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            self.daemon_status_message.clear();
+            self.show_popup(errors.join("\n"));
+        }
+    }
+
+    pub fn run_service_action(&mut self, action: crate::daemon::ServiceAction) {
+        let Some(name) = self.service_snapshots.get(self.service_selected_index).map(|s| s.name.clone()) else {
+            return;
+        };
+
+        self.dispatch_daemon_command(crate::daemon::DaemonCommand {
+            action,
+            target: crate::daemon::ControlTarget::Service(name),
+        });
+        self.refresh_service_snapshots();
+    }
+
     pub fn daemon_settings_path() -> Option<PathBuf> {
         dirs::config_dir().map(|p| p.join("r-top").join("daemon_settings.toml"))
     }
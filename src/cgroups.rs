@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::fs;
+use sysinfo::System;
+
+/*
+    cgroup v2 membership per process, from the "0::/path" line of /proc/<pid>/cgroup.
+    v1-only hosts don't have that line and hybrid ones can leave it at "/", there the name=systemd (or first non-root) hierarchy is used instead.
+    containers are recognised by the scope/directory names the runtimes create:
+        docker-<id>.scope / docker/<id>        (docker, systemd or cgroupfs driver)
+        libpod-<id>.scope                      (podman)
+        cri-containerd-<id>.scope, crio-<id>.scope, kubepods.../<id>
+        lxc.payload.<name> / lxc/<name>
+    docker names come from /var/lib/docker/containers/<id>/config.v2.json when we can read it (root), the short id otherwise
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupKind {
+    Container,
+    Service,
+    Scope,
+    Slice,
+    Other,
+}
+
+impl GroupKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GroupKind::Container => "container",
+            GroupKind::Service => "service",
+            GroupKind::Scope => "scope",
+            GroupKind::Slice => "slice",
+            GroupKind::Other => "cgroup",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProcessCgroup {
+    pub path: String,
+    pub group: String, // container name, systemd unit or the path itself, what processes are grouped by
+    pub kind: GroupKind,
+    pub container_id: Option<String>,
+}
+
+// one row of the grouped view
+pub struct CgroupSummary {
+    pub group: String,
+    pub kind: GroupKind,
+    pub path: String,
+    pub processes: usize,
+    pub cpu: f32, // percent of all cores
+    pub memory: u64,
+}
+
+pub struct CgroupMonitor {
+    per_pid: HashMap<u32, ProcessCgroup>,
+    docker_names: HashMap<String, Option<String>>, // id -> name, None if config.v2.json wasn't readable
+}
+
+impl CgroupMonitor {
+    pub fn new() -> Self {
+        Self {
+            per_pid: HashMap::new(),
+            docker_names: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&ProcessCgroup> {
+        self.per_pid.get(&pid)
+    }
+
+    // call right after system.refresh_all()
+    pub fn refresh(&mut self, system: &System) {
+        let mut per_pid = HashMap::with_capacity(system.processes().len());
+        for pid in system.processes().keys() {
+            let pid = pid.as_u32();
+            let Some(path) = read_cgroup_path(pid) else {
+                continue;
+            };
+            per_pid.insert(pid, self.classify(path));
+        }
+        self.per_pid = per_pid;
+    }
+
+    // processes aggregated per group, busiest first
+    pub fn summarize(&self, system: &System) -> Vec<CgroupSummary> {
+        let num_cores = system.cpus().len().max(1) as f32;
+        let mut groups: HashMap<&str, CgroupSummary> = HashMap::new();
+
+        for (pid, process) in system.processes() {
+            let Some(cgroup) = self.per_pid.get(&pid.as_u32()) else {
+                continue;
+            };
+            let summary = groups.entry(cgroup.group.as_str()).or_insert_with(|| CgroupSummary {
+                group: cgroup.group.clone(),
+                kind: cgroup.kind,
+                path: cgroup.path.clone(),
+                processes: 0,
+                cpu: 0.0,
+                memory: 0,
+            });
+            summary.processes += 1;
+            summary.cpu += process.cpu_usage() / num_cores;
+            summary.memory += process.memory();
+        }
+
+        let mut summaries: Vec<CgroupSummary> = groups.into_values().collect();
+        summaries.sort_by(|a, b| {
+            b.cpu.partial_cmp(&a.cpu)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| b.memory.cmp(&a.memory))
+                .then_with(|| a.group.cmp(&b.group))
+        });
+        summaries
+    }
+
+    fn classify(&mut self, path: String) -> ProcessCgroup {
+        let components: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+
+        // innermost container wins, a container can sit under a pod slice
+        for component in components.iter().rev() {
+            if let Some((runtime, id)) = container_id(component) {
+                let name = match runtime {
+                    Runtime::Docker => self.docker_name(id),
+                    Runtime::Lxc => Some(id.to_string()),
+                    Runtime::Other => None,
+                };
+                let short_id: String = id.chars().take(12).collect();
+                return ProcessCgroup {
+                    group: name.unwrap_or(short_id),
+                    kind: GroupKind::Container,
+                    container_id: (runtime != Runtime::Lxc).then(|| id.to_string()),
+                    path,
+                };
+            }
+        }
+
+        // cgroupfs-driver docker and kubepods use a bare id as the directory name
+        match components.as_slice() {
+            [.., parent, id] if (*parent == "docker" || parent.starts_with("pod") || parent.starts_with("kubepods")) && is_container_id(id) => {
+                let name = if *parent == "docker" { self.docker_name(id) } else { None };
+                let group = name.unwrap_or_else(|| id.chars().take(12).collect());
+                let container_id = Some(id.to_string());
+                return ProcessCgroup { group, kind: GroupKind::Container, container_id, path };
+            }
+            _ => {}
+        }
+
+        // otherwise the innermost systemd unit, services before their slices
+        let unit = components.iter().rev().find_map(|c| {
+            if c.ends_with(".service") {
+                Some((c.to_string(), GroupKind::Service))
+            } else if c.ends_with(".scope") {
+                Some((c.to_string(), GroupKind::Scope))
+            } else if c.ends_with(".slice") {
+                Some((c.to_string(), GroupKind::Slice))
+            } else {
+                None
+            }
+        });
+        let (group, kind) = unit.unwrap_or_else(|| (path.clone(), GroupKind::Other));
+        ProcessCgroup { path, group, kind, container_id: None }
+    }
+
+    fn docker_name(&mut self, id: &str) -> Option<String> {
+        self.docker_names
+            .entry(id.to_string())
+            .or_insert_with(|| read_docker_name(id))
+            .clone()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Runtime {
+    Docker,
+    Lxc,
+    Other,
+}
+
+fn container_id(component: &str) -> Option<(Runtime, &str)> {
+    if let Some(name) = component.strip_prefix("lxc.payload.") {
+        return Some((Runtime::Lxc, name));
+    }
+    let scope = component.strip_suffix(".scope")?;
+    let (runtime, id) = if let Some(id) = scope.strip_prefix("docker-") {
+        (Runtime::Docker, id)
+    } else if let Some(id) = scope.strip_prefix("libpod-") {
+        (Runtime::Other, id)
+    } else if let Some(id) = scope.strip_prefix("cri-containerd-") {
+        (Runtime::Other, id)
+    } else if let Some(id) = scope.strip_prefix("crio-") {
+        (Runtime::Other, id)
+    } else {
+        return None;
+    };
+    is_container_id(id).then_some((runtime, id))
+}
+
+fn is_container_id(s: &str) -> bool {
+    s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn read_cgroup_path(pid: u32) -> Option<String> {
+    let content = fs::read_to_string(format!("/proc/{}/cgroup", pid)).ok()?;
+    Some(parse_cgroup_path(&content))
+}
+
+// the contents of /proc/<pid>/cgroup down to the one path processes get grouped by
+fn parse_cgroup_path(content: &str) -> String {
+    let mut unified = None;
+    let mut fallback = None;
+
+    for line in content.lines() {
+        let mut parts = line.splitn(3, ':');
+        let (Some(id), Some(controllers), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if id == "0" && controllers.is_empty() {
+            unified = Some(path.to_string());
+        } else if path != "/" && (controllers == "name=systemd" || fallback.is_none()) {
+            fallback = Some(path.to_string());
+        }
+    }
+
+    // hybrid hosts can leave everything at the v2 root and do the real grouping in v1
+    let path = match (unified, fallback) {
+        (Some(unified), Some(fallback)) if unified == "/" => fallback,
+        (Some(unified), _) => return unified,
+        (None, fallback) => fallback.unwrap_or_else(|| "/".to_string()),
+    };
+
+    // lxc on a v1 host, the systemd hierarchy has "/lxc/<name>"
+    match path.strip_prefix("/lxc/") {
+        Some(rest) => format!("/lxc.payload.{}", rest.split('/').next().unwrap_or(rest)),
+        None => path,
+    }
+}
+
+// "Name":"/web-1" in the container's config, needs read access to /var/lib/docker
+fn read_docker_name(id: &str) -> Option<String> {
+    let content = fs::read_to_string(format!("/var/lib/docker/containers/{}/config.v2.json", id)).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&content).ok()?;
+    config.get("Name")?.as_str().map(|name| name.trim_start_matches('/').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4f1c2b3a5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f7a8";
+
+    // docker names come from a cache seeded here, so nothing under /var/lib/docker is read
+    fn monitor(docker_name: Option<&str>) -> CgroupMonitor {
+        let mut monitor = CgroupMonitor::new();
+        monitor.docker_names.insert(ID.to_string(), docker_name.map(str::to_string));
+        monitor
+    }
+
+    #[test]
+    fn v2_unified_line() {
+        let content = "0::/user.slice/user-1000.slice/session-2.scope\n";
+        assert_eq!(parse_cgroup_path(content), "/user.slice/user-1000.slice/session-2.scope");
+    }
+
+    #[test]
+    fn v1_prefers_the_systemd_hierarchy() {
+        let content = "\
+12:pids:/system.slice/cron.service
+11:memory:/
+10:cpu,cpuacct:/system.slice
+1:name=systemd:/system.slice/nginx.service
+";
+        assert_eq!(parse_cgroup_path(content), "/system.slice/nginx.service");
+    }
+
+    #[test]
+    fn v1_without_systemd_takes_the_first_non_root() {
+        let content = "4:memory:/\n3:cpu:/batch/job-7\n2:pids:/other\n";
+        assert_eq!(parse_cgroup_path(content), "/batch/job-7");
+    }
+
+    #[test]
+    fn hybrid_with_v2_left_at_root_falls_back_to_v1() {
+        let content = "\
+5:memory:/
+1:name=systemd:/system.slice/docker-abc.scope
+0::/
+";
+        assert_eq!(parse_cgroup_path(content), "/system.slice/docker-abc.scope");
+    }
+
+    #[test]
+    fn hybrid_with_a_real_v2_path_uses_it() {
+        let content = "1:name=systemd:/system.slice/old.service\n0::/system.slice/new.service\n";
+        assert_eq!(parse_cgroup_path(content), "/system.slice/new.service");
+    }
+
+    #[test]
+    fn lxc_on_v1_becomes_a_payload_path() {
+        let content = "1:name=systemd:/lxc/box/init.scope\n";
+        assert_eq!(parse_cgroup_path(content), "/lxc.payload.box");
+    }
+
+    #[test]
+    fn nothing_useful_is_the_root() {
+        assert_eq!(parse_cgroup_path(""), "/");
+        assert_eq!(parse_cgroup_path("garbage\n2:pids:/\n"), "/");
+    }
+
+    #[test]
+    fn docker_systemd_driver_uses_the_container_name() {
+        let cgroup = monitor(Some("web-1")).classify(format!("/system.slice/docker-{}.scope", ID));
+        assert_eq!(cgroup.group, "web-1");
+        assert_eq!(cgroup.kind, GroupKind::Container);
+        assert_eq!(cgroup.container_id.as_deref(), Some(ID));
+    }
+
+    #[test]
+    fn docker_cgroupfs_driver_without_a_name_is_the_short_id() {
+        let cgroup = monitor(None).classify(format!("/docker/{}", ID));
+        assert_eq!(cgroup.group, &ID[..12]);
+        assert_eq!(cgroup.kind, GroupKind::Container);
+        assert_eq!(cgroup.container_id.as_deref(), Some(ID));
+    }
+
+    #[test]
+    fn rootless_podman_under_the_user_service() {
+        let path = format!("/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{}.scope/container", ID);
+        let cgroup = monitor(None).classify(path);
+        assert_eq!(cgroup.group, &ID[..12]);
+        assert_eq!(cgroup.kind, GroupKind::Container);
+        assert_eq!(cgroup.container_id.as_deref(), Some(ID));
+    }
+
+    #[test]
+    fn kubernetes_container_wins_over_its_pod_slice() {
+        let path = format!("/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1234.slice/cri-containerd-{}.scope", ID);
+        let cgroup = monitor(None).classify(path);
+        assert_eq!(cgroup.group, &ID[..12]);
+        assert_eq!(cgroup.kind, GroupKind::Container);
+
+        let cgroupfs = monitor(None).classify(format!("/kubepods/burstable/pod1234/{}", ID));
+        assert_eq!(cgroupfs.kind, GroupKind::Container);
+        assert_eq!(cgroupfs.container_id.as_deref(), Some(ID));
+    }
+
+    #[test]
+    fn lxc_payload_is_named_and_has_no_id() {
+        let cgroup = monitor(None).classify("/lxc.payload.box/system.slice/sshd.service".to_string());
+        assert_eq!(cgroup.group, "box");
+        assert_eq!(cgroup.kind, GroupKind::Container);
+        assert_eq!(cgroup.container_id, None);
+    }
+
+    #[test]
+    fn systemd_units_innermost_first() {
+        let mut monitor = monitor(None);
+        let service = monitor.classify("/system.slice/nginx.service".to_string());
+        assert_eq!((service.group.as_str(), service.kind), ("nginx.service", GroupKind::Service));
+
+        let scope = monitor.classify("/user.slice/user-1000.slice/session-2.scope".to_string());
+        assert_eq!((scope.group.as_str(), scope.kind), ("session-2.scope", GroupKind::Scope));
+
+        let slice = monitor.classify("/machine.slice".to_string());
+        assert_eq!((slice.group.as_str(), slice.kind), ("machine.slice", GroupKind::Slice));
+
+        let other = monitor.classify("/".to_string());
+        assert_eq!((other.group.as_str(), other.kind), ("/", GroupKind::Other));
+    }
+
+    #[test]
+    fn not_quite_a_container_id() {
+        let mut monitor = monitor(None);
+        // too short, and not hex
+        let short = monitor.classify("/system.slice/docker-abc123.scope".to_string());
+        assert_eq!(short.kind, GroupKind::Scope);
+        let not_hex = monitor.classify(format!("/docker/{}", "z".repeat(64)));
+        assert_eq!(not_hex.kind, GroupKind::Other);
+        assert_eq!(not_hex.container_id, None);
+    }
+}
@@ -0,0 +1,380 @@
+use std::cmp::Ordering;
+use serde::{Serialize, Deserialize};
+use sysinfo::{Process, ProcessStatus, System};
+use crate::cgroups::CgroupMonitor;
+use crate::process_io::ProcessIoUsage;
+use crate::process_net::ProcessNetUsage;
+use crate::process_stat::ProcessStatMonitor;
+use crate::utils::{format_bytes, format_duration, format_local_time, format_rate};
+
+/*
+    the process table columns. which ones show, in what order and how wide lives in UserSettings.columns,
+    every kind is in that list exactly once (hidden ones too) so the `C` panel can just move entries around.
+    any visible column can be the sort key, ←/→ steps through them
+ */
+pub const MIN_WIDTH: u16 = 4;
+pub const MAX_WIDTH: u16 = 80;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ColumnKind {
+    Pid,
+    Ppid,
+    Name,
+    User,
+    State,
+    Nice,
+    Priority,
+    Threads,
+    StartTime,
+    CpuTime,
+    Cpu,
+    Memory,
+    Virtual,
+    Shared,
+    Group,
+    Connections,
+    Rx,
+    Tx,
+    Read,
+    Write,
+    Command,
+}
+
+impl ColumnKind {
+    pub const ALL: [ColumnKind; 21] = [
+        ColumnKind::Pid,
+        ColumnKind::Ppid,
+        ColumnKind::Name,
+        ColumnKind::User,
+        ColumnKind::State,
+        ColumnKind::Nice,
+        ColumnKind::Priority,
+        ColumnKind::Threads,
+        ColumnKind::StartTime,
+        ColumnKind::CpuTime,
+        ColumnKind::Cpu,
+        ColumnKind::Memory,
+        ColumnKind::Virtual,
+        ColumnKind::Shared,
+        ColumnKind::Group,
+        ColumnKind::Connections,
+        ColumnKind::Rx,
+        ColumnKind::Tx,
+        ColumnKind::Read,
+        ColumnKind::Write,
+        ColumnKind::Command,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ColumnKind::Pid => "PID",
+            ColumnKind::Ppid => "PPID",
+            ColumnKind::Name => "Name",
+            ColumnKind::User => "User",
+            ColumnKind::State => "S",
+            ColumnKind::Nice => "NI",
+            ColumnKind::Priority => "PRI",
+            ColumnKind::Threads => "Thr",
+            ColumnKind::StartTime => "Started",
+            ColumnKind::CpuTime => "CPU Time",
+            ColumnKind::Cpu => "CPU",
+            ColumnKind::Memory => "Memory",
+            ColumnKind::Virtual => "Virt",
+            ColumnKind::Shared => "Shr",
+            ColumnKind::Group => "Group",
+            ColumnKind::Connections => "Conns",
+            ColumnKind::Rx => "RX",
+            ColumnKind::Tx => "TX",
+            ColumnKind::Read => "Read",
+            ColumnKind::Write => "Write",
+            ColumnKind::Command => "Command",
+        }
+    }
+
+    // longer name for the column panel and the table title
+    pub fn description(&self) -> &'static str {
+        match self {
+            ColumnKind::Pid => "Process ID",
+            ColumnKind::Ppid => "Parent PID",
+            ColumnKind::Name => "Name",
+            ColumnKind::User => "User",
+            ColumnKind::State => "State (R, S, D, T, Z...)",
+            ColumnKind::Nice => "Nice",
+            ColumnKind::Priority => "Priority",
+            ColumnKind::Threads => "Threads",
+            ColumnKind::StartTime => "Start Time",
+            ColumnKind::CpuTime => "CPU Time",
+            ColumnKind::Cpu => "CPU (per Core %)",
+            ColumnKind::Memory => "Memory (resident)",
+            ColumnKind::Virtual => "Virtual Memory",
+            ColumnKind::Shared => "Shared Memory",
+            ColumnKind::Group => "Container / Cgroup",
+            ColumnKind::Connections => "Connections",
+            ColumnKind::Rx => "Network RX",
+            ColumnKind::Tx => "Network TX",
+            ColumnKind::Read => "Disk Read",
+            ColumnKind::Write => "Disk Write",
+            ColumnKind::Command => "Command Line",
+        }
+    }
+
+    pub fn default_width(&self) -> u16 {
+        match self {
+            ColumnKind::Pid | ColumnKind::Ppid => 10,
+            ColumnKind::Name => 30,
+            ColumnKind::User => 10,
+            ColumnKind::State => 3,
+            ColumnKind::Nice | ColumnKind::Priority => 4,
+            ColumnKind::Threads => 5,
+            ColumnKind::StartTime => 10,
+            ColumnKind::CpuTime => 9,
+            ColumnKind::Cpu => 10,
+            ColumnKind::Memory => 14,
+            ColumnKind::Virtual | ColumnKind::Shared => 11,
+            ColumnKind::Group => 24,
+            ColumnKind::Connections => 6,
+            ColumnKind::Rx | ColumnKind::Tx | ColumnKind::Read | ColumnKind::Write => 13,
+            ColumnKind::Command => 40,
+        }
+    }
+
+    // the text columns stretch to fill the row, their width is the minimum
+    pub fn flexible(&self) -> bool {
+        matches!(self, ColumnKind::Name | ColumnKind::Group | ColumnKind::Command)
+    }
+
+    // the direction a column starts out sorted in: the busiest first for usage, a-z for text, the lowest for ids and priorities
+    pub fn descending(&self) -> bool {
+        !matches!(
+            self,
+            ColumnKind::Pid | ColumnKind::Ppid | ColumnKind::Name | ColumnKind::User | ColumnKind::State
+                | ColumnKind::Nice | ColumnKind::Priority | ColumnKind::StartTime | ColumnKind::Group | ColumnKind::Command
+        )
+    }
+
+    // the columns filled from /proc/<pid>/stat and statm (see process_stat.rs)
+    pub fn needs_proc_stat(&self) -> bool {
+        matches!(self, ColumnKind::State | ColumnKind::Nice | ColumnKind::Priority | ColumnKind::Shared)
+    }
+
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ColumnSetting {
+    pub kind: ColumnKind,
+    pub width: u16,
+    pub visible: bool,
+}
+
+// the table as it looked before columns were configurable, everything new starts hidden
+pub fn default_columns() -> Vec<ColumnSetting> {
+    let visible = [
+        ColumnKind::Pid,
+        ColumnKind::Name,
+        ColumnKind::Group,
+        ColumnKind::Cpu,
+        ColumnKind::Memory,
+        ColumnKind::Connections,
+        ColumnKind::Rx,
+        ColumnKind::Tx,
+        ColumnKind::Read,
+        ColumnKind::Write,
+    ];
+    let mut columns: Vec<ColumnSetting> = visible
+        .iter()
+        .map(|kind| ColumnSetting { kind: *kind, width: kind.default_width(), visible: true })
+        .collect();
+    normalize_columns(&mut columns);
+    columns
+}
+
+// fix up a hand edited or older config: no duplicates, every kind present, sane widths, something to show
+pub fn normalize_columns(columns: &mut Vec<ColumnSetting>) {
+    let mut seen = Vec::new();
+    columns.retain(|column| {
+        let first = !seen.contains(&column.kind);
+        seen.push(column.kind);
+        first
+    });
+    for kind in ColumnKind::ALL {
+        if !columns.iter().any(|c| c.kind == kind) {
+            columns.push(ColumnSetting { kind, width: kind.default_width(), visible: false });
+        }
+    }
+    for column in columns.iter_mut() {
+        column.width = column.width.clamp(MIN_WIDTH.min(column.kind.default_width()), MAX_WIDTH);
+    }
+    let nothing_shown = !columns.iter().any(|c| c.visible);
+    if let Some(pid) = columns.iter_mut().find(|c| c.kind == ColumnKind::Pid && nothing_shown) {
+        pid.visible = true;
+    }
+}
+
+// what breaks ties in the sort column, so equal rows keep the same order from one refresh to the next
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecondaryKey {
+    Name,
+    Pid,
+}
+
+impl SecondaryKey {
+    pub fn toggle(&self) -> Self {
+        match self {
+            SecondaryKey::Name => SecondaryKey::Pid,
+            SecondaryKey::Pid => SecondaryKey::Name,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecondaryKey::Name => "name",
+            SecondaryKey::Pid => "PID",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOrder {
+    pub column: ColumnKind,
+    pub descending: bool,
+    pub secondary: SecondaryKey,
+}
+
+pub enum SortValue {
+    Number(f64),
+    Text(String),
+}
+
+impl SortValue {
+    pub fn cmp(&self, other: &SortValue) -> Ordering {
+        match (self, other) {
+            (SortValue::Number(a), SortValue::Number(b)) => a.total_cmp(b),
+            (SortValue::Text(a), SortValue::Text(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (SortValue::Number(_), SortValue::Text(_)) => Ordering::Less,
+            (SortValue::Text(_), SortValue::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+// everything a cell needs besides the process itself
+pub struct ColumnContext<'a> {
+    pub num_cores: f32,
+    pub net_usage: &'a ProcessNetUsage,
+    pub io_usage: &'a ProcessIoUsage,
+    pub stats: &'a ProcessStatMonitor,
+    pub cgroups: &'a CgroupMonitor,
+}
+
+impl<'a> ColumnContext<'a> {
+    pub fn new(
+        system: &System,
+        net_usage: &'a ProcessNetUsage,
+        io_usage: &'a ProcessIoUsage,
+        stats: &'a ProcessStatMonitor,
+        cgroups: &'a CgroupMonitor,
+    ) -> Self {
+        Self { num_cores: system.cpus().len().max(1) as f32, net_usage, io_usage, stats, cgroups }
+    }
+
+    pub fn cell(&self, kind: ColumnKind, process: &Process) -> String {
+        let pid = process.pid().as_u32();
+        match kind {
+            ColumnKind::Pid => pid.to_string(),
+            ColumnKind::Ppid => process.parent().map_or("-".to_string(), |p| p.to_string()),
+            ColumnKind::Name => process.name().to_string_lossy().into_owned(),
+            ColumnKind::User => process.user_id().map_or("-".to_string(), |uid| self.stats.user_name(**uid)),
+            ColumnKind::State => self.state(process).to_string(),
+            ColumnKind::Nice => self.stats.get(pid).map_or("-".to_string(), |s| s.nice.to_string()),
+            ColumnKind::Priority => self.stats.get(pid).map_or("-".to_string(), |s| s.priority.to_string()),
+            ColumnKind::Threads => thread_count(process).to_string(),
+            ColumnKind::StartTime => format_start_time(process.start_time(), process.run_time()),
+            ColumnKind::CpuTime => format_duration(process.accumulated_cpu_time() / 1000),
+            ColumnKind::Cpu => format!("{:.2}%", process.cpu_usage() / self.num_cores),
+            ColumnKind::Memory => format!("{:.2} MB", process.memory() as f64 / 1024.0 / 1024.0),
+            ColumnKind::Virtual => format_bytes(process.virtual_memory()),
+            ColumnKind::Shared => self.stats.get(pid).map_or("-".to_string(), |s| format_bytes(s.shared)),
+            ColumnKind::Group => self.cgroups.get(pid).map_or("-".to_string(), |cgroup| cgroup.group.clone()),
+            // "-" for processes without inet sockets, n/a when the byte counters aren't available
+            ColumnKind::Connections => self.net_usage.get(pid).map_or("-".to_string(), |s| s.connections().to_string()),
+            ColumnKind::Rx | ColumnKind::Tx => match self.net_usage.get(pid) {
+                Some(stats) if self.net_usage.byte_counters_available => {
+                    format_rate(if kind == ColumnKind::Rx { stats.rx_rate } else { stats.tx_rate })
+                }
+                Some(_) => "n/a".to_string(),
+                None => "-".to_string(),
+            },
+            ColumnKind::Read | ColumnKind::Write => match self.io_usage.get(pid) {
+                Some(io) => format_rate(if kind == ColumnKind::Read { io.read_rate } else { io.write_rate }),
+                None => "-".to_string(),
+            },
+            ColumnKind::Command => {
+                let cmd: Vec<String> = process.cmd().iter().map(|s| s.to_string_lossy().into_owned()).collect();
+                if cmd.is_empty() {
+                    // kernel threads have no command line, ps shows them bracketed
+                    format!("[{}]", process.name().to_string_lossy())
+                } else {
+                    cmd.join(" ")
+                }
+            }
+        }
+    }
+
+    pub fn sort_value(&self, kind: ColumnKind, process: &Process) -> SortValue {
+        let pid = process.pid().as_u32();
+        let stat = self.stats.get(pid);
+        match kind {
+            ColumnKind::Pid => SortValue::Number(pid as f64),
+            ColumnKind::Ppid => SortValue::Number(process.parent().map_or(0.0, |p| p.as_u32() as f64)),
+            ColumnKind::Nice => SortValue::Number(stat.map_or(0.0, |s| s.nice as f64)),
+            ColumnKind::Priority => SortValue::Number(stat.map_or(0.0, |s| s.priority as f64)),
+            ColumnKind::Threads => SortValue::Number(thread_count(process) as f64),
+            ColumnKind::StartTime => SortValue::Number(process.start_time() as f64),
+            ColumnKind::CpuTime => SortValue::Number(process.accumulated_cpu_time() as f64),
+            ColumnKind::Cpu => SortValue::Number((process.cpu_usage() / self.num_cores) as f64),
+            ColumnKind::Memory => SortValue::Number(process.memory() as f64),
+            ColumnKind::Virtual => SortValue::Number(process.virtual_memory() as f64),
+            ColumnKind::Shared => SortValue::Number(stat.map_or(0.0, |s| s.shared as f64)),
+            ColumnKind::Connections => SortValue::Number(self.net_usage.get(pid).map_or(0.0, |s| s.connections() as f64)),
+            // bytes/s first, idle processes with open sockets still rank above ones without any
+            ColumnKind::Rx => SortValue::Number(self.net_usage.get(pid).map_or(0.0, |s| s.rx_rate + s.connections() as f64 / 1_000_000.0)),
+            ColumnKind::Tx => SortValue::Number(self.net_usage.get(pid).map_or(0.0, |s| s.tx_rate + s.connections() as f64 / 1_000_000.0)),
+            ColumnKind::Read => SortValue::Number(self.io_usage.get(pid).map_or(0.0, |io| io.read_rate)),
+            ColumnKind::Write => SortValue::Number(self.io_usage.get(pid).map_or(0.0, |io| io.write_rate)),
+            ColumnKind::Name | ColumnKind::User | ColumnKind::State | ColumnKind::Group | ColumnKind::Command => {
+                SortValue::Text(self.cell(kind, process))
+            }
+        }
+    }
+
+    fn state(&self, process: &Process) -> char {
+        if let Some(stat) = self.stats.get(process.pid().as_u32()) {
+            return stat.state;
+        }
+        match process.status() {
+            ProcessStatus::Run => 'R',
+            ProcessStatus::Sleep => 'S',
+            ProcessStatus::Idle => 'I',
+            ProcessStatus::Stop => 'T',
+            ProcessStatus::Tracing => 't',
+            ProcessStatus::Zombie => 'Z',
+            ProcessStatus::Dead => 'X',
+            ProcessStatus::UninterruptibleDiskSleep => 'D',
+            _ => '?',
+        }
+    }
+}
+
+// the main thread counts, sysinfo only lists the others
+fn thread_count(process: &Process) -> usize {
+    process.tasks().map_or(1, |tasks| tasks.len() + 1)
+}
+
+// clock time for processes started in the last day, the date for older ones
+fn format_start_time(start: u64, run_time: u64) -> String {
+    let full = format_local_time(start);
+    match full.split_once(' ') {
+        Some((_, time)) if run_time < 86400 => time.to_string(),
+        Some((date, _)) => date.to_string(),
+        None => full,
+    }
+}
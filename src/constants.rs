@@ -2,11 +2,6 @@
 
 pub const HISTORY_LEN: usize = 64;
 
-// Global static variables for session tracking
-pub static mut SESSION_TOTAL_BYTES: u64 = 0;
-pub static mut PREV_RX: u64 = 0;
-pub static mut PREV_TX: u64 = 0;
-
 // Custom colors
 /*pub const CUSTOM_PURPLE: Color = Color::Rgb(126, 48, 219);
 pub const CUSTOM_LIGHT_PURPLE: Color = Color::Rgb(137, 125, 219);
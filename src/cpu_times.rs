@@ -0,0 +1,217 @@
+use std::fs;
+use crate::constants::HISTORY_LEN;
+use crate::utils::CircularBuffer;
+
+/*
+    where cpu time goes, from /proc/stat:
+        cpu  user nice system idle iowait irq softirq steal guest guest_nice
+        cpu0 ...
+    all in jiffies since boot, so each refresh diffs against the previous sample.
+    guest time is already counted in user, nice is folded into user and softirq into irq to keep the bars readable.
+    steal is what the hypervisor gave to someone else, iowait is idle time with i/o outstanding
+ */
+#[derive(Debug, Clone, Copy, Default)]
+struct CpuTimes {
+    user: u64,
+    system: u64,
+    idle: u64,
+    iowait: u64,
+    irq: u64,
+    steal: u64,
+}
+
+impl CpuTimes {
+    fn total(&self) -> u64 {
+        self.user + self.system + self.idle + self.iowait + self.irq + self.steal
+    }
+}
+
+// percentages of the interval, idle is whatever is left
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CpuBreakdown {
+    pub user: f64,
+    pub system: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub steal: f64,
+}
+
+impl CpuBreakdown {
+    // iowait isn't busy time, it's listed so it can be stacked but doesn't count towards usage
+    pub fn busy(&self) -> f64 {
+        self.user + self.system + self.irq + self.steal
+    }
+
+    // stacking order used by every bar and graph, bottom/left first
+    pub fn segments(&self) -> [f64; 5] {
+        [self.user, self.system, self.irq, self.steal, self.iowait]
+    }
+}
+
+pub const SEGMENT_LABELS: [&str; 5] = ["usr", "sys", "irq", "steal", "iow"];
+
+pub struct CpuTimeMonitor {
+    prev_total: Option<CpuTimes>,
+    prev_cores: Vec<CpuTimes>,
+    pub total: CpuBreakdown,
+    pub cores: Vec<CpuBreakdown>, // same order as system.cpus()
+    pub history: CircularBuffer<CpuBreakdown>, // all cores combined
+    pub available: bool, // false when /proc/stat isn't there
+}
+
+impl CpuTimeMonitor {
+    pub fn new() -> Self {
+        Self {
+            prev_total: None,
+            prev_cores: Vec::new(),
+            total: CpuBreakdown::default(),
+            cores: Vec::new(),
+            history: CircularBuffer::new(HISTORY_LEN),
+            available: cfg!(target_os = "linux"),
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        let Some((total, cores)) = read_proc_stat() else {
+            self.available = false;
+            return;
+        };
+        self.available = true;
+
+        if let Some(prev) = self.prev_total {
+            self.total = breakdown(&prev, &total);
+            self.history.push(self.total);
+        }
+        // cpus can go offline, only diff the ones we have a previous sample for
+        self.cores = cores.iter()
+            .enumerate()
+            .map(|(i, now)| self.prev_cores.get(i).map(|prev| breakdown(prev, now)).unwrap_or_default())
+            .collect();
+
+        self.prev_total = Some(total);
+        self.prev_cores = cores;
+    }
+}
+
+fn breakdown(prev: &CpuTimes, now: &CpuTimes) -> CpuBreakdown {
+    let elapsed = now.total().saturating_sub(prev.total());
+    if elapsed == 0 {
+        return CpuBreakdown::default();
+    }
+    let percent = |now: u64, prev: u64| now.saturating_sub(prev) as f64 * 100.0 / elapsed as f64;
+    CpuBreakdown {
+        user: percent(now.user, prev.user),
+        system: percent(now.system, prev.system),
+        iowait: percent(now.iowait, prev.iowait),
+        irq: percent(now.irq, prev.irq),
+        steal: percent(now.steal, prev.steal),
+    }
+}
+
+// (all cpus, per core in cpuN order)
+fn read_proc_stat() -> Option<(CpuTimes, Vec<CpuTimes>)> {
+    parse_proc_stat(&fs::read_to_string("/proc/stat").ok()?)
+}
+
+fn parse_proc_stat(content: &str) -> Option<(CpuTimes, Vec<CpuTimes>)> {
+    let mut total = None;
+    let mut cores = Vec::new();
+
+    for line in content.lines().take_while(|line| line.starts_with("cpu")) {
+        let mut fields = line.split_whitespace();
+        let name = fields.next()?;
+        let values: Vec<u64> = fields.map(|v| v.parse().unwrap_or(0)).collect();
+        let value = |i: usize| values.get(i).copied().unwrap_or(0);
+        let times = CpuTimes {
+            user: value(0) + value(1),
+            system: value(2),
+            idle: value(3),
+            iowait: value(4),
+            irq: value(5) + value(6),
+            steal: value(7),
+        };
+        if name == "cpu" {
+            total = Some(times);
+        } else {
+            cores.push(times);
+        }
+    }
+
+    Some((total?, cores))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAT: &str = "\
+cpu  10132153 290696 3084719 46828483 16683 0 25195 0 175628 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 23933 0
+cpu1 1335 20 540 88
+intr 114930548 113199788 3 0 5 263 0 4 [...]
+cpu9 1 1 1 1 1 1 1 1 1 1
+";
+
+    #[test]
+    fn parses_cpu_lines() {
+        let (total, cores) = parse_proc_stat(STAT).unwrap();
+        // nice folds into user, softirq into irq
+        assert_eq!(total.user, 10132153 + 290696);
+        assert_eq!(total.system, 3084719);
+        assert_eq!(total.idle, 46828483);
+        assert_eq!(total.iowait, 16683);
+        assert_eq!(total.irq, 25195);
+        assert_eq!(total.steal, 0);
+
+        // stops at the first non-cpu line
+        assert_eq!(cores.len(), 2);
+        // 2.4 kernels stop after idle, the missing columns are 0
+        assert_eq!(cores[1].user, 1355);
+        assert_eq!(cores[1].idle, 88);
+        assert_eq!(cores[1].iowait, 0);
+        assert_eq!(cores[1].steal, 0);
+    }
+
+    #[test]
+    fn needs_the_total_line() {
+        assert!(parse_proc_stat("cpu0 1 2 3 4\n").is_none());
+        assert!(parse_proc_stat("").is_none());
+    }
+
+    fn times(user: u64, system: u64, idle: u64, iowait: u64, irq: u64, steal: u64) -> CpuTimes {
+        CpuTimes { user, system, idle, iowait, irq, steal }
+    }
+
+    #[test]
+    fn breakdown_of_an_interval() {
+        let prev = times(100, 50, 800, 10, 5, 0);
+        let now = times(130, 60, 845, 15, 10, 5); // 100 jiffies later
+        let b = breakdown(&prev, &now);
+        assert_eq!(b.user, 30.0);
+        assert_eq!(b.system, 10.0);
+        assert_eq!(b.iowait, 5.0);
+        assert_eq!(b.irq, 5.0);
+        assert_eq!(b.steal, 5.0);
+        assert_eq!(b.busy(), 50.0);
+    }
+
+    #[test]
+    fn no_time_passed() {
+        let sample = times(1, 2, 3, 4, 5, 6);
+        assert_eq!(breakdown(&sample, &sample).busy(), 0.0);
+    }
+
+    #[test]
+    fn counters_going_backwards() {
+        // iowait is known to go backwards on some kernels, and a wrapped counter looks the same. neither may go negative
+        let prev = times(100, 50, 800, 40, 5, 0);
+        let now = times(150, 50, 850, 30, 5, 0);
+        let b = breakdown(&prev, &now);
+        assert_eq!(b.iowait, 0.0);
+        assert!(b.user > 0.0 && b.user <= 100.0);
+
+        // everything wrapped at once (the counters reset): nothing elapsed, nothing reported
+        let after_reset = times(1, 1, 1, 1, 1, 0);
+        assert_eq!(breakdown(&prev, &after_reset).busy(), 0.0);
+    }
+}
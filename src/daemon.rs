@@ -1,10 +1,18 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, FileTypeExt, MetadataExt};
+use std::os::unix::process::CommandExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
 use sysinfo::{System, Process, Pid};
+use crate::notify::{Notifier, NotifyConfig, NotifyEvent, ServiceEvent};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServiceConfig {
@@ -17,6 +25,18 @@ pub struct ServiceConfig {
     pub max_restarts: u32,
     pub restart_delay_secs: u64, 
     pub health_check: Option<HealthCheckConfig>, 
+    #[serde(default)]
+    pub groups: Vec<String>, // e.g. ["web", "batch"], used for bulk start/stop/restart
+    #[serde(default = "default_autostart")]
+    pub autostart: bool, // false = defined but only started on request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<NotifyConfig>, // overrides the global [notify] table for this service
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>, // run as this user, needs the daemon to run as root
+}
+
+fn default_autostart() -> bool {
+    true
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,14 +84,19 @@ pub struct ServiceState {
     pub health_failures: u32,
     pub restart_policy: RestartPolicy,
     pub health_check: Option<HealthCheck>,
+    pub started_at: Option<Instant>,
+    pub last_health_ok: Option<bool>, // None until the first health check has run
+    pub recent_restarts: Vec<Instant>, // for crash loop detection, trimmed to the notify window
 }
 
 #[derive(Serialize, Deserialize)]
 struct ConfigWrapper {
     services: Vec<ServiceConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    notify: Option<NotifyConfig>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ServiceStatus {
     Running,
     Stopped,
@@ -81,25 +106,194 @@ pub enum ServiceStatus {
     Unhealthy,
 }
 
+impl ServiceStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceStatus::Running => "Running",
+            ServiceStatus::Stopped => "Stopped",
+            ServiceStatus::Failed => "Failed",
+            ServiceStatus::Starting => "Starting",
+            ServiceStatus::Stopping => "Stopping",
+            ServiceStatus::Unhealthy => "Unhealthy",
+        }
+    }
+}
+
+// point-in-time view of a service, what the TUI service manager renders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceSnapshot {
+    pub name: String,
+    pub status: ServiceStatus,
+    pub pid: Option<u32>,
+    pub uptime_secs: Option<u64>,
+    pub restart_count: u32,
+    pub last_health_ok: Option<bool>,
+    pub health_failures: u32,
+    pub groups: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ServiceAction {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl ServiceAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ServiceAction::Start => "start",
+            ServiceAction::Stop => "stop",
+            ServiceAction::Restart => "restart",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "start" => Some(ServiceAction::Start),
+            "stop" => Some(ServiceAction::Stop),
+            "restart" => Some(ServiceAction::Restart),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlTarget {
+    Service(String),
+    Group(String),
+}
+
+// a single request for the supervisor, shared by the CLI, the control socket and the TUI
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaemonCommand {
+    pub action: ServiceAction,
+    pub target: ControlTarget,
+}
+
+impl DaemonCommand {
+    // wire format is one line: "<action> <service|group> <name>"
+    pub fn to_line(&self) -> String {
+        match &self.target {
+            ControlTarget::Service(name) => format!("{} service {}", self.action.as_str(), name),
+            ControlTarget::Group(name) => format!("{} group {}", self.action.as_str(), name),
+        }
+    }
+
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut parts = line.trim().splitn(3, ' ');
+        let action = parts.next().and_then(ServiceAction::parse)
+            .ok_or_else(|| format!("Unknown action in '{}'", line.trim()))?;
+        let kind = parts.next().unwrap_or("");
+        let name = parts.next().map(str::trim).filter(|n| !n.is_empty())
+            .ok_or_else(|| format!("Missing name in '{}'", line.trim()))?
+            .to_string();
+
+        let target = match kind {
+            "service" => ControlTarget::Service(name),
+            "group" => ControlTarget::Group(name),
+            other => return Err(format!("Unknown target '{}', expected service or group", other)),
+        };
+
+        Ok(Self { action, target })
+    }
+}
+
+fn services_path(dir_name: &str) -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("/etc"))
+        .join(dir_name)
+        .join("services.toml")
+}
+
+pub fn default_config_path() -> PathBuf {
+    let path = services_path("r-top");
+    let legacy = services_path("b-top");
+    // the copy below didn't happen (read-only config dir...), keep using the old file rather than starting empty
+    if !path.exists() && legacy.exists() { legacy } else { path }
+}
+
+// the config dir was called b-top before the rename. copy services.toml over once, the old file is left alone
+pub fn migrate_legacy_config() {
+    let path = services_path("r-top");
+    let legacy = services_path("b-top");
+    if path.exists() || !legacy.exists() {
+        return;
+    }
+    let copied = path.parent().map_or(Ok(()), fs::create_dir_all).and_then(|_| fs::copy(&legacy, &path));
+    match copied {
+        Ok(_) => eprintln!("Copied {} to {} (the config dir is r-top now)", legacy.display(), path.display()),
+        Err(e) => eprintln!("Still using {}, couldn't copy it to {}: {}", legacy.display(), path.display(), e),
+    }
+}
+
+pub fn read_service_configs(path: &Path) -> Result<Vec<ServiceConfig>, Box<dyn std::error::Error>> {
+    Ok(read_config_wrapper(path)?.services)
+}
+
+fn read_config_wrapper(path: &Path) -> Result<ConfigWrapper, Box<dyn std::error::Error>> {
+    let config_content = fs::read_to_string(path)?;
+
+    // parse as a wrapper struct to handle the array properly
+    let wrapper: ConfigWrapper = toml::from_str(&config_content)?;
+    Ok(wrapper)
+}
+
+/*
+    appends services to a config file as new [[services]] blocks instead of rewriting it, so existing comments survive.
+    refuses names that are already taken
+ */
+pub fn append_service_configs(path: &Path, services: Vec<ServiceConfig>) -> Result<(), Box<dyn std::error::Error>> {
+    if path.exists() {
+        let existing = read_service_configs(path)?;
+        let taken: Vec<&str> = services.iter()
+            .filter(|s| existing.iter().any(|e| e.name == s.name))
+            .map(|s| s.name.as_str())
+            .collect();
+        if !taken.is_empty() {
+            return Err(format!("{} already defines: {}", path.display(), taken.join(", ")).into());
+        }
+    } else if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    write!(file, "\n{}", services_to_toml(services)?)?;
+    Ok(())
+}
+
+pub fn services_to_toml(services: Vec<ServiceConfig>) -> Result<String, toml::ser::Error> {
+    toml::to_string_pretty(&ConfigWrapper { services, notify: None })
+}
+
+// uid/gid for ServiceConfig.user
+fn resolve_user(name: &str) -> Result<(u32, u32), String> {
+    let c_name = std::ffi::CString::new(name).map_err(|_| format!("Invalid user name '{}'", name))?;
+    let passwd = unsafe { libc::getpwnam(c_name.as_ptr()) };
+    if passwd.is_null() {
+        return Err(format!("Unknown user '{}'", name));
+    }
+    unsafe { Ok(((*passwd).pw_uid, (*passwd).pw_gid)) }
+}
+
 pub struct DaemonSupervisor {
     pub services: HashMap<String, ServiceState>,
     config_path: PathBuf,
     system: System,
+    children: HashMap<String, Child>, // kept so exited services get reaped instead of lingering as zombies
+    notifier: Notifier,
 }
 
 impl DaemonSupervisor {
     pub fn new(config_path: Option<PathBuf>) -> Self {
-        let config_path = config_path.unwrap_or_else(|| {
-            dirs::config_dir()
-                .unwrap_or_else(|| PathBuf::from("/etc"))
-                .join("b-top")
-                .join("services.toml")
-        });
+        let config_path = config_path.unwrap_or_else(default_config_path);
 
         Self {
             services: HashMap::new(),
             config_path,
             system: System::new_all(),
+            children: HashMap::new(),
+            notifier: Notifier::new(None),
         }
     }
 
@@ -112,13 +306,12 @@ impl DaemonSupervisor {
             return Ok(());
         }
 
-        let config_content = fs::read_to_string(&self.config_path)?;
-        
-        // parse as a wrapper struct to handle the array properly     
-        let wrapper: ConfigWrapper = toml::from_str(&config_content)?;
-        let configs = wrapper.services;
+        let wrapper = read_config_wrapper(&self.config_path)?;
+        let verbose = self.notifier.verbose;
+        self.notifier = Notifier::new(wrapper.notify);
+        self.notifier.verbose = verbose;
 
-        for config in configs {
+        for config in wrapper.services {
             // convert from TOML-friendly format to internal format
             let restart_policy = RestartPolicy::from_str(&config.restart_policy);
             let health_check = config.health_check.as_ref().map(|hc| HealthCheck {
@@ -138,6 +331,9 @@ impl DaemonSupervisor {
                 health_failures: 0,
                 restart_policy,
                 health_check,
+                started_at: None,
+                last_health_ok: None,
+                recent_restarts: Vec::new(),
             };
             self.services.insert(config.name.clone(), state);
         }
@@ -162,6 +358,10 @@ impl DaemonSupervisor {
                     timeout_secs: 5,
                     retries: 1,
                 }),
+                groups: vec!["monitoring".to_string()],
+                autostart: true,
+                notify: None,
+                user: None,
             },
             ServiceConfig {
                 name: "stats-collector".to_string(),
@@ -178,6 +378,10 @@ impl DaemonSupervisor {
                     timeout_secs: 5,
                     retries: 2,
                 }),
+                groups: vec!["monitoring".to_string()],
+                autostart: true,
+                notify: None,
+                user: None,
             },
             ServiceConfig {
                 name: "network-monitor".to_string(),
@@ -194,12 +398,17 @@ impl DaemonSupervisor {
                     timeout_secs: 5,
                     retries: 1,
                 }),
+                groups: vec!["monitoring".to_string()],
+                autostart: true,
+                notify: None,
+                user: None,
             },
         ];
 
 
         let wrapper = ConfigWrapper {
             services: default_services,
+            notify: None,
         };
 
         let toml_content = toml::to_string_pretty(&wrapper)?;
@@ -235,9 +444,16 @@ impl DaemonSupervisor {
                 cmd.env(key, value);
             }
 
+            if let Some(user) = &service.config.user {
+                let (uid, gid) = resolve_user(user)?;
+                cmd.uid(uid).gid(gid);
+            }
+
             let child = cmd.spawn()?;
             service.pid = Some(child.id());
             service.status = ServiceStatus::Running;
+            service.started_at = Some(Instant::now());
+            self.children.insert(name.to_string(), child);
 
             println!("Started service '{}', PID: {}", name, service.pid.unwrap());
             Ok(())
@@ -263,9 +479,13 @@ impl DaemonSupervisor {
                     libc::kill(pid as i32, libc::SIGKILL);
                 }
             }
+            if let Some(mut child) = self.children.remove(name) {
+                let _ = child.wait();
+            }
 
             service.pid = None;
             service.status = ServiceStatus::Stopped;
+            service.started_at = None;
             println!("Stopped service '{}'", name);
 
         }
@@ -281,17 +501,13 @@ impl DaemonSupervisor {
 
         let mut restart_candidates: Vec<String> = Vec::new();
 
-        for (name, service) in self.services.iter_mut() {
-            if let Some(pid) = service.pid {
-                if self.system.process(sysinfo::Pid::from(pid as usize)).is_none() {
-                    println!("Service '{}' (PID: {}) is not running", name, pid);
-                    service.pid = None;
-                    service.status = ServiceStatus::Failed;
-
-                    restart_candidates.push(name.clone());
-                }
-            }
+        for (name, pid, reason) in self.collect_exited() {
+            println!("Service '{}' (PID: {}) is not running", name, pid);
+            self.emit_event(&name, NotifyEvent::Failed, Some(pid), reason);
+            restart_candidates.push(name);
+        }
 
+        for (name, service) in self.services.iter_mut() {
             if let Some(health_check) = &service.health_check {
                 if service.status == ServiceStatus::Running {
                     let should_check = service.last_health_check
@@ -306,14 +522,18 @@ impl DaemonSupervisor {
         }
 
         // check restart policy outside the mutable borrow
+        let mut gave_up: Vec<String> = Vec::new();
         for name in restart_candidates {
             if let Some(service) = self.services.get(&name) {
                 if self.should_restart(service) {
                     println!("Restarting service '{}'", name);
                     to_restart.push(name.clone());
+                } else if Self::restarts_exhausted(service) {
+                    gave_up.push(name.clone());
                 }
             }
         }
+        self.notify_gave_up(gave_up);
 
         for (name, health_check) in health_checks_to_run {
             self.perform_health_check(&name, &health_check);
@@ -348,9 +568,89 @@ impl DaemonSupervisor {
         service.restart_count += 1;
         service.last_restart = Some(Instant::now());
 
+        self.note_restart(name);
         self.start_service(name)
     }
 
+    fn restarts_exhausted(service: &ServiceState) -> bool {
+        matches!(service.restart_policy, RestartPolicy::OnFailure)
+            && service.status == ServiceStatus::Failed
+            && service.restart_count >= service.config.max_restarts
+    }
+
+    fn notify_gave_up(&mut self, names: Vec<String>) {
+        for name in names {
+            let restarts = self.services.get(&name).map_or(0, |s| s.restart_count);
+            let message = format!("giving up after {} restart(s)", restarts);
+            self.emit_event(&name, NotifyEvent::CrashLoop, None, message);
+        }
+    }
+
+    // a burst of restarts inside the window counts as a crash loop whatever the restart policy is
+    fn note_restart(&mut self, name: &str) {
+        let Some(service) = self.services.get_mut(name) else {
+            return;
+        };
+        let Some(config) = self.notifier.config_for(service.config.notify.as_ref()) else {
+            return;
+        };
+        let threshold = config.crash_loop_restarts as usize;
+        let window = Duration::from_secs(config.crash_loop_window_secs);
+
+        let now = Instant::now();
+        service.recent_restarts.retain(|t| now.duration_since(*t) <= window);
+        service.recent_restarts.push(now);
+
+        if threshold > 0 && service.recent_restarts.len() >= threshold {
+            let message = format!("restarted {} times in the last {}s", service.recent_restarts.len(), window.as_secs());
+            service.recent_restarts.clear();
+            self.emit_event(name, NotifyEvent::CrashLoop, None, message);
+        }
+    }
+
+    /*
+        finds services whose process is gone. our own children are checked with try_wait, which also reaps them,
+        a zombie still shows up in sysinfo so the pid lookup alone would never notice the exit.
+        sysinfo is the fallback for pids we don't hold a handle for
+     */
+    fn collect_exited(&mut self) -> Vec<(String, u32, String)> {
+        let mut exited = Vec::new();
+
+        for (name, service) in self.services.iter_mut() {
+            let Some(pid) = service.pid else {
+                continue;
+            };
+
+            let reason = match self.children.get_mut(name) {
+                Some(child) => match child.try_wait() {
+                    Ok(Some(status)) => Some(format!("process exited ({})", status)),
+                    _ => None,
+                },
+                None if self.system.process(sysinfo::Pid::from(pid as usize)).is_none() => {
+                    Some("process is no longer running".to_string())
+                }
+                None => None,
+            };
+
+            if let Some(reason) = reason {
+                self.children.remove(name);
+                service.pid = None;
+                service.status = ServiceStatus::Failed;
+                exited.push((name.clone(), pid, reason));
+            }
+        }
+
+        exited
+    }
+
+    fn emit_event(&mut self, name: &str, event: NotifyEvent, pid: Option<u32>, message: String) {
+        let Some(service) = self.services.get(name) else {
+            return;
+        };
+        let event = ServiceEvent::new(name, event, pid, service.restart_count, message);
+        self.notifier.notify(event, service.config.notify.as_ref());
+    }
+
     fn perform_health_check(&mut self, name: &str, health_check: &HealthCheck) {
         let output = Command::new("sh")
             .arg("-c")
@@ -362,6 +662,7 @@ impl DaemonSupervisor {
         match output {
             Ok(output) if output.status.success() => {
                 service.health_failures = 0;
+                service.last_health_ok = Some(true);
                 if service.status == ServiceStatus::Unhealthy {
                     service.status = ServiceStatus::Running;
                     println!("Service '{}' is now healthy", name);
@@ -369,9 +670,17 @@ impl DaemonSupervisor {
             }
             _ => {
                 service.health_failures += 1;
+                service.last_health_ok = Some(false);
                 if service.health_failures >= health_check.retries {
+                    let newly_unhealthy = service.status != ServiceStatus::Unhealthy;
                     service.status = ServiceStatus::Unhealthy;
                     println!("Service '{}' is unhealthy, failed health check.", name);
+
+                    if newly_unhealthy {
+                        let pid = service.pid;
+                        let message = format!("health check failed {} time(s): {}", service.health_failures, health_check.command);
+                        self.emit_event(name, NotifyEvent::Unhealthy, pid, message);
+                    }
                 }
             }
         }
@@ -385,6 +694,95 @@ impl DaemonSupervisor {
         self.services.get(name).map(|s| &s.status)
     }
 
+    pub fn snapshot(&self) -> Vec<ServiceSnapshot> {
+        let mut snapshots: Vec<ServiceSnapshot> = self.services.iter()
+            .map(|(name, service)| ServiceSnapshot {
+                name: name.clone(),
+                status: service.status.clone(),
+                pid: service.pid,
+                uptime_secs: service.started_at.map(|t| t.elapsed().as_secs()),
+                restart_count: service.restart_count,
+                last_health_ok: service.last_health_ok,
+                health_failures: service.health_failures,
+                groups: service.config.groups.clone(),
+            })
+            .collect();
+        snapshots.sort_by(|a, b| a.name.cmp(&b.name));
+        snapshots
+    }
+
+    // names of services that should be launched when the daemon starts
+    pub fn autostart_services(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.services.iter()
+            .filter(|(_, service)| service.config.autostart)
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn group_members(&self, group: &str) -> Vec<String> {
+        let mut names: Vec<String> = self.services.iter()
+            .filter(|(_, service)| service.config.groups.iter().any(|g| g == group))
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    pub fn groups(&self) -> Vec<String> {
+        self.services.values()
+            .flat_map(|service| service.config.groups.iter().cloned())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /*
+        runs a command against one service or a whole group, the returned string is a short summary for the caller
+        (CLI, control socket or TUI). uses the silent start/stop so it doesn't scribble over the TUI
+     */
+    pub fn execute(&mut self, command: &DaemonCommand) -> Result<String, String> {
+        let names = match &command.target {
+            ControlTarget::Service(name) => {
+                if !self.services.contains_key(name) {
+                    return Err(format!("Service '{}' not found", name));
+                }
+                vec![name.clone()]
+            }
+            ControlTarget::Group(group) => {
+                let members = self.group_members(group);
+                if members.is_empty() {
+                    return Err(format!("Group '{}' has no services", group));
+                }
+                members
+            }
+        };
+
+        if matches!(command.action, ServiceAction::Stop | ServiceAction::Restart) {
+            self.stop_services_silent(&names);
+        }
+
+        let mut failures = Vec::new();
+        if matches!(command.action, ServiceAction::Start | ServiceAction::Restart) {
+            for name in &names {
+                if let Err(e) = self.start_service_silent(name) {
+                    if let Some(service) = self.services.get_mut(name) {
+                        service.status = ServiceStatus::Failed;
+                    }
+                    self.emit_event(name, NotifyEvent::Failed, None, format!("failed to start: {}", e));
+                    failures.push(format!("{}: {}", name, e));
+                }
+            }
+        }
+
+        if failures.is_empty() {
+            Ok(format!("{} {}", command.action.as_str(), names.join(", ")))
+        } else {
+            Err(format!("Failed to {} {}", command.action.as_str(), failures.join("; ")))
+        }
+    }
+
     // ========================= SILENT SERVICES ============================
     /*
         it is used you melt
@@ -409,39 +807,61 @@ impl DaemonSupervisor {
                 cmd.env(key, value);
             }
 
+            if let Some(user) = &service.config.user {
+                let (uid, gid) = resolve_user(user)?;
+                cmd.uid(uid).gid(gid);
+            }
+
             let child = cmd.spawn()?;
             service.pid = Some(child.id());
             service.status = ServiceStatus::Running;
+            service.started_at = Some(Instant::now());
+            self.children.insert(name.to_string(), child);
 
             Ok(())
         }
     }
 
-    pub fn stop_service_silent(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let service = self.services.get_mut(name)
-            .ok_or(format!("Service '{}' not found", name))?;
+    // SIGTERM everything first and share one grace period, so stopping a group doesn't take 2s per service
+    pub fn stop_services_silent(&mut self, names: &[String]) {
+        let mut stopping: Vec<(String, u32)> = Vec::new();
 
-        if let Some(pid) = service.pid {
-            service.status = ServiceStatus::Stopping;
-
-            unsafe {
-                libc::kill(pid as i32, libc::SIGTERM);
+        for name in names {
+            let Some(service) = self.services.get_mut(name) else {
+                continue;
+            };
+            if let Some(pid) = service.pid {
+                service.status = ServiceStatus::Stopping;
+                unsafe {
+                    libc::kill(pid as i32, libc::SIGTERM);
+                }
+                stopping.push((name.clone(), pid));
             }
+        }
 
-            std::thread::sleep(Duration::from_secs(2)); 
+        if stopping.is_empty() {
+            return;
+        }
 
-            self.system.refresh_all();
+        std::thread::sleep(Duration::from_secs(2));
+
+        self.system.refresh_all();
+        for (name, pid) in stopping {
             if self.system.process(sysinfo::Pid::from(pid as usize)).is_some() {
                 unsafe {
                     libc::kill(pid as i32, libc::SIGKILL);
                 }
             }
+            if let Some(mut child) = self.children.remove(&name) {
+                let _ = child.wait();
+            }
 
-            service.pid = None;
-            service.status = ServiceStatus::Stopped;
+            if let Some(service) = self.services.get_mut(&name) {
+                service.pid = None;
+                service.status = ServiceStatus::Stopped;
+                service.started_at = None;
+            }
         }
-
-        Ok(())
     }
 
     pub fn check_services_silent(&mut self) {
@@ -451,15 +871,12 @@ impl DaemonSupervisor {
         let mut health_checks_to_run: Vec<(String, HealthCheck)> = Vec::new();
         let mut restart_candidates: Vec<String> = Vec::new();
 
-        for (name, service) in self.services.iter_mut() {
-            if let Some(pid) = service.pid {
-                if self.system.process(sysinfo::Pid::from(pid as usize)).is_none() {
-                    service.pid = None;
-                    service.status = ServiceStatus::Failed;
-                    restart_candidates.push(name.clone());
-                }
-            }
+        for (name, pid, reason) in self.collect_exited() {
+            self.emit_event(&name, NotifyEvent::Failed, Some(pid), reason);
+            restart_candidates.push(name);
+        }
 
+        for (name, service) in self.services.iter_mut() {
             if let Some(health_check) = &service.health_check {
                 if service.status == ServiceStatus::Running {
                     let should_check = service.last_health_check
@@ -473,13 +890,17 @@ impl DaemonSupervisor {
             }
         }
 
+        let mut gave_up: Vec<String> = Vec::new();
         for name in restart_candidates {
             if let Some(service) = self.services.get(&name) {
                 if self.should_restart(service) {
                     to_restart.push(name.clone());
+                } else if Self::restarts_exhausted(service) {
+                    gave_up.push(name.clone());
                 }
             }
         }
+        self.notify_gave_up(gave_up);
 
         for (name, health_check) in health_checks_to_run {
             self.perform_health_check_silent(&name, &health_check);
@@ -504,6 +925,7 @@ impl DaemonSupervisor {
         service.restart_count += 1;
         service.last_restart = Some(Instant::now());
 
+        self.note_restart(name);
         self.start_service_silent(name)
     }
 
@@ -518,14 +940,23 @@ impl DaemonSupervisor {
         match output {
             Ok(output) if output.status.success() => {
                 service.health_failures = 0;
+                service.last_health_ok = Some(true);
                 if service.status == ServiceStatus::Unhealthy {
                     service.status = ServiceStatus::Running;
                 }
             }
             _ => {
                 service.health_failures += 1;
+                service.last_health_ok = Some(false);
                 if service.health_failures >= health_check.retries {
+                    let newly_unhealthy = service.status != ServiceStatus::Unhealthy;
                     service.status = ServiceStatus::Unhealthy;
+
+                    if newly_unhealthy {
+                        let pid = service.pid;
+                        let message = format!("health check failed {} time(s): {}", service.health_failures, health_check.command);
+                        self.emit_event(name, NotifyEvent::Unhealthy, pid, message);
+                    }
                 }
             }
         }
@@ -553,8 +984,8 @@ pub fn run_daemon_mode(config_path: Option<PathBuf>) -> Result<(), Box<dyn std::
     let mut supervisor = DaemonSupervisor::new(config_path);
     supervisor.load_config()?;
 
-    let service_names: Vec<String> = supervisor.services.keys().cloned().collect();
-    for name in service_names {
+    // services with autostart = false stay stopped until started by group/name
+    for name in supervisor.autostart_services() {
         if let Err(e) = supervisor.start_service(&name) {
             eprintln!("Failed to start service '{}': {}", name, e);
         }
@@ -590,25 +1021,375 @@ pub fn run_daemon_mode(config_path: Option<PathBuf>) -> Result<(), Box<dyn std::
             }
         }
     }
+    let groups = supervisor.groups();
+    if !groups.is_empty() {
+        println!("\n=== Service Groups ===");
+        for group in &groups {
+            println!("  {} - {}", group, supervisor.group_members(group).join(", "));
+        }
+    }
+
+    let control = match control_socket_path().and_then(|path| ControlServer::bind(&path)) {
+        Ok(server) => {
+            println!("\nControl socket: {:?} (r-top group <start|stop|restart> <name>)", server.path);
+            Some(server)
+        }
+        Err(e) => {
+            eprintln!("Control socket unavailable: {}", e);
+            None
+        }
+    };
     println!("Press Ctrl+C to stop the daemon.");
 
     loop {
         supervisor.check_services();
-        std::thread::sleep(Duration::from_secs(5));
+
+        // poll the control socket between checks so CLI requests don't wait a full cycle
+        for _ in 0..50 {
+            if let Some(server) = &control {
+                server.poll(&mut supervisor);
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
     }
 }
 
-pub fn run_daemon_mode_silent(config_path: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
-    let mut supervisor = DaemonSupervisor::new(config_path);
-    supervisor.load_config()?;
+// ========================= SUPERVISOR HANDLE ============================
+/*
+    integrated mode: the supervisor lives on its own thread and the TUI only ever talks to it through this.
+    commands go down a channel, results come back on another, and the thread publishes a snapshot of every
+    service after each tick so the UI can read state without waiting on the supervisor.
+    dropping the handle (normal exit, panic unwind, whatever) stops the services and joins the thread,
+    so the daemon can't outlive r-top anymore
+ */
+pub enum SupervisorRequest {
+    Command(DaemonCommand),
+    Shutdown,
+}
+
+pub struct SupervisorHandle {
+    requests: Sender<SupervisorRequest>,
+    responses: Receiver<Result<String, String>>,
+    snapshots: Arc<Mutex<Vec<ServiceSnapshot>>>,
+    thread: Option<JoinHandle<()>>,
+}
 
-    let service_names: Vec<String> = supervisor.services.keys().cloned().collect();
-    for name in service_names {
-        let _ = supervisor.start_service_silent(&name); // Ignore errors silently
+impl SupervisorHandle {
+    pub fn spawn(config_path: Option<PathBuf>) -> Result<Self, String> {
+        let mut supervisor = DaemonSupervisor::new(config_path);
+        supervisor.notifier.verbose = false; // the TUI owns the terminal
+        // load on the caller's thread so config errors surface before the TUI takes over the terminal
+        supervisor.load_config().map_err(|e| e.to_string())?;
+
+        let (request_tx, request_rx) = mpsc::channel();
+        let (response_tx, response_rx) = mpsc::channel();
+        let snapshots = Arc::new(Mutex::new(supervisor.snapshot()));
+        let published = snapshots.clone();
+
+        let thread = std::thread::Builder::new()
+            .name("r-top-supervisor".to_string())
+            .spawn(move || run_supervisor_thread(supervisor, request_rx, response_tx, published))
+            .map_err(|e| format!("Failed to spawn supervisor thread: {}", e))?;
+
+        Ok(Self {
+            requests: request_tx,
+            responses: response_rx,
+            snapshots,
+            thread: Some(thread),
+        })
     }
 
-    loop {
+    // fire and forget, the result shows up in try_response()
+    pub fn send(&self, command: DaemonCommand) -> Result<(), String> {
+        self.requests.send(SupervisorRequest::Command(command))
+            .map_err(|_| "Supervisor thread has stopped".to_string())
+    }
+
+    pub fn try_response(&self) -> Option<Result<String, String>> {
+        self.responses.try_recv().ok()
+    }
+
+    pub fn snapshot(&self) -> Vec<ServiceSnapshot> {
+        self.snapshots.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| !t.is_finished())
+    }
+
+    // blocks until every service has been stopped
+    pub fn shutdown(&mut self) {
+        if let Some(thread) = self.thread.take() {
+            let _ = self.requests.send(SupervisorRequest::Shutdown);
+            if thread.join().is_err() {
+                eprintln!("Supervisor thread panicked during shutdown.");
+            }
+        }
+    }
+}
+
+impl Drop for SupervisorHandle {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn run_supervisor_thread(
+    mut supervisor: DaemonSupervisor,
+    requests: Receiver<SupervisorRequest>,
+    responses: Sender<Result<String, String>>,
+    snapshots: Arc<Mutex<Vec<ServiceSnapshot>>>,
+) {
+    for name in supervisor.autostart_services() {
+        let _ = supervisor.start_service_silent(&name);
+    }
+
+    // the CLI can still reach us while the TUI is up
+    let control = control_socket_path().and_then(|path| ControlServer::bind(&path)).ok();
+    let publish = |supervisor: &DaemonSupervisor| {
+        if let Ok(mut published) = snapshots.lock() {
+            *published = supervisor.snapshot();
+        }
+    };
+
+    'supervise: loop {
         supervisor.check_services_silent();
-        std::thread::sleep(Duration::from_secs(5));
+        publish(&supervisor);
+
+        for _ in 0..50 {
+            match requests.recv_timeout(Duration::from_millis(100)) {
+                Ok(SupervisorRequest::Command(command)) => {
+                    let _ = responses.send(supervisor.execute(&command));
+                }
+                // a dropped handle means the TUI is gone, treat it like a shutdown request
+                Ok(SupervisorRequest::Shutdown) | Err(RecvTimeoutError::Disconnected) => break 'supervise,
+                Err(RecvTimeoutError::Timeout) => {}
+            }
+
+            if let Some(server) = &control {
+                server.poll(&mut supervisor);
+            }
+            publish(&supervisor);
+        }
+    }
+
+    let names: Vec<String> = supervisor.services.keys().cloned().collect();
+    supervisor.stop_services_silent(&names);
+    publish(&supervisor);
+}
+
+// ========================= CONTROL SOCKET ============================
+/*
+    lets a second `r-top group ...` / `r-top service ...` invocation (or the TUI) poke a running daemon.
+    one request line in, one response line out ("ok ..." / "err ...").
+    the special request "status" answers with the service snapshots as JSON.
+    the socket lives in $XDG_RUNTIME_DIR, or a 0700 dir of our own under the temp dir, never straight in a
+    shared /tmp where anyone could put their own socket (or a symlink) at a name we'd predictably use
+ */
+pub fn control_socket_path() -> std::io::Result<PathBuf> {
+    let dir = match dirs::runtime_dir() {
+        Some(dir) => dir,
+        None => private_dir(&std::env::temp_dir().join(format!("r-top-{}", current_uid())))?,
+    };
+    Ok(dir.join("r-top-daemon.sock"))
+}
+
+fn current_uid() -> u32 {
+    unsafe { libc::getuid() }
+}
+
+// creates the dir 0700 if it's missing, and refuses one that isn't ours alone
+fn private_dir(dir: &Path) -> std::io::Result<PathBuf> {
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e),
+        _ => {}
+    }
+
+    // symlink_metadata, a symlink someone planted there is not our dir either
+    let meta = fs::symlink_metadata(dir)?;
+    if !meta.is_dir() || meta.uid() != current_uid() || meta.mode() & 0o077 != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{:?} is not a private directory owned by us", dir),
+        ));
+    }
+    Ok(dir.to_path_buf())
+}
+
+// only ever talk to (or delete) a socket that we own
+fn check_socket_owner(path: &Path) -> std::io::Result<()> {
+    let meta = fs::symlink_metadata(path)?;
+    if !meta.file_type().is_socket() || meta.uid() != current_uid() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{:?} is not a socket owned by us, leaving it alone", path),
+        ));
+    }
+    Ok(())
+}
+
+pub struct ControlServer {
+    listener: UnixListener,
+    pub path: PathBuf,
+}
+
+impl ControlServer {
+    pub fn bind(path: &Path) -> std::io::Result<Self> {
+        if fs::symlink_metadata(path).is_ok() {
+            check_socket_owner(path)?;
+            // another daemon is answering, don't steal its socket
+            if UnixStream::connect(path).is_ok() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::AddrInUse,
+                    format!("another daemon is already listening on {:?}", path),
+                ));
+            }
+            // stale socket from a daemon that didn't clean up
+            fs::remove_file(path)?;
+        }
+
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+
+        Ok(Self {
+            listener,
+            path: path.to_path_buf(),
+        })
+    }
+
+    // handle every pending request without blocking, call this from the supervisor loop
+    pub fn poll(&self, supervisor: &mut DaemonSupervisor) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            let _ = Self::handle_client(stream, supervisor);
+        }
     }
-}
\ No newline at end of file
+
+    fn handle_client(stream: UnixStream, supervisor: &mut DaemonSupervisor) -> std::io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+
+        let result = if line.trim() == "status" {
+            serde_json::to_string(&supervisor.snapshot()).map_err(|e| e.to_string())
+        } else {
+            DaemonCommand::parse(&line).and_then(|cmd| supervisor.execute(&cmd))
+        };
+
+        let response = match result {
+            Ok(msg) => format!("ok {}\n", msg),
+            Err(e) => format!("err {}\n", e),
+        };
+
+        (&stream).write_all(response.as_bytes())
+    }
+}
+
+impl Drop for ControlServer {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// client side of the control socket, used by the CLI subcommands and the TUI daemon panel
+pub fn send_control_command(command: &DaemonCommand) -> Result<String, String> {
+    send_control_line(&command.to_line())
+}
+
+pub fn query_service_status() -> Result<Vec<ServiceSnapshot>, String> {
+    let json = send_control_line("status")?;
+    serde_json::from_str(&json).map_err(|e| format!("Bad status response: {}", e))
+}
+
+fn send_control_line(request: &str) -> Result<String, String> {
+    let path = control_socket_path().map_err(|e| format!("No control socket directory: {}", e))?;
+    check_socket_owner(&path)
+        .map_err(|e| format!("No running daemon at {:?} ({}). Start one with `r-top -d`.", path, e))?;
+    let mut stream = UnixStream::connect(&path)
+        .map_err(|e| format!("No running daemon at {:?} ({}). Start one with `r-top -d`.", path, e))?;
+
+    // stopping waits out the grace period, so give it a while
+    stream.set_read_timeout(Some(Duration::from_secs(30)))
+        .map_err(|e| e.to_string())?;
+    stream.write_all(format!("{}\n", request).as_bytes())
+        .map_err(|e| format!("Failed to send command: {}", e))?;
+
+    let mut response = String::new();
+    BufReader::new(&stream).read_line(&mut response)
+        .map_err(|e| format!("Failed to read daemon response: {}", e))?;
+
+    let response = response.trim();
+    if let Some(msg) = response.strip_prefix("ok ") {
+        Ok(msg.to_string())
+    } else if let Some(msg) = response.strip_prefix("err ") {
+        Err(msg.to_string())
+    } else {
+        Err(format!("Unexpected daemon response: '{}'", response))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn scratch(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("r-top-daemon-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn private_dir_is_created_0700() {
+        let root = scratch("create");
+        let dir = private_dir(&root.join("run")).unwrap();
+        assert_eq!(fs::metadata(&dir).unwrap().mode() & 0o777, 0o700);
+        // a second call finds it and is fine with it
+        assert!(private_dir(&dir).is_ok());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn private_dir_refuses_open_modes_and_symlinks() {
+        let root = scratch("refuse");
+        let open = root.join("open");
+        fs::create_dir(&open).unwrap();
+        fs::set_permissions(&open, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(private_dir(&open).is_err());
+
+        let link = root.join("link");
+        std::os::unix::fs::symlink(&open, &link).unwrap();
+        assert!(private_dir(&link).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn only_our_own_sockets_are_trusted() {
+        let root = scratch("socket");
+        let socket = root.join("ok.sock");
+        let _listener = UnixListener::bind(&socket).unwrap();
+        assert!(check_socket_owner(&socket).is_ok());
+
+        // a plain file (or a link to our socket) at the socket path is not something to connect to or delete
+        let file = root.join("file.sock");
+        fs::write(&file, "").unwrap();
+        assert!(check_socket_owner(&file).is_err());
+        let link = root.join("link.sock");
+        std::os::unix::fs::symlink(&socket, &link).unwrap();
+        assert!(check_socket_owner(&link).is_err());
+        assert!(check_socket_owner(&root.join("missing.sock")).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn bind_leaves_foreign_files_alone() {
+        let root = scratch("bind");
+        let path = root.join("r-top-daemon.sock");
+        fs::write(&path, "not a socket").unwrap();
+        assert!(ControlServer::bind(&path).is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "not a socket");
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,286 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+use sysinfo::Disks;
+use crate::constants::HISTORY_LEN;
+use crate::utils::CircularBuffer;
+
+/*
+    per-disk throughput and iops.
+    sysinfo only knows about mounted filesystems, the i/o counters live in /proc/diskstats per block device:
+    - fields after the name: reads completed, reads merged, sectors read, ms reading, writes completed, writes merged, sectors written...
+    - sectors there are always 512 bytes, whatever the real device uses
+    a mount is matched to its device through the st_dev of the mount point, and failing that (btrfs, /dev/root)
+    through the device path with symlinks resolved (/dev/mapper/vg-root -> dm-0)
+ */
+const SECTOR_SIZE: u64 = 512;
+
+// device name -> ((major, minor), counters)
+type DeviceTable = HashMap<String, ((u32, u32), DiskCounters)>;
+
+#[derive(Debug, Clone, Copy, Default)]
+struct DiskCounters {
+    reads: u64,
+    sectors_read: u64,
+    writes: u64,
+    sectors_written: u64,
+}
+
+pub struct DiskStats {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub read_only: bool,
+    pub inodes: Option<(u64, u64)>, // (total, free), None when statvfs fails or the fs has no fixed inode table
+    pub device: Option<String>, // name in /proc/diskstats, None for overlay/tmpfs and friends
+    pub read_rate: f64, // bytes/s
+    pub write_rate: f64,
+    pub read_iops: f64,
+    pub write_iops: f64,
+    pub read_history: CircularBuffer<u64>,
+    pub write_history: CircularBuffer<u64>,
+    pub iops_history: CircularBuffer<u64>,
+}
+
+impl DiskStats {
+    fn new(mount_point: &str) -> Self {
+        Self {
+            name: String::new(),
+            mount_point: mount_point.to_string(),
+            file_system: String::new(),
+            total_space: 0,
+            available_space: 0,
+            read_only: false,
+            inodes: None,
+            device: None,
+            read_rate: 0.0,
+            write_rate: 0.0,
+            read_iops: 0.0,
+            write_iops: 0.0,
+            read_history: CircularBuffer::new(HISTORY_LEN),
+            write_history: CircularBuffer::new(HISTORY_LEN),
+            iops_history: CircularBuffer::new(HISTORY_LEN),
+        }
+    }
+
+    pub fn usage(&self) -> f64 {
+        self.total_space.saturating_sub(self.available_space) as f64 / self.total_space.max(1) as f64
+    }
+
+    pub fn inode_usage(&self) -> Option<f64> {
+        self.inodes
+            .filter(|(total, _)| *total > 0)
+            .map(|(total, free)| total.saturating_sub(free) as f64 / total as f64)
+    }
+}
+
+pub struct DiskMonitor {
+    disks: Vec<DiskStats>, // same order as sysinfo's disk list, keyed by mount point
+    prev_counters: HashMap<String, DiskCounters>, // device -> counters at the last refresh
+    pub counters_available: bool, // false when /proc/diskstats can't be read (not linux)
+}
+
+impl DiskMonitor {
+    pub fn new() -> Self {
+        Self {
+            disks: Vec::new(),
+            prev_counters: HashMap::new(),
+            counters_available: cfg!(target_os = "linux"),
+        }
+    }
+
+    // call right after disks.refresh(), elapsed is the time since the previous refresh (0 for the first fill)
+    pub fn refresh(&mut self, disks: &Disks, elapsed_secs: f64) {
+        let devices = read_diskstats();
+        self.counters_available = devices.is_some();
+        let devices = devices.unwrap_or_default();
+
+        let mut previous: HashMap<String, DiskStats> = self.disks.drain(..).map(|d| (d.mount_point.clone(), d)).collect();
+
+        for disk in disks.list() {
+            let mount_point = disk.mount_point().to_string_lossy().to_string();
+            let mut stats = previous.remove(&mount_point).unwrap_or_else(|| DiskStats::new(&mount_point));
+
+            stats.name = disk.name().to_string_lossy().to_string();
+            stats.file_system = disk.file_system().to_string_lossy().to_string();
+            stats.total_space = disk.total_space();
+            stats.available_space = disk.available_space();
+            stats.read_only = disk.is_read_only();
+            stats.inodes = inode_counts(&mount_point);
+            if stats.device.is_none() {
+                stats.device = resolve_device(&stats.name, &mount_point, &devices);
+            }
+
+            let current = stats.device.as_ref().and_then(|d| devices.get(d)).map(|(_, c)| *c);
+            let previous_counters = stats.device.as_ref().and_then(|d| self.prev_counters.get(d));
+            match (current, previous_counters) {
+                (Some(now), Some(before)) if elapsed_secs > 0.0 => {
+                    (stats.read_rate, stats.write_rate, stats.read_iops, stats.write_iops) = counter_rates(before, &now, elapsed_secs);
+                    stats.read_history.push(stats.read_rate as u64);
+                    stats.write_history.push(stats.write_rate as u64);
+                    stats.iops_history.push((stats.read_iops + stats.write_iops) as u64);
+                }
+                // first sample, nothing to diff against yet
+                _ => {
+                    stats.read_rate = 0.0;
+                    stats.write_rate = 0.0;
+                    stats.read_iops = 0.0;
+                    stats.write_iops = 0.0;
+                }
+            }
+
+            self.disks.push(stats);
+        }
+
+        self.prev_counters = devices.into_iter().map(|(name, (_, counters))| (name, counters)).collect();
+    }
+
+    pub fn list(&self) -> &[DiskStats] {
+        &self.disks
+    }
+
+    pub fn get(&self, index: usize) -> Option<&DiskStats> {
+        self.disks.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.disks.len()
+    }
+}
+
+// (read bytes/s, write bytes/s, read iops, write iops). a counter that went backwards (wrapped on a 32 bit kernel,
+// or the device was re-added) reads as 0 for that sample instead of a huge spike
+fn counter_rates(before: &DiskCounters, now: &DiskCounters, elapsed_secs: f64) -> (f64, f64, f64, f64) {
+    (
+        now.sectors_read.saturating_sub(before.sectors_read) as f64 * SECTOR_SIZE as f64 / elapsed_secs,
+        now.sectors_written.saturating_sub(before.sectors_written) as f64 * SECTOR_SIZE as f64 / elapsed_secs,
+        now.reads.saturating_sub(before.reads) as f64 / elapsed_secs,
+        now.writes.saturating_sub(before.writes) as f64 / elapsed_secs,
+    )
+}
+
+// None when the file isn't there
+fn read_diskstats() -> Option<DeviceTable> {
+    fs::read_to_string("/proc/diskstats").ok().map(|content| parse_diskstats(&content))
+}
+
+fn parse_diskstats(content: &str) -> DeviceTable {
+    let mut devices = HashMap::new();
+
+    for line in content.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() < 10 {
+            continue;
+        }
+        let number = |i: usize| fields[i].parse::<u64>().unwrap_or(0);
+        let counters = DiskCounters {
+            reads: number(3),
+            sectors_read: number(5),
+            writes: number(7),
+            sectors_written: number(9),
+        };
+        let major = fields[0].parse::<u32>().unwrap_or(0);
+        let minor = fields[1].parse::<u32>().unwrap_or(0);
+        devices.insert(fields[2].to_string(), ((major, minor), counters));
+    }
+
+    devices
+}
+
+fn resolve_device(name: &str, mount_point: &str, devices: &DeviceTable) -> Option<String> {
+    // the device number of the mount point is the most reliable link, it also covers /dev/root
+    if let Ok(meta) = fs::metadata(mount_point) {
+        let dev = meta.dev();
+        let wanted = (libc::major(dev), libc::minor(dev));
+        if let Some((device, _)) = devices.iter().find(|(_, (numbers, _))| *numbers == wanted) {
+            return Some(device.clone());
+        }
+    }
+
+    // btrfs hands out anonymous device numbers, fall back to the device node name
+    let path = fs::canonicalize(name).ok()?;
+    let device = path.file_name()?.to_string_lossy().to_string();
+    devices.contains_key(&device).then_some(device)
+}
+
+fn inode_counts(mount_point: &str) -> Option<(u64, u64)> {
+    let path = CString::new(mount_point).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    // btrfs, vfat etc. report 0 inodes since they allocate them on demand
+    if stat.f_files == 0 {
+        return None;
+    }
+    Some((stat.f_files as u64, stat.f_ffree as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 17 counters (5.5+ with discard/flush fields), a 2.6 style partition line with only 4, and the classic 11 with a garbled field
+    const DISKSTATS: &str = "\
+ 259       0 nvme0n1 151842 40561 11253638 33021 289553 204130 22446120 260918 0 181740 303940 0 0 0 0 14583 10001
+ 259       1 nvme0n1p1 392 1024 14380 85 2 0 2 0 0 96 85 0 0 0 0 0 0
+   8      17 sdb1 47 1696 46 368
+ 253       0 dm-0 120 0 4096 12 abc 0 800 4 0 10 16
+";
+
+    #[test]
+    fn parses_diskstats() {
+        let devices = parse_diskstats(DISKSTATS);
+        // too few fields to have write counters
+        assert!(!devices.contains_key("sdb1"));
+        assert_eq!(devices.len(), 3);
+
+        let (numbers, nvme) = devices["nvme0n1"];
+        assert_eq!(numbers, (259, 0));
+        assert_eq!(nvme.reads, 151842);
+        assert_eq!(nvme.sectors_read, 11253638);
+        assert_eq!(nvme.writes, 289553);
+        assert_eq!(nvme.sectors_written, 22446120);
+
+        assert_eq!(devices["nvme0n1p1"].0, (259, 1));
+
+        // an unparsable field counts as 0 rather than dropping the device
+        let (_, dm) = devices["dm-0"];
+        assert_eq!(dm.reads, 120);
+        assert_eq!(dm.writes, 0);
+        assert_eq!(dm.sectors_written, 800);
+    }
+
+    #[test]
+    fn ignores_blank_and_short_lines() {
+        assert!(parse_diskstats("").is_empty());
+        assert!(parse_diskstats("\n   \n 8 0 sda 1 2 3\n").is_empty());
+    }
+
+    #[test]
+    fn rates_from_counter_deltas() {
+        let before = DiskCounters { reads: 100, sectors_read: 2000, writes: 50, sectors_written: 1000 };
+        let now = DiskCounters { reads: 300, sectors_read: 6000, writes: 90, sectors_written: 5000 };
+        let (read_rate, write_rate, read_iops, write_iops) = counter_rates(&before, &now, 2.0);
+        assert_eq!(read_rate, 4000.0 * 512.0 / 2.0);
+        assert_eq!(write_rate, 4000.0 * 512.0 / 2.0);
+        assert_eq!(read_iops, 100.0);
+        assert_eq!(write_iops, 20.0);
+    }
+
+    #[test]
+    fn wrapped_counters_read_as_idle() {
+        // 32 bit counters roll over, the sample straddling it must not turn into terabytes per second
+        let before = DiskCounters { reads: u32::MAX as u64 - 5, sectors_read: u32::MAX as u64 - 10, writes: 7, sectors_written: 70 };
+        let now = DiskCounters { reads: 4, sectors_read: 8, writes: 9, sectors_written: 86 };
+        let (read_rate, write_rate, read_iops, write_iops) = counter_rates(&before, &now, 1.0);
+        assert_eq!(read_rate, 0.0);
+        assert_eq!(read_iops, 0.0);
+        // the counters that didn't wrap still count
+        assert_eq!(write_rate, 16.0 * 512.0);
+        assert_eq!(write_iops, 2.0);
+    }
+}
@@ -1,12 +1,14 @@
 use std::io;
 use crossterm::event::{KeyEvent, KeyCode};
-use sysinfo::{System, Process};
+use sysinfo::{System, Process, Pid};
 use tachyonfx::{fx, Motion, Interpolation};
 use ratatui::prelude::Color;
-use libc::{kill, SIGKILL};
 use crate::app_state::AppState;
 use crate::constants::{ANIMATION_COLOR, ANIMATION_TIMER_MS};
-use crate::system_info::{filter_processes_cached, sort_processes_cached};
+use crate::daemon::ServiceAction;
+use crate::system_info::{filter_processes_cached, search_matches, sorted_processes, process_subtree};
+use crate::process_control;
+use crate::signals::SignalTargets;
 
 pub fn handle_key_event(
     key: KeyEvent,
@@ -25,6 +27,80 @@ pub fn handle_key_event(
         }
     }
 
+    // process control dialog from `m`: ↑/↓ pick a setting, ←/→ change it, Space toggles a cpu, Enter applies
+    if let Some(dialog) = app_state.control_dialog.as_mut() {
+        match key.code {
+            KeyCode::Up => dialog.up(),
+            KeyCode::Down => dialog.down(),
+            KeyCode::Left => dialog.adjust(-1),
+            KeyCode::Right => dialog.adjust(1),
+            KeyCode::Char(' ') => dialog.toggle_cpu(),
+            KeyCode::Char('a') => dialog.enable_all_cpus(),
+            KeyCode::Enter => app_state.apply_control_dialog(),
+            KeyCode::Esc | KeyCode::Char('m') => app_state.close_control_dialog(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    // details pane from `f`: ↑/↓ and PgUp/PgDn scroll, Tab/←/→ jump between sections
+    if app_state.details.is_some() {
+        match key.code {
+            KeyCode::Up => app_state.details_scroll_by(-1),
+            KeyCode::Down => app_state.details_scroll_by(1),
+            KeyCode::PageUp => app_state.details_scroll_by(-10),
+            KeyCode::PageDown => app_state.details_scroll_by(10),
+            KeyCode::Home => app_state.details_scroll = 0,
+            KeyCode::End => app_state.details_scroll_by(isize::MAX),
+            KeyCode::Tab | KeyCode::Right => app_state.details_jump_section(true),
+            KeyCode::BackTab | KeyCode::Left => app_state.details_jump_section(false),
+            KeyCode::Esc | KeyCode::Char('f') => app_state.close_details(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    // column panel from `C`: Space shows/hides, ←/→ resize, [ / ] move, s sorts on it, closing saves
+    if app_state.columns_panel_visible {
+        match key.code {
+            KeyCode::Up => app_state.columns_up(),
+            KeyCode::Down => app_state.columns_down(),
+            KeyCode::Char(' ') => app_state.column_toggle_visible(),
+            KeyCode::Left => app_state.column_resize(-1),
+            KeyCode::Right => app_state.column_resize(1),
+            KeyCode::Char('[') => app_state.column_move(true),
+            KeyCode::Char(']') => app_state.column_move(false),
+            KeyCode::Char('s') | KeyCode::Enter => app_state.column_sort_selected(),
+            KeyCode::Esc | KeyCode::Char('C') => app_state.close_columns_panel(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
+    // signal menu from `k`: Enter on a signal asks for confirmation, y/Enter then sends it
+    if app_state.signal_menu_visible {
+        match (app_state.signal_confirm, key.code) {
+            (false, KeyCode::Up) => app_state.signal_menu_up(),
+            (false, KeyCode::Down) => app_state.signal_menu_down(),
+            (false, KeyCode::Left) => app_state.signal_target_cycle(false),
+            (false, KeyCode::Right) => app_state.signal_target_cycle(true),
+            (false, KeyCode::Enter) => app_state.signal_confirm = true,
+            (false, KeyCode::Esc) | (false, KeyCode::Char('k')) => app_state.close_signal_menu(),
+            (true, KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter) => {
+                app_state.effects.add_effect(
+                    fx::dissolve((100, Interpolation::QuintInOut)).with_area(app_state.info_area)
+                );
+                app_state.send_selected_signal();
+                app_state.effects.add_effect(
+                    fx::coalesce((100, Interpolation::QuintInOut)).with_area(app_state.info_area)
+                );
+            }
+            (true, KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc) => app_state.signal_confirm = false,
+            _ => {}
+        }
+        return Ok(false);
+    }
+
     if let KeyCode::Char('z') = key.code {
         // Don't allow toggling pause when theme panel is open
         if app_state.theme_panel_visible {
@@ -41,7 +117,7 @@ pub fn handle_key_event(
     }
 
     // Handle pause menu navigation
-    if app_state.pause_overlay && !app_state.theme_panel_visible && !app_state.settings_panel_visible && !app_state.daemon_panel_visible {
+    if app_state.pause_overlay && !app_state.theme_panel_visible && !app_state.settings_panel_visible && !app_state.daemon_panel_visible && !app_state.service_manager_visible {
         match key.code {
             KeyCode::Up => {
                 app_state.pause_menu_up();
@@ -63,8 +139,12 @@ pub fn handle_key_event(
                         app_state.open_settings_panel();
                     }
                     2 => {
-                        // DAEMON SETTINGS
-                        app_state.open_daemon_panel();
+                        // DAEMON SETTINGS -> live service manager when the supervisor runs alongside us
+                        if app_state.supervisor.is_some() {
+                            app_state.open_service_manager();
+                        } else {
+                            app_state.open_daemon_panel();
+                        }
                     }
                     3 => {
                         // EXIT
@@ -125,8 +205,7 @@ pub fn handle_key_event(
                         app_state.apply_user_settings();
                     }
                     1 => {
-                        app_state.user_settings.default_interface = if app_state.user_settings.default_interface.to_lowercase() == "lo" { "eth0".to_string() } else { "lo".to_string() };
-                        app_state.apply_user_settings();
+                        app_state.cycle_default_interface(false);
                     }
                     2 => {
                         app_state.user_settings.default_usage_view = if app_state.user_settings.default_usage_view.to_lowercase() == "gpu" { "cpu".to_string() } else { "gpu".to_string() };
@@ -156,8 +235,7 @@ pub fn handle_key_event(
                         app_state.apply_user_settings();
                     }
                     1 => {
-                        app_state.user_settings.default_interface = if app_state.user_settings.default_interface.to_lowercase() == "lo" { "eth0".to_string() } else { "lo".to_string() };
-                        app_state.apply_user_settings();
+                        app_state.cycle_default_interface(true);
                     }
                     2 => {
                         app_state.user_settings.default_usage_view = if app_state.user_settings.default_usage_view.to_lowercase() == "gpu" { "cpu".to_string() } else { "gpu".to_string() };
@@ -209,6 +287,25 @@ pub fn handle_key_event(
         }
     }
 
+    // Service manager input when visible (integrated mode)
+    if app_state.service_manager_visible {
+        match key.code {
+            KeyCode::Up => app_state.service_manager_up(),
+            KeyCode::Down => app_state.service_manager_down(),
+            KeyCode::Char('s') => app_state.run_service_action(ServiceAction::Start),
+            KeyCode::Char('x') => app_state.run_service_action(ServiceAction::Stop),
+            KeyCode::Char('r') => app_state.run_service_action(ServiceAction::Restart),
+            KeyCode::Tab => {
+                // groups and the auto-run picker still live on the settings panel
+                app_state.close_service_manager();
+                app_state.open_daemon_panel();
+            }
+            KeyCode::Esc => app_state.close_service_manager(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
     // Daemon settings panel input when visible
     if app_state.daemon_panel_visible {
         match key.code {
@@ -217,7 +314,7 @@ pub fn handle_key_event(
                 return Ok(false);
             }
             KeyCode::Down => {
-                app_state.daemon_panel_down(app_state.available_services.len() + 2); // +2 for Save/Reset buttons
+                app_state.daemon_panel_down(app_state.daemon_panel_len());
                 return Ok(false);
             }
             KeyCode::Left | KeyCode::Right => {
@@ -240,15 +337,16 @@ pub fn handle_key_event(
                 return Ok(false);
             }
             KeyCode::Enter => {
+                let save_index = app_state.available_services.len() + app_state.available_groups.len();
                 match app_state.daemon_selected_index {
-                    idx if idx == app_state.available_services.len() => {
+                    idx if idx == save_index => {
                         // Save button
                         if let Err(e) = app_state.save_daemon_settings() {
                             app_state.show_popup(format!("Failed to save daemon settings: {}", e));
                         }
                         return Ok(false);
                     }
-                    idx if idx == app_state.available_services.len() + 1 => {
+                    idx if idx == save_index + 1 => {
                         // Reset button
                         app_state.reset_daemon_settings();
                         return Ok(false);
@@ -256,6 +354,19 @@ pub fn handle_key_event(
                     _ => return Ok(false),
                 }
             }
+            // group rows: bulk start/stop/restart on the running daemon
+            KeyCode::Char('s') => {
+                app_state.run_daemon_group_action(ServiceAction::Start);
+                return Ok(false);
+            }
+            KeyCode::Char('x') => {
+                app_state.run_daemon_group_action(ServiceAction::Stop);
+                return Ok(false);
+            }
+            KeyCode::Char('r') => {
+                app_state.run_daemon_group_action(ServiceAction::Restart);
+                return Ok(false);
+            }
             KeyCode::Esc => {
                 app_state.close_daemon_panel();
                 return Ok(false);
@@ -267,11 +378,55 @@ pub fn handle_key_event(
     if app_state.handle_search_input(key) {
         return Ok(false);
     }
+
+    // disk panel overlay, u/i keep working as usual so only the close keys need handling here
+    if app_state.disk_panel_visible && matches!(key.code, KeyCode::Esc | KeyCode::Char('d')) {
+        app_state.toggle_disk_panel();
+        return Ok(false);
+    }
+    if app_state.sensors_panel_visible && matches!(key.code, KeyCode::Esc | KeyCode::Char('e')) {
+        app_state.toggle_sensors_panel();
+        return Ok(false);
+    }
+
+    // thread view: ↑/↓ select, ←/→ change the sort column, everything else acts on the process as usual
+    if let Some(view) = app_state.thread_view.as_mut() {
+        let handled = match key.code {
+            KeyCode::Up => { view.up(); true }
+            KeyCode::Down => { view.down(); true }
+            KeyCode::Left => { app_state.thread_sort_cycle(false); true }
+            KeyCode::Right => { app_state.thread_sort_cycle(true); true }
+            KeyCode::Esc | KeyCode::Char('T') => { app_state.close_thread_view(); true }
+            _ => false,
+        };
+        if handled {
+            return Ok(false);
+        }
+    }
+
+    // grouped cgroup view takes over the process table navigation, everything else works as usual
+    if app_state.group_view {
+        let handled = match key.code {
+            KeyCode::Up => { app_state.group_view_up(); true }
+            KeyCode::Down => { app_state.group_view_down(); true }
+            KeyCode::Enter => { app_state.search_selected_group(); true }
+            KeyCode::Esc | KeyCode::Char('c') => { app_state.toggle_group_view(); true }
+            _ => false,
+        };
+        if handled {
+            return Ok(false);
+        }
+    }
     match key.code {
         KeyCode::Char('q') => return Ok(true),
 
         KeyCode::Char('/') => {
-            app_state.toggle_search();
+            // a locked filter goes back to editing, otherwise / opens and closes the search
+            if app_state.search_active && !app_state.search_editing {
+                app_state.search_editing = true;
+            } else {
+                app_state.toggle_search();
+            }
         }
 
         KeyCode::Esc => {
@@ -293,10 +448,8 @@ pub fn handle_key_event(
                 app_state.tree_navigate_down();
             } else {
                 if app_state.selected_process + 1 < processes.len() {
-                    app_state.selected_process += 1;
-                    if app_state.selected_process >= app_state.scroll_offset + app_state.visible_rows {
-                        app_state.scroll_offset += 1;
-                    }
+                    app_state.select_row(app_state.selected_process + 1);
+                    app_state.scroll_to_selected();
                 }
             }
         }
@@ -305,25 +458,23 @@ pub fn handle_key_event(
                 app_state.tree_navigate_up();
             } else {
                 if app_state.selected_process > 0 {
-                    app_state.selected_process -= 1;
-                    if app_state.selected_process < app_state.scroll_offset {
-                        app_state.scroll_offset = app_state.scroll_offset.saturating_sub(1);
-                    }
+                    app_state.select_row(app_state.selected_process - 1);
+                    app_state.scroll_to_selected();
                 }
             }
         }
         KeyCode::PageDown => {
-            app_state.selected_process = (app_state.selected_process + app_state.visible_rows)
-                .min(processes.len().saturating_sub(1));
+            app_state.select_row((app_state.selected_process + app_state.visible_rows)
+                .min(processes.len().saturating_sub(1)));
             app_state.scroll_offset = (app_state.scroll_offset + app_state.visible_rows)
                 .min(processes.len().saturating_sub(app_state.visible_rows));
         }
         KeyCode::PageUp => {
-            app_state.selected_process = app_state.selected_process.saturating_sub(app_state.visible_rows);
+            app_state.select_row(app_state.selected_process.saturating_sub(app_state.visible_rows));
             app_state.scroll_offset = app_state.scroll_offset.saturating_sub(app_state.visible_rows);
         }
         KeyCode::Home => {
-            app_state.selected_process = 0;
+            app_state.select_row(0);
             app_state.scroll_offset = 0;
         }
         /*
@@ -334,7 +485,6 @@ pub fn handle_key_event(
                 app_state.collapse_current_node();
             } else {
                 app_state.cycle_sort_left();
-                app_state.process_cache.invalidate();
             }
         }
 
@@ -343,23 +493,26 @@ pub fn handle_key_event(
                 app_state.expand_current_node();
             } else {
                 app_state.cycle_sort_right();
-                app_state.process_cache.invalidate();
             }
         }
 
         KeyCode::Char('b') => {
             add_sweep_effect(&mut app_state.effects, app_state.net_area);
-            app_state.switch_to_loopback();
+            app_state.previous_interface();
         }
         KeyCode::Char('n') => {
             add_sweep_effect(&mut app_state.effects, app_state.net_area);
-            app_state.switch_to_ethernet();
+            app_state.next_interface();
         }
 
         KeyCode::Char('v') => {
             add_sweep_effect(&mut app_state.effects, app_state.cpu_usage_area);
             app_state.toggle_gpu_usage_view();
         }
+        KeyCode::Char('l') => {
+            add_sweep_effect(&mut app_state.effects, app_state.cpu_average_area);
+            app_state.toggle_pressure_view();
+        }
         
         KeyCode::Enter => {
             if app_state.show_info && app_state.show_tree_view {
@@ -385,65 +538,130 @@ pub fn handle_key_event(
             }
         }
 
-        KeyCode::Char('o') => {
-            if let Err(err) = change_process_priority(processes, app_state.selected_process, "5") {
-                app_state.show_popup(err);
+        // quick nice steps on the highlighted row or every tagged process, m has the full dialog
+        KeyCode::Char('o') | KeyCode::Char('p') => {
+            let step = if key.code == KeyCode::Char('o') { 1 } else { -1 };
+            let batch = !app_state.tagged.is_empty();
+            let targets = if batch {
+                app_state.tagged_targets(system)
+            } else {
+                selected_process_target(app_state, system, processes).into_iter().collect()
+            };
+
+            let failures: Vec<String> = targets
+                .iter()
+                .filter_map(|(pid, name)| {
+                    process_control::get_nice(*pid)
+                        .and_then(|nice| process_control::set_nice(*pid, nice + step))
+                        .err()
+                        .map(|e| format!("{} ({}): {}", name, pid, e))
+                })
+                .collect();
+
+            if batch && failures.is_empty() {
+                app_state.show_notice(format!("Nice {:+} applied to {} tagged processes", step, targets.len()));
+            } else if batch {
+                app_state.show_popup(format!(
+                    "Nice {:+} applied to {} of {} tagged processes, failed:\n{}",
+                    step,
+                    targets.len() - failures.len(),
+                    targets.len(),
+                    failures.join("\n")
+                ));
+            } else if !failures.is_empty() {
+                app_state.show_popup(format!("Failed to change priority of {}", failures.join("\n")));
             }
+            app_state.invalidate_sort();
         }
-        KeyCode::Char('p') => {
-            if let Err(err) = change_process_priority(processes, app_state.selected_process, "-5") {
-                app_state.show_popup(err);
+        KeyCode::Char('m') => {
+            if let Some((pid, name)) = selected_process_target(app_state, system, processes) {
+                app_state.open_control_dialog(pid, name, system.cpus().len());
             }
         }
 
+        // freeze/resume: SIGSTOP the tagged set (or the selected row), SIGCONT if they're all stopped already
+        KeyCode::Char('F') => {
+            let targets = if app_state.tagged.is_empty() {
+                selected_process_target(app_state, system, processes).into_iter().collect()
+            } else {
+                app_state.tagged_targets(system)
+            };
+            app_state.freeze_or_resume(targets);
+        }
+        KeyCode::Char('Z') => {
+            app_state.toggle_frozen_filter();
+        }
+        KeyCode::Char('C') => {
+            app_state.open_columns_panel();
+        }
+        KeyCode::Char('w') => {
+            app_state.toggle_follow();
+        }
+        KeyCode::Char('R') => {
+            app_state.toggle_sort_direction();
+        }
+        KeyCode::Char('S') => {
+            app_state.toggle_secondary_sort();
+        }
+        KeyCode::Char('T') => {
+            if let Some((pid, name)) = selected_process_target(app_state, system, processes) {
+                app_state.open_thread_view(pid, name);
+            }
+        }
+        KeyCode::Char('f') => {
+            if let Some((pid, _)) = selected_process_target(app_state, system, processes) {
+                app_state.open_details(system, pid);
+            }
+        }
+
+        // tagging: space marks the row, * everything matching the search, U forgets them all
+        KeyCode::Char(' ') => {
+            if let Some((pid, _)) = selected_process_target(app_state, system, processes) {
+                app_state.toggle_tag(pid);
+            }
+        }
+        KeyCode::Char('*') => {
+            // fresh matches by pid, not the cached rows, which can be from before a re-sort
+            let pids: Vec<u32> = if app_state.search_active && !app_state.is_search_empty() {
+                search_matches(app_state, processes).iter().map(|p| p.pid().as_u32()).collect()
+            } else {
+                processes.iter().map(|p| p.pid().as_u32()).collect()
+            };
+            app_state.tag_all(pids);
+        }
+        KeyCode::Char('U') => {
+            app_state.clear_tags();
+        }
+
         KeyCode::Char('u') => {
             add_sweep_effect(&mut app_state.effects, app_state.disk_area);
             app_state.previous_disk();
         }
         KeyCode::Char('i') => {
             add_sweep_effect(&mut app_state.effects, app_state.disk_area);
-            // We'll need to pass the disk count from main
-            app_state.next_disk(usize::MAX); // Temporary - should be actual disk count
+            app_state.next_disk();
+        }
+        KeyCode::Char('d') => {
+            app_state.toggle_disk_panel();
+        }
+        KeyCode::Char('e') => {
+            app_state.toggle_sensors_panel();
+        }
+        KeyCode::Char('c') => {
+            add_sweep_effect(&mut app_state.effects, app_state.info_area);
+            app_state.toggle_group_view();
         }
 
         KeyCode::Char('g') => {
             app_state.previous_gpu();
         }
         KeyCode::Char('G') => {
-            app_state.next_gpu(app_state.gpu_info_cache.len());
+            app_state.next_gpu(app_state.gpu.gpus.len());
         }
         
         KeyCode::Char('k') => {
-            let target_pid = if app_state.show_info && app_state.show_tree_view {
-                app_state.get_selected_tree_item().map(|item| item.pid.as_u32() as i32)
-            } else {
-                let actual_process = if app_state.search_active && !app_state.is_search_empty() {
-                    let sorted_processes = sort_processes_cached(system, &app_state.sort_category, &mut app_state.process_cache, &app_state.search_active);
-                    filter_processes_cached(system, &sorted_processes, app_state)
-                } else {
-                    processes.clone()
-                };
-
-                actual_process.get(app_state.selected_process).map(|proc| proc.pid().as_u32() as i32)
-            };
-
-            if let Some(pid) = target_pid {
-                app_state.effects.add_effect(
-                    fx::dissolve((100, Interpolation::QuintInOut)).with_area(app_state.info_area)
-                );
-                
-                unsafe {
-                    kill(pid, SIGKILL);
-                }
-                
-                app_state.effects.add_effect(
-                    fx::coalesce((100, Interpolation::QuintInOut)).with_area(app_state.info_area)
-                );
-                
-                app_state.invalidate_tree_cache();
-                app_state.invalidate_search_cache();
-                app_state.process_cache.invalidate();
-            }
+            let choices = signal_choices(app_state, system, processes);
+            app_state.open_signal_menu(choices);
         }
         
         KeyCode::Char('+') => {
@@ -467,6 +685,69 @@ pub fn handle_key_event(
     Ok(false)
 }
 
+// (pid, name) of the highlighted row, in whichever view (tree, filtered or flat) is showing
+fn selected_process_target(app_state: &mut AppState, system: &System, processes: &[&Process]) -> Option<(u32, String)> {
+    if app_state.show_info && app_state.show_tree_view {
+        return app_state.get_selected_tree_item().map(|item| (item.pid.as_u32(), item.name.clone()));
+    }
+
+    // by pid, the index only says where it was when the list was last drawn. a row that was only just
+    // moved to (no pid picked up yet) is the one exception
+    let visible = visible_processes(app_state, system, processes);
+    let Some(pid) = app_state.selected_pid else {
+        return visible.get(app_state.selected_process).map(|proc| (proc.pid().as_u32(), proc.name().to_string_lossy().to_string()));
+    };
+    let target = visible.iter().find(|proc| proc.pid().as_u32() == pid).map(|proc| (pid, proc.name().to_string_lossy().to_string()));
+    if target.is_none() {
+        // never fall back to whatever took its row
+        app_state.show_popup(format!("{} (PID {}) is gone, pick another process", app_state.selected_name, pid));
+    }
+    target
+}
+
+// what `k` can aim at, the first one is preselected: the tagged set when there is one, otherwise the row
+fn signal_choices(app_state: &mut AppState, system: &System, processes: &[&Process]) -> Vec<SignalTargets> {
+    let mut choices = Vec::new();
+    if !app_state.tagged.is_empty() {
+        choices.push(SignalTargets::new("tagged".to_string(), app_state.tagged_targets(system)));
+    }
+
+    if let Some((pid, name)) = selected_process_target(app_state, system, processes) {
+        let subtree = process_subtree(system, Pid::from_u32(pid));
+        choices.push(SignalTargets::new(name.clone(), vec![(pid, name.clone())]));
+
+        if subtree.len() > 1 {
+            // deepest first so nothing gets orphaned (and reparented to init) on the way
+            let mut children_first = subtree.clone();
+            children_first.sort_by_key(|(_, _, depth)| std::cmp::Reverse(*depth));
+            let strip = |list: Vec<(u32, String, usize)>| list.into_iter().map(|(pid, name, _)| (pid, name)).collect();
+            choices.push(SignalTargets::new(format!("subtree of {}, children first", name), strip(children_first)));
+            choices.push(SignalTargets::new(format!("subtree of {}, all at once", name), strip(subtree)).at_once());
+        }
+    }
+
+    if app_state.search_active && !app_state.is_search_empty() {
+        // matched again by pid against the live list, the cached rows may be from an older order
+        let matches: Vec<(u32, String)> = search_matches(app_state, processes)
+            .iter()
+            .filter(|proc| proc.thread_kind().is_none())
+            .map(|proc| (proc.pid().as_u32(), proc.name().to_string_lossy().to_string()))
+            .collect();
+        choices.push(SignalTargets::new(format!("all matches of '{}'", app_state.search_query), matches));
+    }
+    choices
+}
+
+// the flat list as shown, narrowed down to the search matches when searching
+fn visible_processes<'a>(app_state: &mut AppState, system: &'a System, processes: &[&'a Process]) -> Vec<&'a Process> {
+    if app_state.search_active && !app_state.is_search_empty() {
+        let sorted_processes = sorted_processes(system, app_state);
+        filter_processes_cached(system, &sorted_processes, app_state)
+    } else {
+        processes.to_vec()
+    }
+}
+
 fn add_sweep_effect(effects: &mut tachyonfx::EffectManager<()>, area: ratatui::layout::Rect) {
     let color = Color::from_u32(ANIMATION_COLOR);
     let timer = (ANIMATION_TIMER_MS, Interpolation::QuintInOut);
@@ -475,27 +756,44 @@ fn add_sweep_effect(effects: &mut tachyonfx::EffectManager<()>, area: ratatui::l
     );
 }
 
-fn change_process_priority(processes: &Vec<&Process>, selected_process: usize, nice_value: &str) -> Result<(), String> {
-    if let Some(proc) = processes.get(selected_process) {
-        let output = std::process::Command::new("renice")
-            .arg("-n")
-            .arg(nice_value)
-            .arg("-p")
-            .arg(proc.pid().to_string())
-            .output()
-            .map_err(|e| format!("Failed to execute renice: {}", e))?;
-
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!(
-                "Permission denied: Failed to change process priority.\n\n\
-                Error: {}\n\n\
-                Try running r-top with sudo:\n  sudo r-top",
-                stderr.trim()
-            ));
-        }
-        Ok(())
-    } else {
-        Err("Process not found".to_string())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn all_matches(app_state: &mut AppState, system: &System, processes: &[&Process]) -> HashSet<u32> {
+        let choices = signal_choices(app_state, system, processes);
+        let all = choices.iter().find(|choice| choice.label.starts_with("all matches")).expect("no all matches choice");
+        // the test binary is r-top's own lineage here, so it lands in excluded
+        all.targets.iter().chain(all.excluded.iter()).map(|(pid, _)| *pid).collect()
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn all_matches_does_not_follow_a_resort() {
+        let system = System::new_all();
+        let own = system.process(Pid::from_u32(std::process::id())).expect("own process");
+        let name = own.name().to_string_lossy().to_string();
+
+        let mut app_state = AppState::new();
+        app_state.search_active = true;
+        app_state.search_query = name.clone();
+
+        let mut by_pid: Vec<&Process> = system.processes().values().collect();
+        by_pid.sort_by_key(|proc| proc.pid());
+        let mut reversed = by_pid.clone();
+        reversed.reverse();
+
+        // a search cache left over from some other order (or query) must not leak into the targets
+        filter_processes_cached(&system, &by_pid, &mut app_state);
+        app_state.filtered_processes = by_pid.iter().take(3).map(|proc| proc.pid().as_u32()).collect();
+
+        let first = all_matches(&mut app_state, &system, &by_pid);
+        let second = all_matches(&mut app_state, &system, &reversed);
+        assert_eq!(first, second);
+        assert!(first.contains(&std::process::id()));
+        for pid in &first {
+            let proc = system.process(Pid::from_u32(*pid)).unwrap();
+            assert!(proc.name().to_string_lossy().contains(&name));
+        }
+    }
+}
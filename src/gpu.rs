@@ -0,0 +1,455 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/*
+    GPUs, typed.
+    - amdgpu, i915 and xe expose what we need under /sys/class/drm/cardN/device:
+        gpu_busy_percent (amdgpu only), mem_info_vram_total / mem_info_vram_used (amdgpu, bytes),
+        hwmon/hwmonN/temp1_input (millidegrees, amdgpu and discrete intel)
+    - nvidia's driver doesn't put any of that in sysfs, so nvidia cards still come from nvidia-smi
+    - per-process usage is the DRM fdinfo interface (linux 5.19+ for amdgpu/i915, 6.8 for xe):
+        /proc/<pid>/fdinfo/<fd> of a /dev/dri fd has drm-client-id and drm-engine-<name>: <ns> ns
+      a client can be open through several fds, so engine time is counted once per (pdev, client id)
+    both roots are configurable so the parsing can be pointed at a copied sysfs/proc tree
+ */
+const NVIDIA_SMI_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+    Other,
+}
+
+impl GpuVendor {
+    fn from_pci_id(id: &str) -> Self {
+        match id {
+            "0x10de" => GpuVendor::Nvidia,
+            "0x1002" => GpuVendor::Amd,
+            "0x8086" => GpuVendor::Intel,
+            _ => GpuVendor::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GpuVendor::Nvidia => "NVIDIA",
+            GpuVendor::Amd => "AMD",
+            GpuVendor::Intel => "Intel",
+            GpuVendor::Other => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: GpuVendor,
+    pub driver: Option<String>, // kernel driver (amdgpu, i915, xe) or the nvidia driver version
+    pub busy_percent: Option<f64>,
+    pub memory_total: Option<u64>, // bytes
+    pub memory_used: Option<u64>,
+    pub temperature: Option<f64>, // °C
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessGpuUsage {
+    pub busy_percent: f64, // engine time over the interval, summed across engines so it can pass 100
+    pub memory: u64, // bytes of vram (or system memory for integrated gpus) held by the process
+}
+
+pub struct GpuMonitor {
+    pub gpus: Vec<GpuInfo>,
+    per_pid: HashMap<u32, ProcessGpuUsage>,
+    prev_engine_ns: HashMap<u32, u64>,
+    nvidia: Vec<GpuInfo>,
+    nvidia_checked: Option<Instant>,
+    sys_root: PathBuf,
+    proc_root: PathBuf,
+}
+
+impl GpuMonitor {
+    pub fn new() -> Self {
+        Self::with_roots("/sys", "/proc")
+    }
+
+    pub fn with_roots(sys_root: impl Into<PathBuf>, proc_root: impl Into<PathBuf>) -> Self {
+        Self {
+            gpus: Vec::new(),
+            per_pid: HashMap::new(),
+            prev_engine_ns: HashMap::new(),
+            nvidia: Vec::new(),
+            nvidia_checked: None,
+            sys_root: sys_root.into(),
+            proc_root: proc_root.into(),
+        }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&ProcessGpuUsage> {
+        self.per_pid.get(&pid)
+    }
+
+    // elapsed is the time since the previous refresh, 0 for the first fill
+    pub fn refresh(&mut self, elapsed_secs: f64) {
+        // nvidia-smi is a whole process spawn, don't run it every refresh
+        if self.nvidia_checked.is_none_or(|t| t.elapsed() >= NVIDIA_SMI_INTERVAL) {
+            self.nvidia = query_nvidia_smi();
+            self.nvidia_checked = Some(Instant::now());
+        }
+
+        let mut gpus = self.nvidia.clone();
+        let have_nvidia = !gpus.is_empty();
+        gpus.extend(
+            read_drm_cards(&self.sys_root)
+                .into_iter()
+                .filter(|gpu| !(have_nvidia && gpu.vendor == GpuVendor::Nvidia)),
+        );
+        self.gpus = gpus;
+
+        let clients = read_drm_clients(&self.proc_root);
+        let mut per_pid = HashMap::new();
+        let mut engine_ns = HashMap::new();
+        for (pid, client) in clients {
+            let prev = self.prev_engine_ns.get(&pid).copied();
+            let busy_percent = match prev {
+                Some(prev) if elapsed_secs > 0.0 => {
+                    client.engine_ns.saturating_sub(prev) as f64 / (elapsed_secs * 1e9) * 100.0
+                }
+                _ => 0.0,
+            };
+            per_pid.insert(pid, ProcessGpuUsage { busy_percent, memory: client.memory });
+            engine_ns.insert(pid, client.engine_ns);
+        }
+        self.per_pid = per_pid;
+        self.prev_engine_ns = engine_ns;
+    }
+}
+
+// every /sys/class/drm/cardN (connectors like card0-DP-1 skipped), in card order
+pub fn read_drm_cards(sys_root: &Path) -> Vec<GpuInfo> {
+    let Ok(entries) = fs::read_dir(sys_root.join("class/drm")) else {
+        return Vec::new();
+    };
+
+    let mut cards: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("card") && !n.contains('-'))
+        })
+        .collect();
+    cards.sort();
+
+    cards.iter().filter_map(|card| read_drm_card(&card.join("device"))).collect()
+}
+
+fn read_drm_card(device: &Path) -> Option<GpuInfo> {
+    let vendor_id = read_trimmed(&device.join("vendor"))?;
+    let vendor = GpuVendor::from_pci_id(&vendor_id);
+    let device_id = read_trimmed(&device.join("device")).unwrap_or_default();
+    let driver = fs::read_link(device.join("driver"))
+        .ok()
+        .and_then(|link| link.file_name().map(|n| n.to_string_lossy().to_string()));
+
+    let name = read_trimmed(&device.join("product_name"))
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| format!("{} GPU [{}]", vendor.as_str(), device_id.trim_start_matches("0x")));
+
+    Some(GpuInfo {
+        name,
+        vendor,
+        driver,
+        busy_percent: read_number(&device.join("gpu_busy_percent")),
+        memory_total: read_number(&device.join("mem_info_vram_total")),
+        memory_used: read_number(&device.join("mem_info_vram_used")),
+        temperature: read_hwmon_temperature(device),
+    })
+}
+
+fn read_hwmon_temperature(device: &Path) -> Option<f64> {
+    let mut hwmons: Vec<PathBuf> = fs::read_dir(device.join("hwmon")).ok()?.flatten().map(|e| e.path()).collect();
+    hwmons.sort();
+    hwmons.iter()
+        .find_map(|hwmon| read_number::<f64>(&hwmon.join("temp1_input")))
+        .map(|millidegrees| millidegrees / 1000.0)
+}
+
+struct DrmClient {
+    engine_ns: u64,
+    memory: u64,
+}
+
+// pid -> summed engine time and memory over all its drm clients
+fn read_drm_clients(proc_root: &Path) -> HashMap<u32, DrmClient> {
+    let mut clients = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return clients;
+    };
+
+    for entry in entries.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+
+        let mut seen = Vec::new();
+        let mut client = DrmClient { engine_ns: 0, memory: 0 };
+        for fd in fds.flatten() {
+            let is_drm = fs::read_link(fd.path()).is_ok_and(|target| target.starts_with("/dev/dri/"));
+            if !is_drm {
+                continue;
+            }
+            let Ok(info) = fs::read_to_string(entry.path().join("fdinfo").join(fd.file_name())) else {
+                continue;
+            };
+            let Some(fdinfo) = parse_drm_fdinfo(&info) else {
+                continue;
+            };
+            if seen.contains(&fdinfo.key) {
+                continue;
+            }
+            client.engine_ns += fdinfo.engine_ns;
+            client.memory += fdinfo.memory;
+            seen.push(fdinfo.key);
+        }
+
+        if !seen.is_empty() {
+            clients.insert(pid, client);
+        }
+    }
+
+    clients
+}
+
+struct DrmFdinfo {
+    key: (String, String), // (drm-pdev, drm-client-id)
+    engine_ns: u64,
+    memory: u64,
+}
+
+/*
+    drm-driver:     amdgpu
+    drm-pdev:       0000:03:00.0
+    drm-client-id:  42
+    drm-engine-gfx: 1234567 ns
+    drm-memory-vram:        2048 KiB        (amdgpu, older i915)
+    drm-total-vram0:        2048 KiB        (xe and newer kernels)
+ */
+fn parse_drm_fdinfo(content: &str) -> Option<DrmFdinfo> {
+    let mut pdev = String::new();
+    let mut client_id = None;
+    let mut engine_ns = 0;
+    let mut memory = 0;
+
+    for line in content.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let number = || value.split_whitespace().next().and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+
+        if key == "drm-pdev" {
+            pdev = value.to_string();
+        } else if key == "drm-client-id" {
+            client_id = Some(value.to_string());
+        } else if key.starts_with("drm-engine-") && !key.starts_with("drm-engine-capacity-") && value.ends_with("ns") {
+            engine_ns += number();
+        } else if key.starts_with("drm-memory-vram") || key.starts_with("drm-total-vram") {
+            let unit = value.split_whitespace().nth(1).unwrap_or("");
+            memory += number() * unit_multiplier(unit);
+        }
+    }
+
+    Some(DrmFdinfo { key: (pdev, client_id?), engine_ns, memory })
+}
+
+fn unit_multiplier(unit: &str) -> u64 {
+    match unit {
+        "KiB" => 1024,
+        "MiB" => 1024 * 1024,
+        "GiB" => 1024 * 1024 * 1024,
+        _ => 1,
+    }
+}
+
+fn query_nvidia_smi() -> Vec<GpuInfo> {
+    let Ok(output) = std::process::Command::new("nvidia-smi")
+        .arg("--query-gpu=name,driver_version,memory.total,memory.used,temperature.gpu,utilization.gpu")
+        .arg("--format=csv,noheader,nounits")
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+
+    // memory is in MiB, "[N/A]" shows up for fields a card doesn't support
+    let mib = |v: &str| v.parse::<u64>().ok().map(|m| m * 1024 * 1024);
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
+            (parts.len() >= 6).then(|| GpuInfo {
+                name: parts[0].to_string(),
+                vendor: GpuVendor::Nvidia,
+                driver: Some(format!("nvidia {}", parts[1])),
+                busy_percent: parts[5].parse().ok(),
+                memory_total: mib(parts[2]),
+                memory_used: mib(parts[3]),
+                temperature: parts[4].parse().ok(),
+            })
+        })
+        .collect()
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+fn read_number<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    read_trimmed(path)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+
+    // a scratch sysfs/proc tree, removed again when the test is done with it
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("r-top-gpu-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            Fixture(root)
+        }
+
+        fn write(&self, path: &str, content: &str) {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        fn link(&self, path: &str, target: &str) {
+            let path = self.0.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            symlink(target, path).unwrap();
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn reads_sysfs_cards() {
+        let sys = Fixture::new("sysfs");
+        sys.write("class/drm/card0/device/vendor", "0x1002\n");
+        sys.write("class/drm/card0/device/device", "0x73bf\n");
+        sys.write("class/drm/card0/device/product_name", "Radeon RX 6800\n");
+        sys.write("class/drm/card0/device/gpu_busy_percent", "37\n");
+        sys.write("class/drm/card0/device/mem_info_vram_total", "17163091968\n");
+        sys.write("class/drm/card0/device/mem_info_vram_used", "1073741824\n");
+        sys.write("class/drm/card0/device/hwmon/hwmon3/temp1_input", "45500\n");
+        sys.link("class/drm/card0/device/driver", "../../../bus/pci/drivers/amdgpu");
+        // connectors live next to the cards and have to be skipped
+        sys.write("class/drm/card0-DP-1/status", "connected\n");
+        // an integrated intel gpu exposes none of the numbers
+        sys.write("class/drm/card1/device/vendor", "0x8086\n");
+        sys.write("class/drm/card1/device/device", "0x9a49\n");
+
+        let cards = read_drm_cards(&sys.0);
+        assert_eq!(cards.len(), 2);
+
+        let amd = &cards[0];
+        assert_eq!(amd.name, "Radeon RX 6800");
+        assert_eq!(amd.vendor, GpuVendor::Amd);
+        assert_eq!(amd.driver.as_deref(), Some("amdgpu"));
+        assert_eq!(amd.busy_percent, Some(37.0));
+        assert_eq!(amd.memory_total, Some(17163091968));
+        assert_eq!(amd.memory_used, Some(1073741824));
+        assert_eq!(amd.temperature, Some(45.5));
+
+        let intel = &cards[1];
+        assert_eq!(intel.name, "Intel GPU [9a49]");
+        assert_eq!(intel.vendor, GpuVendor::Intel);
+        assert_eq!(intel.driver, None);
+        assert_eq!(intel.busy_percent, None);
+        assert_eq!(intel.memory_total, None);
+        assert_eq!(intel.temperature, None);
+    }
+
+    #[test]
+    fn missing_drm_class_means_no_cards() {
+        let sys = Fixture::new("empty-sysfs");
+        assert!(read_drm_cards(&sys.0).is_empty());
+    }
+
+    fn fdinfo(gfx_ns: u64) -> String {
+        format!(
+            "pos:\t0\nflags:\t02100002\ndrm-driver:\tamdgpu\ndrm-pdev:\t0000:03:00.0\ndrm-client-id:\t42\n\
+             drm-engine-gfx:\t{} ns\ndrm-engine-compute:\t500000000 ns\ndrm-engine-capacity-gfx:\t2\n\
+             drm-memory-vram:\t2048 KiB\n",
+            gfx_ns
+        )
+    }
+
+    #[test]
+    fn per_process_engine_deltas() {
+        let sys = Fixture::new("deltas-sys");
+        let proc = Fixture::new("deltas-proc");
+        proc.link("1234/fd/5", "/dev/dri/renderD128");
+        // a dup of the same client must only count once
+        proc.link("1234/fd/6", "/dev/dri/renderD128");
+        proc.link("1234/fd/7", "/dev/null");
+        proc.write("1234/fdinfo/5", &fdinfo(1_000_000_000));
+        proc.write("1234/fdinfo/6", &fdinfo(1_000_000_000));
+        proc.write("1234/fdinfo/7", "pos:\t0\nflags:\t0100002\n");
+        // no gpu fds at all
+        proc.link("99/fd/0", "/dev/null");
+        proc.write("self/status", "not a pid\n");
+
+        let mut monitor = GpuMonitor::with_roots(&sys.0, &proc.0);
+        monitor.refresh(0.0);
+        let first = monitor.get(1234).copied().unwrap();
+        assert_eq!(first.busy_percent, 0.0);
+        assert_eq!(first.memory, 2048 * 1024);
+        assert!(monitor.get(99).is_none());
+
+        // half a second more on gfx over two seconds is 25%
+        proc.write("1234/fdinfo/5", &fdinfo(1_500_000_000));
+        proc.write("1234/fdinfo/6", &fdinfo(1_500_000_000));
+        monitor.refresh(2.0);
+        let second = monitor.get(1234).copied().unwrap();
+        assert!((second.busy_percent - 25.0).abs() < 1e-9, "{}", second.busy_percent);
+        assert_eq!(second.memory, 2048 * 1024);
+
+        // a counter that went backwards (client reopened) reads as idle, not as a huge number
+        proc.write("1234/fdinfo/5", &fdinfo(0));
+        proc.write("1234/fdinfo/6", &fdinfo(0));
+        monitor.refresh(2.0);
+        assert_eq!(monitor.get(1234).unwrap().busy_percent, 0.0);
+    }
+
+    #[test]
+    fn fdinfo_needs_a_client_id() {
+        assert!(parse_drm_fdinfo("drm-driver:\ti915\ndrm-engine-render:\t100 ns\n").is_none());
+
+        let xe = parse_drm_fdinfo("drm-pdev:\t0000:00:02.0\ndrm-client-id:\t7\ndrm-engine-rcs:\t300 ns\ndrm-total-vram0:\t3 MiB\n").unwrap();
+        assert_eq!(xe.key, ("0000:00:02.0".to_string(), "7".to_string()));
+        assert_eq!(xe.engine_ns, 300);
+        assert_eq!(xe.memory, 3 * 1024 * 1024);
+    }
+}
@@ -0,0 +1,833 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde_norway::Value;
+use crate::daemon::{HealthCheckConfig, ServiceConfig};
+
+/*
+    `r-top import` - turns systemd units and docker-compose files into services.toml entries.
+    nothing here is lossless, anything we can't express in a ServiceConfig ends up as a warning instead of being dropped quietly
+ */
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Systemd,
+    Compose,
+}
+
+impl ImportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "systemd" => Some(ImportFormat::Systemd),
+            "compose" => Some(ImportFormat::Compose),
+            _ => None,
+        }
+    }
+
+    // by extension first, then by sniffing the first section header
+    pub fn detect(path: &Path, content: &str) -> Option<Self> {
+        let file_name = path.file_name()?.to_string_lossy().to_lowercase();
+        if file_name.ends_with(".service") {
+            return Some(ImportFormat::Systemd);
+        }
+        if file_name.ends_with(".yml") || file_name.ends_with(".yaml") {
+            return Some(ImportFormat::Compose);
+        }
+
+        let first = content.lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#') && !l.starts_with(';'))?;
+        if first.starts_with('[') {
+            Some(ImportFormat::Systemd)
+        } else if first.starts_with("services:") || first.starts_with("version:") {
+            Some(ImportFormat::Compose)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct ImportResult {
+    pub services: Vec<ServiceConfig>,
+    pub warnings: Vec<String>,
+}
+
+pub fn import_file(path: &Path, format: Option<ImportFormat>) -> Result<ImportResult, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let format = format.or_else(|| ImportFormat::detect(path, &content))
+        .ok_or_else(|| format!("Can't tell whether {} is a systemd unit or a compose file, pass --format", path.display()))?;
+
+    match format {
+        ImportFormat::Systemd => {
+            let name = path.file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "imported".to_string());
+            import_systemd_unit(&name, &content)
+        }
+        ImportFormat::Compose => {
+            // relative bind mounts and env files are relative to the compose file, not to wherever the daemon runs
+            let base_dir = fs::canonicalize(path).ok()
+                .and_then(|p| p.parent().map(Path::to_path_buf))
+                .unwrap_or_else(|| PathBuf::from("."));
+            import_compose(&content, &base_dir)
+        }
+    }
+}
+
+fn new_service(name: &str, command: String, args: Vec<String>) -> ServiceConfig {
+    ServiceConfig {
+        name: name.to_string(),
+        command,
+        args,
+        working_dir: None,
+        env_vars: HashMap::new(),
+        restart_policy: "never".to_string(),
+        max_restarts: 5,
+        restart_delay_secs: 10,
+        health_check: None,
+        groups: Vec::new(),
+        autostart: true,
+        notify: None,
+        user: None,
+    }
+}
+
+// ========================= SYSTEMD ============================
+
+// [Section] -> list of (key, value), keys can repeat (Environment=, ExecStart=)
+fn parse_unit_file(content: &str) -> Vec<(String, String, String)> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+    let mut pending = String::new();
+
+    for raw in content.lines() {
+        let line = raw.trim();
+        if pending.is_empty() && (line.is_empty() || line.starts_with('#') || line.starts_with(';')) {
+            continue;
+        }
+
+        // trailing backslash continues the value on the next line
+        if let Some(stripped) = line.strip_suffix('\\') {
+            pending.push_str(stripped);
+            pending.push(' ');
+            continue;
+        }
+        let line = if pending.is_empty() { line.to_string() } else { std::mem::take(&mut pending) + line };
+
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line[1..line.len() - 1].to_string();
+        } else if let Some((key, value)) = line.split_once('=') {
+            entries.push((section.clone(), key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    entries
+}
+
+fn import_systemd_unit(name: &str, content: &str) -> Result<ImportResult, String> {
+    let mut warnings = Vec::new();
+    let mut exec_start: Option<String> = None;
+    let mut service_name = name.to_string();
+
+    if let Some(base) = name.strip_suffix('@') {
+        warnings.push(format!("{}: template units need an instance name, imported as '{}'", name, base));
+        service_name = base.to_string();
+    }
+
+    let mut service = new_service(&service_name, String::new(), Vec::new());
+    // systemd's own default is Restart=no
+    service.restart_policy = "never".to_string();
+    service.restart_delay_secs = 0;
+
+    for (section, key, value) in parse_unit_file(content) {
+        let w = |msg: String| format!("{}: {}", service_name, msg);
+
+        match (section.as_str(), key.as_str()) {
+            ("Service", "ExecStart") => {
+                if value.is_empty() {
+                    exec_start = None; // an empty ExecStart= resets the list in drop-ins
+                } else if exec_start.is_some() {
+                    warnings.push(w(format!("only the first ExecStart is used, ignoring '{}'", value)));
+                } else {
+                    exec_start = Some(value);
+                }
+            }
+            ("Service", "WorkingDirectory") => {
+                let dir = value.trim_start_matches('-');
+                if dir == "~" {
+                    warnings.push(w("WorkingDirectory=~ depends on the user's home, set working_dir by hand".to_string()));
+                } else {
+                    service.working_dir = Some(dir.to_string());
+                }
+            }
+            ("Service", "Environment") => {
+                match shell_words::split(&value) {
+                    Ok(assignments) => {
+                        for assignment in assignments {
+                            match assignment.split_once('=') {
+                                Some((k, v)) => { service.env_vars.insert(k.to_string(), v.to_string()); }
+                                None => warnings.push(w(format!("skipping malformed Environment entry '{}'", assignment))),
+                            }
+                        }
+                    }
+                    Err(e) => warnings.push(w(format!("can't parse Environment={}: {}", value, e))),
+                }
+            }
+            ("Service", "Restart") => {
+                service.restart_policy = match value.as_str() {
+                    "no" => "never",
+                    "always" => "always",
+                    "on-failure" => "on_failure",
+                    "on-abnormal" | "on-abort" | "on-watchdog" => {
+                        warnings.push(w(format!("Restart={} imported as on_failure", value)));
+                        "on_failure"
+                    }
+                    "on-success" => {
+                        warnings.push(w("Restart=on-success has no equivalent, imported as never".to_string()));
+                        "never"
+                    }
+                    other => {
+                        warnings.push(w(format!("unknown Restart={}, imported as never", other)));
+                        "never"
+                    }
+                }.to_string();
+            }
+            ("Service", "RestartSec") => match parse_duration_secs(&value) {
+                Some(secs) => service.restart_delay_secs = secs,
+                None => warnings.push(w(format!("can't parse RestartSec={}", value))),
+            },
+            ("Service", "User") => service.user = Some(value),
+            ("Service", "Type") => {
+                if !matches!(value.as_str(), "simple" | "exec") {
+                    warnings.push(w(format!("Type={} isn't supported, the daemon expects the command to stay in the foreground", value)));
+                }
+            }
+            ("Unit", "StartLimitBurst") | ("Service", "StartLimitBurst") => match value.parse() {
+                Ok(burst) => service.max_restarts = burst,
+                Err(_) => warnings.push(w(format!("can't parse StartLimitBurst={}", value))),
+            },
+            // descriptive or install-time only, nothing to translate
+            ("Unit", "Description") | ("Unit", "Documentation") | ("Install", _) => {}
+            (section, key) => warnings.push(w(format!("[{}] {}={} is not supported and was ignored", section, key, value))),
+        }
+    }
+
+    let exec_start = exec_start.ok_or_else(|| format!("{}: no ExecStart= in the [Service] section", service_name))?;
+
+    // strip the special executable prefixes (-, @, +, !, !!)
+    let exec = exec_start.trim_start_matches(['-', '@', '+', '!', ':']);
+    if exec.len() != exec_start.len() {
+        warnings.push(format!("{}: ExecStart prefix '{}' was dropped", service_name, &exec_start[..exec_start.len() - exec.len()]));
+    }
+    if exec.contains('%') {
+        warnings.push(format!("{}: ExecStart uses unit specifiers (%), fill them in by hand", service_name));
+    }
+    if exec.contains('$') {
+        warnings.push(format!("{}: ExecStart references environment variables, which are not expanded. wrap it in /bin/sh -c if needed", service_name));
+    }
+
+    let mut words = shell_words::split(exec)
+        .map_err(|e| format!("{}: can't parse ExecStart: {}", service_name, e))?;
+    if words.is_empty() {
+        return Err(format!("{}: empty ExecStart", service_name));
+    }
+    service.command = words.remove(0);
+    service.args = words;
+
+    Ok(ImportResult { services: vec![service], warnings })
+}
+
+// systemd style time spans: "5", "5s", "500ms", "1min 30s", "2h"
+fn parse_duration_secs(value: &str) -> Option<u64> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(secs);
+    }
+
+    let mut total_ms: u64 = 0;
+    for part in value.split_whitespace() {
+        let split = part.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let (number, unit) = part.split_at(split);
+        let number: f64 = number.parse().ok()?;
+        let factor_ms = match unit {
+            "ms" | "msec" => 1.0,
+            "s" | "sec" | "second" | "seconds" => 1000.0,
+            "m" | "min" | "minute" | "minutes" => 60_000.0,
+            "h" | "hr" | "hour" | "hours" => 3_600_000.0,
+            "d" | "day" | "days" => 86_400_000.0,
+            _ => return None,
+        };
+        total_ms += (number * factor_ms) as u64;
+    }
+
+    // round sub-second delays up, restart_delay_secs has no finer resolution
+    Some(total_ms.div_ceil(1000))
+}
+
+// ========================= DOCKER COMPOSE ============================
+/*
+    every compose service becomes a foreground `docker run --rm --name ...` like example-services/docker_container.toml,
+    so the daemon supervises the docker client and `docker exec` works for health checks
+ */
+
+fn import_compose(content: &str, base_dir: &Path) -> Result<ImportResult, String> {
+    let root: Value = serde_norway::from_str(content).map_err(|e| format!("Invalid compose file: {}", e))?;
+    let services = root.get("services")
+        .and_then(Value::as_mapping)
+        .ok_or("Compose file has no services: section")?;
+
+    // compose's project name, so the whole file can be started and stopped as one group
+    let project = root.get("name")
+        .and_then(yaml_scalar)
+        .or_else(|| base_dir.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "compose".to_string());
+
+    let mut result = ImportResult { services: Vec::new(), warnings: Vec::new() };
+
+    for (name, definition) in services {
+        let Some(name) = name.as_str() else {
+            continue;
+        };
+        match compose_service(name, definition, base_dir, &mut result.warnings) {
+            Some(mut service) => {
+                service.working_dir = Some(base_dir.to_string_lossy().to_string());
+                service.groups = vec![project.clone()];
+                result.services.push(service);
+            }
+            None => result.warnings.push(format!("{}: skipped", name)),
+        }
+    }
+
+    for key in root.as_mapping().into_iter().flat_map(|m| m.keys()).filter_map(Value::as_str) {
+        if !matches!(key, "services" | "version" | "name") {
+            result.warnings.push(format!("top level '{}' section is not supported and was ignored", key));
+        }
+    }
+
+    Ok(result)
+}
+
+fn yaml_scalar(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+// `command:` and friends are either a shell-ish string or a list
+fn yaml_words(value: &Value) -> Option<Vec<String>> {
+    match value {
+        Value::String(s) => shell_words::split(s).ok(),
+        Value::Sequence(items) => items.iter().map(yaml_scalar).collect(),
+        _ => None,
+    }
+}
+
+fn compose_service(name: &str, definition: &Value, base_dir: &Path, warnings: &mut Vec<String>) -> Option<ServiceConfig> {
+    let Some(mapping) = definition.as_mapping() else {
+        warnings.push(format!("{}: service definition is not a mapping", name));
+        return None;
+    };
+    let Some(image) = definition.get("image").and_then(yaml_scalar) else {
+        warnings.push(format!("{}: no image (build: is not supported), build and tag it first", name));
+        return None;
+    };
+
+    let container = definition.get("container_name")
+        .and_then(yaml_scalar)
+        .unwrap_or_else(|| format!("{}-daemon", name));
+
+    let mut args: Vec<String> = vec!["run".into(), "--rm".into(), "--name".into(), container.clone()];
+    let mut service = new_service(name, "/usr/bin/docker".to_string(), Vec::new());
+    let mut trailing: Vec<String> = Vec::new();
+
+    for (key, value) in mapping {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        let w = |msg: String| format!("{}: {}", name, msg);
+
+        match key {
+            "image" | "container_name" => {}
+            "ports" | "volumes" | "env_file" | "dns" | "cap_add" | "cap_drop" | "devices" | "extra_hosts" => {
+                let flag = match key {
+                    "ports" => "-p",
+                    "volumes" => "-v",
+                    "env_file" => "--env-file",
+                    "dns" => "--dns",
+                    "cap_add" => "--cap-add",
+                    "cap_drop" => "--cap-drop",
+                    "devices" => "--device",
+                    _ => "--add-host",
+                };
+                let items: Vec<Value> = match value {
+                    Value::Sequence(items) => items.clone(),
+                    other => vec![other.clone()],
+                };
+                for item in items {
+                    match yaml_scalar(&item) {
+                        Some(v) => {
+                            args.push(flag.to_string());
+                            args.push(if key == "volumes" { absolute_bind_source(&v, base_dir) } else { v });
+                        }
+                        None => warnings.push(w(format!("long {} syntax is not supported, use the short string form", key))),
+                    }
+                }
+            }
+            "environment" => match value {
+                Value::Sequence(items) => {
+                    for item in items.iter().filter_map(yaml_scalar) {
+                        args.push("-e".into());
+                        args.push(item);
+                    }
+                }
+                Value::Mapping(vars) => {
+                    for (k, v) in vars {
+                        if let Some(k) = yaml_scalar(k) {
+                            args.push("-e".into());
+                            // a bare key passes the variable through from the daemon's environment
+                            args.push(match yaml_scalar(v) {
+                                Some(v) => format!("{}={}", k, v),
+                                None => k,
+                            });
+                        }
+                    }
+                }
+                _ => warnings.push(w("unreadable environment section".to_string())),
+            },
+            "working_dir" | "user" | "hostname" | "network_mode" | "mem_limit" | "cpus" | "stop_signal" => {
+                let flag = match key {
+                    "working_dir" => "-w",
+                    "user" => "-u",
+                    "hostname" => "--hostname",
+                    "network_mode" => "--network",
+                    "mem_limit" => "--memory",
+                    "cpus" => "--cpus",
+                    _ => "--stop-signal",
+                };
+                match yaml_scalar(value) {
+                    Some(v) => {
+                        args.push(flag.to_string());
+                        args.push(v);
+                    }
+                    None => warnings.push(w(format!("unreadable {}", key))),
+                }
+            }
+            "privileged" | "init" | "tty" | "stdin_open" | "read_only" => {
+                if value.as_bool() == Some(true) {
+                    args.push(match key {
+                        "privileged" => "--privileged",
+                        "init" => "--init",
+                        "tty" => "-t",
+                        "stdin_open" => "-i",
+                        _ => "--read-only",
+                    }.to_string());
+                }
+            }
+            "entrypoint" => match yaml_words(value) {
+                Some(mut words) if !words.is_empty() => {
+                    args.push("--entrypoint".into());
+                    args.push(words.remove(0));
+                    // docker run only takes the executable, the rest goes in front of the command
+                    trailing.splice(0..0, words);
+                }
+                _ => warnings.push(w("unreadable entrypoint".to_string())),
+            },
+            "command" => match yaml_words(value) {
+                Some(words) => trailing.extend(words),
+                None => warnings.push(w("unreadable command".to_string())),
+            },
+            "restart" => {
+                let policy = yaml_scalar(value).unwrap_or_default();
+                service.restart_policy = match policy.as_str() {
+                    "no" | "false" => "never".to_string(),
+                    "always" | "unless-stopped" => "always".to_string(),
+                    p if p.starts_with("on-failure") => {
+                        if let Some(n) = p.strip_prefix("on-failure:").and_then(|n| n.parse().ok()) {
+                            service.max_restarts = n;
+                        }
+                        "on_failure".to_string()
+                    }
+                    other => {
+                        warnings.push(w(format!("unknown restart policy '{}', imported as never", other)));
+                        "never".to_string()
+                    }
+                };
+            }
+            "healthcheck" => service.health_check = compose_healthcheck(name, &container, value, warnings),
+            "labels" => {
+                warnings.push(w("labels are not passed on".to_string()));
+            }
+            other => warnings.push(w(format!("'{}' is not supported and was ignored", other))),
+        }
+    }
+
+    args.push(image);
+    args.extend(trailing);
+    service.args = args;
+
+    Some(service)
+}
+
+// docker run wants absolute host paths for bind mounts, "./data:/data" is relative to the compose file
+fn absolute_bind_source(volume: &str, base_dir: &Path) -> String {
+    if volume.starts_with("./") || volume.starts_with("../") || volume.starts_with(".:") {
+        let (source, rest) = volume.split_once(':').unwrap_or((volume, ""));
+        let source = base_dir.join(source.trim_start_matches("./"));
+        if rest.is_empty() {
+            source.to_string_lossy().to_string()
+        } else {
+            format!("{}:{}", source.to_string_lossy(), rest)
+        }
+    } else {
+        volume.to_string()
+    }
+}
+
+fn compose_healthcheck(name: &str, container: &str, value: &Value, warnings: &mut Vec<String>) -> Option<HealthCheckConfig> {
+    if value.get("disable").and_then(Value::as_bool) == Some(true) {
+        return None;
+    }
+
+    // test: ["CMD", ...] | ["CMD-SHELL", "..."] | "string" (same as CMD-SHELL)
+    let command = match value.get("test")? {
+        Value::String(s) => format!("docker exec {} sh -c {}", container, shell_words::quote(s)),
+        Value::Sequence(items) => {
+            let words: Vec<String> = items.iter().filter_map(yaml_scalar).collect();
+            match words.split_first() {
+                Some((kind, rest)) if kind == "CMD" => {
+                    format!("docker exec {} {}", container, shell_words::join(rest))
+                }
+                Some((kind, rest)) if kind == "CMD-SHELL" => {
+                    format!("docker exec {} sh -c {}", container, shell_words::quote(&rest.join(" ")))
+                }
+                Some((kind, _)) if kind == "NONE" => return None,
+                _ => {
+                    warnings.push(format!("{}: unreadable healthcheck test", name));
+                    return None;
+                }
+            }
+        }
+        _ => {
+            warnings.push(format!("{}: unreadable healthcheck test", name));
+            return None;
+        }
+    };
+
+    // compose writes durations like 1m30s
+    let duration = |key: &str, default: u64| {
+        value.get(key)
+            .and_then(yaml_scalar)
+            .and_then(|s| parse_compose_duration(&s))
+            .unwrap_or(default)
+    };
+
+    if value.get("start_period").is_some() {
+        warnings.push(format!("{}: healthcheck start_period is not supported", name));
+    }
+
+    Some(HealthCheckConfig {
+        command,
+        interval_secs: duration("interval", 30),
+        timeout_secs: duration("timeout", 30),
+        retries: value.get("retries").and_then(Value::as_u64).unwrap_or(3) as u32,
+    })
+}
+
+fn parse_compose_duration(value: &str) -> Option<u64> {
+    // "1m30s" -> "1m 30s" so the systemd parser can take it
+    let mut spaced = String::new();
+    let mut prev_alpha = false;
+    for c in value.chars() {
+        if c.is_ascii_digit() && prev_alpha {
+            spaced.push(' ');
+        }
+        prev_alpha = c.is_ascii_alphabetic();
+        spaced.push(c);
+    }
+    parse_duration_secs(&spaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn import_error(result: Result<ImportResult, String>) -> String {
+        match result {
+            Ok(_) => panic!("expected the import to fail"),
+            Err(e) => e,
+        }
+    }
+
+    fn has_warning(result: &ImportResult, needle: &str) -> bool {
+        result.warnings.iter().any(|w| w.contains(needle))
+    }
+
+    const WEB_UNIT: &str = "\
+# a comment
+[Unit]
+Description=Web frontend
+After=network.target
+
+[Service]
+Type=simple
+User=www
+WorkingDirectory=-/srv/web
+Environment=\"PORT=8080\" \"GREETING=hello world\"
+Environment=MODE=prod
+ExecStart=-/usr/bin/web --listen 0.0.0.0:8080 \\
+    --title \"My Site\" 'single quoted'
+Restart=on-failure
+RestartSec=500ms
+StartLimitBurst=7
+LimitNOFILE=4096
+
+[Install]
+WantedBy=multi-user.target
+";
+
+    #[test]
+    fn systemd_unit() {
+        let result = import_systemd_unit("web", WEB_UNIT).unwrap();
+        let service = &result.services[0];
+        assert_eq!(service.name, "web");
+        assert_eq!(service.command, "/usr/bin/web");
+        assert_eq!(service.args, vec!["--listen", "0.0.0.0:8080", "--title", "My Site", "single quoted"]);
+        assert_eq!(service.working_dir.as_deref(), Some("/srv/web"));
+        assert_eq!(service.user.as_deref(), Some("www"));
+        assert_eq!(service.env_vars.get("PORT").map(String::as_str), Some("8080"));
+        assert_eq!(service.env_vars.get("GREETING").map(String::as_str), Some("hello world"));
+        assert_eq!(service.env_vars.get("MODE").map(String::as_str), Some("prod"));
+        assert_eq!(service.restart_policy, "on_failure");
+        // sub-second delays round up
+        assert_eq!(service.restart_delay_secs, 1);
+        assert_eq!(service.max_restarts, 7);
+
+        assert!(has_warning(&result, "ExecStart prefix '-' was dropped"));
+        assert!(has_warning(&result, "[Unit] After=network.target"));
+        assert!(has_warning(&result, "[Service] LimitNOFILE=4096"));
+        assert!(!has_warning(&result, "WantedBy"));
+    }
+
+    #[test]
+    fn exec_start_prefixes() {
+        for (line, prefix) in [("+/bin/true", "+"), ("!!/bin/true", "!!"), ("@/bin/true", "@"), (":/bin/true", ":")] {
+            let result = import_systemd_unit("x", &format!("[Service]\nExecStart={}\n", line)).unwrap();
+            assert_eq!(result.services[0].command, "/bin/true");
+            assert!(has_warning(&result, &format!("prefix '{}'", prefix)), "{}", line);
+        }
+
+        let result = import_systemd_unit("x", "[Service]\nExecStart=/bin/echo %i $HOME\n").unwrap();
+        assert!(has_warning(&result, "unit specifiers"));
+        assert!(has_warning(&result, "environment variables"));
+    }
+
+    #[test]
+    fn exec_start_reset_and_repeats() {
+        // an empty ExecStart= clears what came before, like in a drop-in
+        let unit = "[Service]\nExecStart=/bin/old\nExecStart=\nExecStart=/bin/new --flag\nExecStart=/bin/third\n";
+        let result = import_systemd_unit("x", unit).unwrap();
+        assert_eq!(result.services[0].command, "/bin/new");
+        assert_eq!(result.services[0].args, vec!["--flag"]);
+        assert!(has_warning(&result, "ignoring '/bin/third'"));
+    }
+
+    #[test]
+    fn systemd_errors() {
+        assert!(import_error(import_systemd_unit("x", "[Unit]\nDescription=nothing\n")).contains("no ExecStart"));
+        assert!(import_error(import_systemd_unit("x", "[Service]\nExecStart=/bin/sh -c \"unterminated\n")).contains("can't parse ExecStart"));
+    }
+
+    #[test]
+    fn restart_mapping() {
+        let cases = [
+            ("no", "never", false),
+            ("always", "always", false),
+            ("on-failure", "on_failure", false),
+            ("on-abnormal", "on_failure", true),
+            ("on-abort", "on_failure", true),
+            ("on-watchdog", "on_failure", true),
+            ("on-success", "never", true),
+            ("sometimes", "never", true),
+        ];
+        for (value, policy, warns) in cases {
+            let unit = format!("[Service]\nExecStart=/bin/true\nRestart={}\n", value);
+            let result = import_systemd_unit("x", &unit).unwrap();
+            assert_eq!(result.services[0].restart_policy, policy, "Restart={}", value);
+            assert_eq!(has_warning(&result, "Restart="), warns, "Restart={}", value);
+        }
+
+        // systemd defaults to no restarts
+        let result = import_systemd_unit("x", "[Service]\nExecStart=/bin/true\n").unwrap();
+        assert_eq!(result.services[0].restart_policy, "never");
+    }
+
+    #[test]
+    fn template_units() {
+        let result = import_systemd_unit("getty@", "[Service]\nExecStart=/sbin/agetty %I\n").unwrap();
+        assert_eq!(result.services[0].name, "getty");
+        assert!(has_warning(&result, "template units"));
+    }
+
+    #[test]
+    fn unit_file_sections_and_continuations() {
+        let entries = parse_unit_file("; comment\n[Service]\nA=1\nB = two \\\n  three\n[Install]\nC=\n");
+        assert_eq!(entries, vec![
+            ("Service".to_string(), "A".to_string(), "1".to_string()),
+            ("Service".to_string(), "B".to_string(), "two  three".to_string()),
+            ("Install".to_string(), "C".to_string(), String::new()),
+        ]);
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(parse_duration_secs("5"), Some(5));
+        assert_eq!(parse_duration_secs("5s"), Some(5));
+        assert_eq!(parse_duration_secs("1min 30s"), Some(90));
+        assert_eq!(parse_duration_secs("2h"), Some(7200));
+        assert_eq!(parse_duration_secs("100ms"), Some(1));
+        assert_eq!(parse_duration_secs("0.5min"), Some(30));
+        assert_eq!(parse_duration_secs("5 fortnights"), None);
+        assert_eq!(parse_compose_duration("1m30s"), Some(90));
+        assert_eq!(parse_compose_duration("10s"), Some(10));
+    }
+
+    #[test]
+    fn format_detection() {
+        assert_eq!(ImportFormat::detect(Path::new("web.service"), ""), Some(ImportFormat::Systemd));
+        assert_eq!(ImportFormat::detect(Path::new("compose.yaml"), ""), Some(ImportFormat::Compose));
+        assert_eq!(ImportFormat::detect(Path::new("unit"), "# hi\n\n[Unit]\n"), Some(ImportFormat::Systemd));
+        assert_eq!(ImportFormat::detect(Path::new("stack"), "services:\n  a: {}\n"), Some(ImportFormat::Compose));
+        assert_eq!(ImportFormat::detect(Path::new("notes.txt"), "hello"), None);
+    }
+
+    const COMPOSE: &str = r#"
+name: shop
+services:
+  web:
+    image: nginx:1.27
+    ports: ["8080:80"]
+    volumes:
+      - ./html:/usr/share/nginx/html:ro
+      - logs:/var/log/nginx
+    environment:
+      TZ: UTC
+      PASSTHROUGH:
+    restart: unless-stopped
+    command: nginx -g "daemon off;"
+    healthcheck:
+      test: ["CMD-SHELL", "curl -f http://localhost/ || exit 1"]
+      interval: 1m30s
+      timeout: 5s
+      retries: 4
+      start_period: 10s
+  worker:
+    image: shop/worker
+    container_name: shop-worker
+    entrypoint: ["/entry.sh", "--verbose"]
+    command: ["run", "--queue", "orders"]
+    environment: ["QUEUE=orders"]
+    restart: on-failure:3
+    privileged: true
+    labels:
+      tier: backend
+    deploy:
+      replicas: 2
+  builder:
+    build: .
+networks:
+  default: {}
+"#;
+
+    #[test]
+    fn compose_file() {
+        let base = Path::new("/opt/shop");
+        let result = import_compose(COMPOSE, base).unwrap();
+        assert_eq!(result.services.len(), 2);
+
+        let web = &result.services[0];
+        assert_eq!(web.name, "web");
+        assert_eq!(web.command, "/usr/bin/docker");
+        assert_eq!(web.args, vec![
+            "run", "--rm", "--name", "web-daemon",
+            "-p", "8080:80",
+            "-v", "/opt/shop/html:/usr/share/nginx/html:ro",
+            "-v", "logs:/var/log/nginx",
+            "-e", "TZ=UTC",
+            "-e", "PASSTHROUGH",
+            "nginx:1.27", "nginx", "-g", "daemon off;",
+        ]);
+        assert_eq!(web.restart_policy, "always");
+        assert_eq!(web.groups, vec!["shop"]);
+        assert_eq!(web.working_dir.as_deref(), Some("/opt/shop"));
+        let health = web.health_check.as_ref().unwrap();
+        assert_eq!(health.command, "docker exec web-daemon sh -c 'curl -f http://localhost/ || exit 1'");
+        assert_eq!(health.interval_secs, 90);
+        assert_eq!(health.timeout_secs, 5);
+        assert_eq!(health.retries, 4);
+
+        let worker = &result.services[1];
+        assert_eq!(worker.args, vec![
+            "run", "--rm", "--name", "shop-worker",
+            "--entrypoint", "/entry.sh",
+            "-e", "QUEUE=orders",
+            "--privileged",
+            "shop/worker", "--verbose", "run", "--queue", "orders",
+        ]);
+        assert_eq!(worker.restart_policy, "on_failure");
+        assert_eq!(worker.max_restarts, 3);
+
+        assert!(has_warning(&result, "web: healthcheck start_period"));
+        assert!(has_warning(&result, "worker: labels"));
+        assert!(has_warning(&result, "worker: 'deploy' is not supported"));
+        assert!(has_warning(&result, "builder: no image"));
+        assert!(has_warning(&result, "builder: skipped"));
+        assert!(has_warning(&result, "top level 'networks'"));
+    }
+
+    #[test]
+    fn compose_command_forms() {
+        let string_form = import_compose("services:\n  a:\n    image: busybox\n    command: sh -c 'echo hi; sleep 1'\n", Path::new("/p")).unwrap();
+        let list_form = import_compose("services:\n  a:\n    image: busybox\n    command: [sh, -c, 'echo hi; sleep 1']\n", Path::new("/p")).unwrap();
+        assert_eq!(string_form.services[0].args, list_form.services[0].args);
+        assert!(string_form.services[0].args.ends_with(&["busybox".to_string(), "sh".into(), "-c".into(), "echo hi; sleep 1".into()]));
+        // no name: the project is the directory the file is in
+        assert_eq!(string_form.services[0].groups, vec!["p"]);
+    }
+
+    #[test]
+    fn compose_restart_and_healthcheck_variants() {
+        let yaml = "services:\n  a:\n    image: x\n    restart: \"no\"\n    healthcheck:\n      test: [\"CMD\", \"pg_isready\", \"-U\", \"my user\"]\n  b:\n    image: x\n    restart: sometimes\n    healthcheck:\n      disable: true\n  c:\n    image: x\n    healthcheck:\n      test: [\"NONE\"]\n";
+        let result = import_compose(yaml, Path::new("/p")).unwrap();
+        let [a, b, c] = &result.services[..] else {
+            panic!("expected three services");
+        };
+        assert_eq!(a.restart_policy, "never");
+        assert_eq!(a.health_check.as_ref().unwrap().command, "docker exec a-daemon pg_isready -U 'my user'");
+        assert_eq!(a.health_check.as_ref().unwrap().interval_secs, 30);
+        assert_eq!(b.restart_policy, "never");
+        assert!(has_warning(&result, "unknown restart policy 'sometimes'"));
+        assert!(b.health_check.is_none());
+        assert!(c.health_check.is_none());
+    }
+
+    #[test]
+    fn compose_errors() {
+        assert!(import_error(import_compose("services: [", Path::new("/p"))).starts_with("Invalid compose file"));
+        assert!(import_error(import_compose("version: '3'\n", Path::new("/p"))).contains("no services"));
+    }
+
+    #[test]
+    fn bind_sources() {
+        let base = Path::new("/srv/app");
+        assert_eq!(absolute_bind_source("./data:/data", base), "/srv/app/data:/data");
+        assert_eq!(absolute_bind_source("../shared:/shared:ro", base), "/srv/app/../shared:/shared:ro");
+        assert_eq!(absolute_bind_source(".:/src", base), "/srv/app/.:/src");
+        assert_eq!(absolute_bind_source("/abs:/abs", base), "/abs:/abs");
+        assert_eq!(absolute_bind_source("named:/data", base), "named:/data");
+    }
+}
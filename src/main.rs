@@ -3,7 +3,6 @@ use crossterm::event::{self, Event};
 use sysinfo::{System, RefreshKind, Networks, Disks};
 
 mod constants;
-mod types;
 mod utils;
 mod ui;
 mod event_handler;
@@ -11,6 +10,24 @@ mod system_info;
 mod app_state;
 mod theme;
 mod daemon;
+mod notify;
+mod import;
+mod process_net;
+mod process_io;
+mod net_interfaces;
+mod disk_stats;
+mod memory_stats;
+mod pressure;
+mod sensors;
+mod cpu_times;
+mod gpu;
+mod cgroups;
+mod signals;
+mod process_control;
+mod process_details;
+mod threads;
+mod columns;
+mod process_stat;
 
 use clap::{Arg, Command as ClapCommand, ArgAction};
 use std::path::PathBuf;
@@ -21,11 +38,11 @@ use event_handler::*;
 use system_info::*;
 use app_state::*;
 use utils::CircularBuffer;
-use daemon::{run_daemon_mode, DaemonSupervisor};
+use daemon::{run_daemon_mode, DaemonCommand, ServiceAction, ControlTarget, SupervisorHandle, send_control_command, default_config_path, append_service_configs, services_to_toml};
+use import::{import_file, ImportFormat};
 use ctrlc::*;
 
-use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
-use std::thread::{self, JoinHandle};
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 
 /*
     please refrain from taking any comments that dont have proper punctuation as serious
@@ -66,7 +83,7 @@ fn main() -> io::Result<()> {
             Arg::new("integrate")
                 .short('i')
                 .long("integrate")
-                .help("Run daemon in the background while showing the process monitor (requires -d). THIS FEATURE IS EXPERIMENTAL. IT MAY NOT SHUTDOWN WITH THE PROGRAM.")
+                .help("Run daemon in the background while showing the process monitor (requires -d). Supervised services are stopped when r-top exits.")
                 .action(ArgAction::SetTrue)
                 .required(false)
         )
@@ -79,11 +96,47 @@ fn main() -> io::Result<()> {
                 .action(clap::ArgAction::Set)
                 .value_parser(clap::value_parser!(PathBuf))
         )
+        .subcommand(control_subcommand("group", "Start, stop or restart every service in a group on the running daemon."))
+        .subcommand(control_subcommand("service", "Start, stop or restart a single service on the running daemon."))
+        .subcommand(
+            ClapCommand::new("import")
+                .about("Convert a systemd .service file or a docker-compose file into services.toml entries.")
+                .arg(
+                    Arg::new("file")
+                        .help("The unit or compose file to convert.")
+                        .required(true)
+                        .value_parser(clap::value_parser!(PathBuf))
+                )
+                .arg(
+                    Arg::new("format")
+                        .long("format")
+                        .help("Input format, guessed from the file name and contents when omitted.")
+                        .value_parser(["systemd", "compose"])
+                )
+                .arg(
+                    Arg::new("write")
+                        .short('w')
+                        .long("write")
+                        .help("Append the services to the config file (see --config) instead of printing them.")
+                        .action(ArgAction::SetTrue)
+                )
+        )
         .get_matches();
 
+    // before any mode takes over the terminal, the notice is a plain line on stderr
+    daemon::migrate_legacy_config();
+
+    if let Some(("import", sub_matches)) = matches.subcommand() {
+        return run_import_command(sub_matches, matches.get_one::<PathBuf>("config").cloned());
+    }
+
+    if let Some((kind, sub_matches)) = matches.subcommand() {
+        return run_control_command(kind, sub_matches);
+    }
+
     let daemon_mode = matches.get_flag("daemon");
     let integrate_mode = matches.get_flag("integrate");
-    let config_path = matches.get_one::<String>("config").map(PathBuf::from);
+    let config_path = matches.get_one::<PathBuf>("config").cloned();
 
     if let Some(ref path) = config_path {
         println!("Config Path: {:?}", path);
@@ -97,93 +150,126 @@ fn main() -> io::Result<()> {
 
     if daemon_mode && integrate_mode {
         println!("=== STARTING INTEGRATED MODE ===");
-        return run_integrated_mode(config_path);
+        run_integrated_mode(config_path)
     } else if daemon_mode {
         println!("=== STARTING DAEMON MODE ===");
-        return run_daemon_mode_wrapper(config_path);
+        run_daemon_mode_wrapper(config_path)
     } else {
         println!("=== STARTING PROCMON MODE ===");
-        return run_process_monitor();
+        run_process_monitor(config_path, None)
     }
 }
 
-fn run_daemon_mode_wrapper(config_path: Option<PathBuf>) -> io::Result<()> {
-    match run_daemon_mode(config_path) {
-        Ok(_) => Ok(()),
-        Err(e) => {
-            eprintln!("Error running daemon mode: {}", e);
-            Err(io::Error::new(io::ErrorKind::Other, e.to_string()))
-        }
-    }
+fn control_subcommand(name: &'static str, about: &'static str) -> ClapCommand {
+    ClapCommand::new(name)
+        .about(about)
+        .arg(
+            Arg::new("action")
+                .help("What to do with the target.")
+                .required(true)
+                .value_parser(["start", "stop", "restart"])
+        )
+        .arg(
+            Arg::new("name")
+                .help("Name of the service or group as written in services.toml.")
+                .required(true)
+        )
 }
 
-fn run_integrated_mode(config_path: Option<PathBuf>) -> io::Result<()> {
-    println!("Starting r-top and b-daemon in integration mode.");
-
-    let shutdown_signal = Arc::new(AtomicBool::new(false));
-    let shutdown_signal_clone = shutdown_signal.clone();
-
-    let daemon_config = config_path.clone();
-    let daemon_handle: JoinHandle<Result<(), Box<dyn std::error::Error + Send>>> = thread::spawn(move || {
-        run_daemon_mode_silent(daemon_config, shutdown_signal_clone)
-    });
-
-    thread::sleep(Duration::from_millis(500));
+fn run_control_command(kind: &str, matches: &clap::ArgMatches) -> io::Result<()> {
+    let action = matches.get_one::<String>("action")
+        .and_then(|a| ServiceAction::parse(a))
+        .expect("clap restricts action values");
+    let name = matches.get_one::<String>("name").cloned().unwrap_or_default();
 
-    let result = run_process_monitor();
-
-    shutdown_signal.store(true, Ordering::Relaxed);
+    let target = if kind == "group" {
+        ControlTarget::Group(name)
+    } else {
+        ControlTarget::Service(name)
+    };
 
-    match daemon_handle.join() {
-        Ok(daemon_result) => {
-            if let Err(e) = daemon_result {
-                eprintln!("Daemon thread error: {}", e);
-            }
+    match send_control_command(&DaemonCommand { action, target }) {
+        Ok(msg) => {
+            println!("{}", msg);
+            Ok(())
         }
-        Err(_) => {
-            eprintln!("Failed to join daemon thread.")
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
         }
     }
-
-    result
 }
 
-fn run_daemon_mode_silent(config_path: Option<PathBuf>, shutdown_signal: Arc<AtomicBool>) -> Result<(), Box<dyn std::error::Error + Send>> {
-    use daemon::DaemonSupervisor;
+fn run_import_command(matches: &clap::ArgMatches, config_path: Option<PathBuf>) -> io::Result<()> {
+    let file = matches.get_one::<PathBuf>("file").cloned().unwrap_or_default();
+    let format = matches.get_one::<String>("format").and_then(|f| ImportFormat::parse(f));
 
-    let mut supervisor = DaemonSupervisor::new(config_path);
-    supervisor.load_config().map_err(|e| -> Box<dyn std::error::Error + Send> {
-        Box::new(std::io::Error::new(std::io::ErrorKind::Other, e.to_string()))
-    })?;
+    let result = match import_file(&file, format) {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+    };
 
-    let service_names: Vec<String> = supervisor.services.keys().cloned().collect();
-    for name in &service_names {
-        let _ = supervisor.start_service(&name);
+    // warnings go to stderr so stdout stays valid TOML
+    for warning in &result.warnings {
+        eprintln!("warning: {}", warning);
     }
 
-    while !shutdown_signal.load(Ordering::Relaxed) {
-        supervisor.check_services_silent();
+    if result.services.is_empty() {
+        eprintln!("Error: nothing to import");
+        std::process::exit(1);
+    }
 
-        for _ in 0..50 {
-            if shutdown_signal.load(Ordering::Relaxed) {
-                break;
-            }
-            std::thread::sleep(Duration::from_millis(100));
+    if matches.get_flag("write") {
+        let path = config_path.unwrap_or_else(default_config_path);
+        let names: Vec<String> = result.services.iter().map(|s| s.name.clone()).collect();
+        if let Err(e) = append_service_configs(&path, result.services) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
         }
+        println!("Added {} to {}", names.join(", "), path.display());
+    } else {
+        let toml = services_to_toml(result.services).map_err(io::Error::other)?;
+        print!("{}", toml);
     }
 
-    for name in &service_names{
-        if let Err(e) = supervisor.stop_service_silent(name) {
-            eprintln!("Error stopping service '{}': {}", name, e)
+    Ok(())
+}
+
+fn run_daemon_mode_wrapper(config_path: Option<PathBuf>) -> io::Result<()> {
+    match run_daemon_mode(config_path) {
+        Ok(_) => Ok(()),
+        Err(e) => {
+            eprintln!("Error running daemon mode: {}", e);
+            Err(io::Error::new(io::ErrorKind::Other, e.to_string()))
         }
     }
+}
 
-    Ok(())
+fn run_integrated_mode(config_path: Option<PathBuf>) -> io::Result<()> {
+    println!("Starting r-top and b-daemon in integration mode.");
+
+    let supervisor = SupervisorHandle::spawn(config_path.clone()).map_err(|e| {
+        eprintln!("Error starting daemon supervisor: {}", e);
+        io::Error::other(e)
+    })?;
+
+    // the handle is moved into the TUI's state, dropping it there stops every service and joins the thread
+    run_process_monitor(config_path, Some(supervisor))
 }
 
-fn run_process_monitor() -> io::Result<()> {
+fn run_process_monitor(config_path: Option<PathBuf>, supervisor: Option<SupervisorHandle>) -> io::Result<()> {
+    // SIGINT/SIGTERM from outside should still go through the normal exit path so supervised services get stopped
+    let shutdown_signal = if supervisor.is_some() { Some(setup_signal_handler()) } else { None };
+
     let mut terminal = ratatui::init();
     let mut app_state = AppState::new();
+    app_state.supervisor = supervisor;
+    if let Some(path) = config_path {
+        app_state.daemon_config_path = path;
+    }
 
     // Load user settings (ignore errors, keep defaults if missing)
     if let Err(e) = app_state.load_user_settings() {
@@ -199,21 +285,51 @@ fn run_process_monitor() -> io::Result<()> {
     let mut system = System::new_with_specifics(refresh);
     let mut networks = Networks::new_with_refreshed_list();
     let mut disks = Disks::new_with_refreshed_list();
+    app_state.refresh_disks(&disks, 0.0);
+    app_state.memory_monitor.refresh(&system, 0.0);
+    app_state.pressure.refresh();
+    app_state.sensors.refresh(&system);
+    app_state.cpu_times.refresh();
+    app_state.gpu.refresh(0.0);
+    app_state.refresh_cgroups(&system);
+    app_state.refresh_process_stats(&system);
 
     // Replace VecDeque with CircularBuffer - much more memory efficient!
     let mut cpu_history: Vec<CircularBuffer<f32>> = vec![];
     let mut last_refresh = Instant::now();
 
     loop {
+        if shutdown_signal.as_ref().is_some_and(|s| s.load(Ordering::Relaxed)) {
+            break;
+        }
+
         let now = Instant::now();
         if now.duration_since(last_refresh) >= app_state.refresh_interval {
             /*
             UPDATE: still wondering if I should fix the refresh_all() method
              */
             system.refresh_all();
-            networks.refresh(false);
-            disks.refresh(false);
+            networks.refresh(true);
+            disks.refresh(true);
+            app_state.net_usage.refresh();
+            let elapsed = now.duration_since(last_refresh).as_secs_f64();
+            app_state.io_usage.refresh(&system, elapsed);
+            app_state.memory_monitor.refresh(&system, elapsed);
+            app_state.pressure.refresh();
+            app_state.sensors.refresh(&system);
+            app_state.cpu_times.refresh();
+            app_state.gpu.refresh(elapsed);
+            app_state.refresh_cgroups(&system);
+            app_state.refresh_process_stats(&system);
+            app_state.prune_tags(&system);
+            app_state.refresh_details(&system);
+            app_state.refresh_threads();
+            app_state.refresh_network(&networks, elapsed);
+            app_state.refresh_disks(&disks, elapsed);
+            app_state.process_cache.refreshed();
             app_state.invalidate_rows_cache();
+            // the search keeps indices into the sorted list, which just changed
+            app_state.invalidate_search_cache();
             last_refresh = now;
         }
 
@@ -225,11 +341,13 @@ fn run_process_monitor() -> io::Result<()> {
         }
 
         update_cpu_history(&mut cpu_history, &system);
-        let processes = sort_processes_cached(&system, &app_state.sort_category, &mut app_state.process_cache, &app_state.search_active);
+        app_state.poll_supervisor_responses();
+        app_state.poll_signal_followups();
+        let processes = sorted_processes(&system, &mut app_state);
         
         terminal.draw(|frame| {
             app_state.update_terminal_area(frame.size());  //-> should i seperate this from render_ui?
-            render_ui(frame, &system, &networks, &disks, &processes, &cpu_history, &mut app_state);
+            render_ui(frame, &system, &processes, &cpu_history, &mut app_state);
         })?;
 
         if event::poll(Duration::from_millis(16))? {
@@ -244,5 +362,10 @@ fn run_process_monitor() -> io::Result<()> {
     }
 
     ratatui::restore();
+
+    if let Some(mut supervisor) = app_state.supervisor.take() {
+        println!("Stopping supervised services...");
+        supervisor.shutdown();
+    }
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+use crate::system_info::memory_used_gib;
+
+/*
+    system memory breakdown, the same numbers free(1) shows:
+    - /proc/meminfo for the sizes (kB), used = total - available like newer procps, cached includes SReclaimable
+    - /proc/vmstat pswpin/pswpout are pages swapped since boot, diffed into rates
+    anywhere without /proc we fall back to what sysinfo knows and leave cached/buffers at 0
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryStats {
+    pub total: u64, // bytes
+    pub used: u64,
+    pub free: u64,
+    pub available: u64,
+    pub cached: u64,
+    pub buffers: u64,
+    pub shared: u64,
+    pub swap_total: u64,
+    pub swap_used: u64,
+    pub swap_in_rate: f64, // bytes/s
+    pub swap_out_rate: f64,
+}
+
+pub struct MemoryMonitor {
+    pub stats: MemoryStats,
+    pub detailed: bool, // false when /proc/meminfo wasn't readable
+    prev_swapped_pages: Option<(u64, u64)>,
+    page_size: u64,
+}
+
+impl MemoryMonitor {
+    pub fn new() -> Self {
+        Self {
+            stats: MemoryStats::default(),
+            detailed: cfg!(target_os = "linux"),
+            prev_swapped_pages: None,
+            page_size: unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64,
+        }
+    }
+
+    // call right after system.refresh_all(), elapsed is the time since the previous refresh
+    pub fn refresh(&mut self, system: &System, elapsed_secs: f64) {
+        let (in_rate, out_rate) = self.swap_rates(elapsed_secs);
+
+        self.stats = match read_meminfo() {
+            Some(info) => {
+                self.detailed = true;
+                MemoryStats {
+                    swap_in_rate: in_rate,
+                    swap_out_rate: out_rate,
+                    ..meminfo_stats(&info)
+                }
+            }
+            None => {
+                self.detailed = false;
+                MemoryStats {
+                    total: system.total_memory(),
+                    used: (memory_used_gib(system) * 1024.0 * 1024.0 * 1024.0) as u64,
+                    free: system.free_memory(),
+                    available: system.available_memory(),
+                    swap_total: system.total_swap(),
+                    swap_used: system.used_swap(),
+                    ..Default::default()
+                }
+            }
+        };
+    }
+
+    fn swap_rates(&mut self, elapsed_secs: f64) -> (f64, f64) {
+        let Some((pages_in, pages_out)) = read_swapped_pages() else {
+            return (0.0, 0.0);
+        };
+        let rates = match self.prev_swapped_pages {
+            Some((prev_in, prev_out)) if elapsed_secs > 0.0 => (
+                page_rate(prev_in, pages_in, self.page_size, elapsed_secs),
+                page_rate(prev_out, pages_out, self.page_size, elapsed_secs),
+            ),
+            _ => (0.0, 0.0),
+        };
+        self.prev_swapped_pages = Some((pages_in, pages_out));
+        rates
+    }
+}
+
+// bytes/s from two readings of a page counter, one that went backwards (wrapped) counts as nothing happened
+fn page_rate(before: u64, now: u64, page_size: u64, elapsed_secs: f64) -> f64 {
+    now.saturating_sub(before) as f64 * page_size as f64 / elapsed_secs
+}
+
+// sizes from parsed /proc/meminfo, keys an older kernel doesn't have count as 0
+fn meminfo_stats(info: &HashMap<String, u64>) -> MemoryStats {
+    let kb = |key: &str| info.get(key).copied().unwrap_or(0) * 1024;
+    let total = kb("MemTotal");
+    MemoryStats {
+        total,
+        used: total.saturating_sub(kb("MemAvailable")),
+        free: kb("MemFree"),
+        available: kb("MemAvailable"),
+        cached: kb("Cached") + kb("SReclaimable"),
+        buffers: kb("Buffers"),
+        shared: kb("Shmem"),
+        swap_total: kb("SwapTotal"),
+        swap_used: kb("SwapTotal").saturating_sub(kb("SwapFree")),
+        ..Default::default()
+    }
+}
+
+// "Key:   1234 kB" lines, values left in kB
+fn read_meminfo() -> Option<HashMap<String, u64>> {
+    let content = fs::read_to_string("/proc/meminfo").ok()?;
+    Some(parse_kb_fields(&content))
+}
+
+fn read_swapped_pages() -> Option<(u64, u64)> {
+    parse_swapped_pages(&fs::read_to_string("/proc/vmstat").ok()?)
+}
+
+fn parse_swapped_pages(content: &str) -> Option<(u64, u64)> {
+    let mut pages_in = None;
+    let mut pages_out = None;
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next().and_then(|v| v.parse::<u64>().ok())) {
+            (Some("pswpin"), Some(value)) => pages_in = Some(value),
+            (Some("pswpout"), Some(value)) => pages_out = Some(value),
+            _ => {}
+        }
+    }
+    Some((pages_in?, pages_out?))
+}
+
+fn parse_kb_fields(content: &str) -> HashMap<String, u64> {
+    content.lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let value = rest.split_whitespace().next()?.parse::<u64>().ok()?;
+            Some((key.to_string(), value))
+        })
+        .collect()
+}
+
+/*
+    what a single process really costs, from /proc/<pid>/smaps_rollup (linux 4.14+):
+    - RSS counts shared pages in full for every process mapping them
+    - PSS splits shared pages between the processes sharing them
+    - USS is only the private pages, what you'd get back by killing it
+    the kernel walks every mapping to produce this so it's only read for the selected process
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessMemory {
+    pub rss: u64, // bytes
+    pub pss: u64,
+    pub uss: u64,
+    pub swap: u64,
+}
+
+pub struct ProcessMemoryCache {
+    pid: Option<u32>,
+    read_at: Instant,
+    memory: Option<ProcessMemory>,
+}
+
+impl ProcessMemoryCache {
+    pub fn new() -> Self {
+        Self {
+            pid: None,
+            read_at: Instant::now(),
+            memory: None,
+        }
+    }
+
+    // None when smaps_rollup isn't there or belongs to another user (needs root or ptrace access)
+    pub fn get(&mut self, pid: u32, max_age: Duration) -> Option<ProcessMemory> {
+        if self.pid != Some(pid) || self.read_at.elapsed() >= max_age {
+            self.pid = Some(pid);
+            self.read_at = Instant::now();
+            self.memory = read_smaps_rollup(pid);
+        }
+        self.memory
+    }
+}
+
+fn read_smaps_rollup(pid: u32) -> Option<ProcessMemory> {
+    let content = fs::read_to_string(format!("/proc/{}/smaps_rollup", pid)).ok()?;
+    Some(parse_smaps_rollup(&content))
+}
+
+fn parse_smaps_rollup(content: &str) -> ProcessMemory {
+    let fields = parse_kb_fields(content);
+    let kb = |key: &str| fields.get(key).copied().unwrap_or(0) * 1024;
+    ProcessMemory {
+        rss: kb("Rss"),
+        pss: kb("Pss"),
+        uss: kb("Private_Clean") + kb("Private_Dirty") + kb("Private_Hugetlb"),
+        swap: kb("Swap"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MEMINFO: &str = "\
+MemTotal:       16318412 kB
+MemFree:         1203456 kB
+MemAvailable:    9876543 kB
+Buffers:          345678 kB
+Cached:          7654321 kB
+SwapCached:        12345 kB
+Shmem:            456789 kB
+SReclaimable:     234567 kB
+SwapTotal:       8388604 kB
+SwapFree:        8000000 kB
+HugePages_Total:       0
+Hugepagesize:       2048 kB
+";
+
+    #[test]
+    fn meminfo_breakdown() {
+        let info = parse_kb_fields(MEMINFO);
+        // unit-less lines are still numbers
+        assert_eq!(info["HugePages_Total"], 0);
+
+        let stats = meminfo_stats(&info);
+        assert_eq!(stats.total, 16318412 * 1024);
+        assert_eq!(stats.free, 1203456 * 1024);
+        assert_eq!(stats.available, 9876543 * 1024);
+        assert_eq!(stats.used, (16318412 - 9876543) * 1024);
+        assert_eq!(stats.buffers, 345678 * 1024);
+        assert_eq!(stats.cached, (7654321 + 234567) * 1024);
+        assert_eq!(stats.shared, 456789 * 1024);
+        assert_eq!(stats.swap_total, 8388604 * 1024);
+        assert_eq!(stats.swap_used, (8388604 - 8000000) * 1024);
+    }
+
+    #[test]
+    fn meminfo_missing_fields() {
+        // pre-3.14 kernels have no MemAvailable, 2.6.19 no SReclaimable, and a swapless box no Swap lines
+        let stats = meminfo_stats(&parse_kb_fields("MemTotal: 1000 kB\nMemFree: 400 kB\nCached: 100 kB\ngarbage line\nBroken: x kB\n"));
+        assert_eq!(stats.total, 1000 * 1024);
+        assert_eq!(stats.available, 0);
+        assert_eq!(stats.used, 1000 * 1024);
+        assert_eq!(stats.cached, 100 * 1024);
+        assert_eq!(stats.swap_total, 0);
+        assert_eq!(stats.swap_used, 0);
+    }
+
+    #[test]
+    fn vmstat_swap_counters() {
+        let vmstat = "nr_free_pages 300000\npswpin 1234\npswpout 5678\npgfault 99\n";
+        assert_eq!(parse_swapped_pages(vmstat), Some((1234, 5678)));
+        // both are needed, a kernel without swap support has neither
+        assert_eq!(parse_swapped_pages("nr_free_pages 1\npswpin 3\n"), None);
+        assert_eq!(parse_swapped_pages(""), None);
+    }
+
+    #[test]
+    fn swap_rates() {
+        assert_eq!(page_rate(1000, 1100, 4096, 2.0), 100.0 * 4096.0 / 2.0);
+        assert_eq!(page_rate(1000, 1000, 4096, 2.0), 0.0);
+        // wrapped or reset counter
+        assert_eq!(page_rate(u64::MAX - 3, 10, 4096, 1.0), 0.0);
+    }
+
+    #[test]
+    fn smaps_rollup() {
+        let content = "\
+55d0c0a00000-7ffd5e1fe000 ---p 00000000 00:00 0                          [rollup]
+Rss:               12288 kB
+Pss:                6144 kB
+Pss_Anon:           4096 kB
+Shared_Clean:       4096 kB
+Shared_Dirty:          0 kB
+Private_Clean:      2048 kB
+Private_Dirty:      6144 kB
+Swap:                512 kB
+";
+        let memory = parse_smaps_rollup(content);
+        assert_eq!(memory.rss, 12288 * 1024);
+        assert_eq!(memory.pss, 6144 * 1024);
+        // Private_Hugetlb is missing here, it only shows up on kernels with hugetlb accounting
+        assert_eq!(memory.uss, (2048 + 6144) * 1024);
+        assert_eq!(memory.swap, 512 * 1024);
+    }
+}
@@ -0,0 +1,127 @@
+use sysinfo::Networks;
+use crate::constants::HISTORY_LEN;
+use crate::utils::CircularBuffer;
+
+/*
+    per-interface throughput. sysinfo's received()/transmitted() are bytes since the previous refresh,
+    so rates need the real time between refreshes. session counters start when r-top does
+ */
+pub struct InterfaceStats {
+    pub name: String,
+    pub rx_rate: f64, // bytes/s
+    pub tx_rate: f64,
+    pub rx_history: CircularBuffer<u64>,
+    pub tx_history: CircularBuffer<u64>,
+    pub session_rx: u64,
+    pub session_tx: u64,
+    pub total_rx: u64, // since boot
+    pub total_tx: u64,
+    pub packets_rx: u64,
+    pub packets_tx: u64,
+    pub errors_rx: u64,
+    pub errors_tx: u64,
+    pub addresses: Vec<String>, // "addr/prefix"
+    pub mac: String,
+}
+
+impl InterfaceStats {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            rx_rate: 0.0,
+            tx_rate: 0.0,
+            rx_history: CircularBuffer::new(HISTORY_LEN),
+            tx_history: CircularBuffer::new(HISTORY_LEN),
+            session_rx: 0,
+            session_tx: 0,
+            total_rx: 0,
+            total_tx: 0,
+            packets_rx: 0,
+            packets_tx: 0,
+            errors_rx: 0,
+            errors_tx: 0,
+            addresses: Vec::new(),
+            mac: String::new(),
+        }
+    }
+}
+
+pub struct NetworkMonitor {
+    interfaces: Vec<InterfaceStats>, // sorted by name so cycling order is stable
+}
+
+impl NetworkMonitor {
+    pub fn new() -> Self {
+        Self { interfaces: Vec::new() }
+    }
+
+    // call right after networks.refresh(), elapsed is the time since the previous refresh
+    pub fn refresh(&mut self, networks: &Networks, elapsed_secs: f64) {
+        let elapsed = elapsed_secs.max(0.001);
+
+        // interfaces can come and go (docker0, wg0, usb tethering...)
+        self.interfaces.retain(|iface| networks.contains_key(&iface.name));
+
+        for (name, data) in networks.iter() {
+            let index = match self.interfaces.iter().position(|i| &i.name == name) {
+                Some(index) => index,
+                None => {
+                    self.interfaces.push(InterfaceStats::new(name));
+                    self.interfaces.len() - 1
+                }
+            };
+            let iface = &mut self.interfaces[index];
+
+            iface.rx_rate = data.received() as f64 / elapsed;
+            iface.tx_rate = data.transmitted() as f64 / elapsed;
+            iface.rx_history.push(iface.rx_rate as u64);
+            iface.tx_history.push(iface.tx_rate as u64);
+            iface.session_rx += data.received();
+            iface.session_tx += data.transmitted();
+            iface.total_rx = data.total_received();
+            iface.total_tx = data.total_transmitted();
+            iface.packets_rx = data.total_packets_received();
+            iface.packets_tx = data.total_packets_transmitted();
+            iface.errors_rx = data.total_errors_on_received();
+            iface.errors_tx = data.total_errors_on_transmitted();
+            iface.addresses = data.ip_networks().iter().map(|ip| format!("{}/{}", ip.addr, ip.prefix)).collect();
+            iface.mac = data.mac_address().to_string();
+        }
+
+        self.interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    pub fn get(&self, name: &str) -> Option<&InterfaceStats> {
+        self.interfaces.iter().find(|i| i.name == name)
+    }
+
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.interfaces.iter().position(|i| i.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.interfaces.len()
+    }
+
+    // neighbour of `current` in name order, wrapping around. unknown names start from the top
+    pub fn cycle(&self, current: &str, forward: bool) -> Option<String> {
+        let len = self.interfaces.len();
+        if len == 0 {
+            return None;
+        }
+        let next = match self.position(current) {
+            Some(pos) if forward => (pos + 1) % len,
+            Some(pos) => (pos + len - 1) % len,
+            None => 0,
+        };
+        Some(self.interfaces[next].name.clone())
+    }
+
+    // first interface that isn't loopback, for when the configured one doesn't exist on this machine
+    pub fn preferred(&self) -> Option<String> {
+        self.interfaces.iter()
+            .find(|i| i.name != "lo")
+            .or(self.interfaces.first())
+            .map(|i| i.name.clone())
+    }
+}
@@ -0,0 +1,374 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Command, Stdio};
+use std::sync::Once;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+/*
+    failure notifications for the daemon supervisor.
+    configured globally with a top level [notify] table in services.toml, or per service with [services.notify]
+    (a service level table replaces the global one for that service, it doesn't merge)
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotifyConfig {
+    pub command: Option<String>, // run through `sh -c`, event details are in R_TOP_* env vars
+    pub webhook: Option<String>, // plain http:// only, gets a JSON POST
+    #[serde(default)]
+    pub syslog: bool,
+    #[serde(default = "default_min_interval")]
+    pub min_interval_secs: u64, // per service + event, so a flapping service doesn't spam
+    #[serde(default = "default_crash_loop_restarts")]
+    pub crash_loop_restarts: u32,
+    #[serde(default = "default_crash_loop_window")]
+    pub crash_loop_window_secs: u64,
+}
+
+fn default_min_interval() -> u64 {
+    300
+}
+
+fn default_crash_loop_restarts() -> u32 {
+    3
+}
+
+fn default_crash_loop_window() -> u64 {
+    120
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NotifyEvent {
+    Failed,
+    Unhealthy,
+    CrashLoop,
+}
+
+impl NotifyEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotifyEvent::Failed => "failed",
+            NotifyEvent::Unhealthy => "unhealthy",
+            NotifyEvent::CrashLoop => "crash_loop",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceEvent {
+    pub service: String,
+    pub event: &'static str,
+    pub pid: Option<u32>,
+    pub restart_count: u32,
+    pub message: String,
+    pub timestamp: u64,
+}
+
+impl ServiceEvent {
+    pub fn new(service: &str, event: NotifyEvent, pid: Option<u32>, restart_count: u32, message: String) -> Self {
+        Self {
+            service: service.to_string(),
+            event: event.as_str(),
+            pid,
+            restart_count,
+            message,
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs()),
+        }
+    }
+}
+
+pub struct Notifier {
+    global: Option<NotifyConfig>,
+    last_sent: HashMap<(String, &'static str), Instant>,
+    suppressed: HashMap<(String, &'static str), u32>,
+    pub verbose: bool, // false when sharing the terminal with the TUI
+}
+
+impl Notifier {
+    pub fn new(global: Option<NotifyConfig>) -> Self {
+        Self {
+            global,
+            last_sent: HashMap::new(),
+            suppressed: HashMap::new(),
+            verbose: true,
+        }
+    }
+
+    pub fn config_for<'a>(&'a self, service: Option<&'a NotifyConfig>) -> Option<&'a NotifyConfig> {
+        service.or(self.global.as_ref())
+    }
+
+    pub fn notify(&mut self, event: ServiceEvent, service_config: Option<&NotifyConfig>) {
+        self.notify_at(event, service_config, Instant::now());
+    }
+
+    // `now` comes in from outside so the rate limit can be tested without sleeping, returns what was sent
+    fn notify_at(&mut self, event: ServiceEvent, service_config: Option<&NotifyConfig>, now: Instant) -> Option<ServiceEvent> {
+        let config = service_config.or(self.global.as_ref()).cloned()?;
+
+        let key = (event.service.clone(), event.event);
+        let interval = Duration::from_secs(config.min_interval_secs);
+
+        if self.last_sent.get(&key).is_some_and(|last| now.duration_since(*last) < interval) {
+            *self.suppressed.entry(key).or_insert(0) += 1;
+            return None;
+        }
+
+        let mut event = event;
+        if let Some(count) = self.suppressed.remove(&key) {
+            event.message = format!("{} ({} similar event(s) suppressed)", event.message, count);
+        }
+        self.last_sent.insert(key, now);
+
+        if self.verbose {
+            println!("Notify: service '{}' {}: {}", event.service, event.event, event.message);
+        }
+
+        if let Some(command) = &config.command {
+            run_command_hook(command, &event, self.verbose);
+        }
+        if let Some(url) = &config.webhook {
+            post_webhook(url, &event, self.verbose);
+        }
+        if config.syslog {
+            write_syslog(&event);
+        }
+        Some(event)
+    }
+}
+
+fn run_command_hook(command: &str, event: &ServiceEvent, verbose: bool) {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .env("R_TOP_SERVICE", &event.service)
+        .env("R_TOP_EVENT", event.event)
+        .env("R_TOP_PID", event.pid.map_or(String::new(), |p| p.to_string()))
+        .env("R_TOP_RESTARTS", event.restart_count.to_string())
+        .env("R_TOP_MESSAGE", &event.message)
+        .env("R_TOP_TIMESTAMP", event.timestamp.to_string())
+        // the hook must not scribble over the TUI (or the daemon's own log)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // wait on a separate thread so a slow hook can't stall the supervisor (and the child gets reaped)
+    std::thread::spawn(move || match cmd.status() {
+        Ok(status) if !status.success() && verbose => eprintln!("Notify command exited with {}", status),
+        Err(e) if verbose => eprintln!("Failed to run notify command: {}", e),
+        _ => {}
+    });
+}
+
+fn post_webhook(url: &str, event: &ServiceEvent, verbose: bool) {
+    let url = url.to_string();
+    let body = serde_json::to_string(event).unwrap_or_default();
+
+    std::thread::spawn(move || {
+        if let (Err(e), true) = (send_http_post(&url, &body), verbose) {
+            eprintln!("Webhook {} failed: {}", url, e);
+        }
+    });
+}
+
+// minimal HTTP/1.1 POST, enough for a local listener or a chat webhook relay without pulling in a client crate
+pub fn send_http_post(url: &str, body: &str) -> Result<u16, String> {
+    let rest = url.strip_prefix("http://")
+        .ok_or_else(|| format!("only http:// webhooks are supported, got '{}'", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let address = if authority.contains(':') { authority.to_string() } else { format!("{}:80", authority) };
+
+    let socket_addr = address.to_socket_addrs()
+        .map_err(|e| format!("resolve {}: {}", address, e))?
+        .next()
+        .ok_or_else(|| format!("no address for {}", address))?;
+
+    let mut stream = TcpStream::connect_timeout(&socket_addr, Duration::from_secs(3))
+        .map_err(|e| format!("connect {}: {}", address, e))?;
+    stream.set_read_timeout(Some(Duration::from_secs(5))).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(Duration::from_secs(5))).map_err(|e| e.to_string())?;
+
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: r-top\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path, authority, body.len(), body
+    );
+    stream.write_all(request.as_bytes()).map_err(|e| format!("send: {}", e))?;
+
+    // only the status line matters, read_line keeps going across short reads until the \r\n
+    let mut status_line = String::new();
+    BufReader::new(stream).read_line(&mut status_line).map_err(|e| format!("read: {}", e))?;
+    let code = status_line.split_whitespace().nth(1)
+        .and_then(|c| c.parse::<u16>().ok())
+        .ok_or_else(|| format!("bad response '{}'", status_line.trim_end()))?;
+
+    if (200..300).contains(&code) {
+        Ok(code)
+    } else {
+        Err(format!("HTTP {}", code))
+    }
+}
+
+fn write_syslog(event: &ServiceEvent) {
+    static OPEN: Once = Once::new();
+    OPEN.call_once(|| unsafe {
+        libc::openlog(c"r-top".as_ptr(), libc::LOG_PID, libc::LOG_DAEMON);
+    });
+
+    let line = format!("service '{}' {}: {}", event.service, event.event, event.message);
+    if let Ok(msg) = CString::new(line) {
+        unsafe {
+            libc::syslog(libc::LOG_WARNING, c"%s".as_ptr(), msg.as_ptr());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+
+    // accepts one connection, hands back everything it received and answers with `response` in two writes
+    fn serve_once(response: &'static str) -> (String, std::thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/r-top", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut received = Vec::new();
+            let mut buf = [0u8; 1024];
+            // headers first, then as many body bytes as Content-Length says
+            loop {
+                let n = stream.read(&mut buf).unwrap();
+                received.extend_from_slice(&buf[..n]);
+                let text = String::from_utf8_lossy(&received).to_string();
+                if let Some(end) = text.find("\r\n\r\n") {
+                    let length = text.lines()
+                        .find_map(|l| l.strip_prefix("Content-Length: "))
+                        .and_then(|v| v.trim().parse::<usize>().ok())
+                        .unwrap_or(0);
+                    if received.len() >= end + 4 + length {
+                        break;
+                    }
+                }
+                if n == 0 {
+                    break;
+                }
+            }
+            let (first, rest) = response.split_at(5);
+            stream.write_all(first.as_bytes()).unwrap();
+            stream.flush().unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            stream.write_all(rest.as_bytes()).unwrap();
+            String::from_utf8(received).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn posts_json_and_parses_a_split_status_line() {
+        let (url, server) = serve_once("HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n");
+        let body = r#"{"service":"web","event":"crashed"}"#;
+        assert_eq!(send_http_post(&url, body), Ok(204));
+
+        let request = server.join().unwrap();
+        let (head, sent_body) = request.split_once("\r\n\r\n").unwrap();
+        let mut lines = head.lines();
+        assert_eq!(lines.next(), Some("POST /hooks/r-top HTTP/1.1"));
+        let headers: Vec<&str> = lines.collect();
+        assert!(headers.iter().any(|h| h.starts_with("Host: 127.0.0.1:")));
+        assert!(headers.contains(&"Content-Type: application/json"));
+        assert!(headers.contains(&format!("Content-Length: {}", body.len()).as_str()));
+        assert!(headers.contains(&"Connection: close"));
+        assert_eq!(sent_body, body);
+    }
+
+    #[test]
+    fn non_2xx_is_an_error() {
+        let (url, server) = serve_once("HTTP/1.1 500 Internal Server Error\r\n\r\n");
+        assert_eq!(send_http_post(&url, "{}"), Err("HTTP 500".to_string()));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn garbage_response_is_reported() {
+        let (url, server) = serve_once("hello there\r\n");
+        assert!(send_http_post(&url, "{}").unwrap_err().starts_with("bad response"));
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn only_http_urls() {
+        assert!(send_http_post("https://example.com/hook", "{}").is_err());
+    }
+
+    // no command, webhook or syslog, so only the rate limiting is exercised
+    fn quiet_config(min_interval_secs: u64) -> NotifyConfig {
+        NotifyConfig {
+            command: None,
+            webhook: None,
+            syslog: false,
+            min_interval_secs,
+            crash_loop_restarts: default_crash_loop_restarts(),
+            crash_loop_window_secs: default_crash_loop_window(),
+        }
+    }
+
+    fn quiet_notifier(min_interval_secs: u64) -> Notifier {
+        let mut notifier = Notifier::new(Some(quiet_config(min_interval_secs)));
+        notifier.verbose = false;
+        notifier
+    }
+
+    fn event(service: &str, kind: NotifyEvent) -> ServiceEvent {
+        ServiceEvent::new(service, kind, Some(42), 1, "exited with 1".to_string())
+    }
+
+    #[test]
+    fn repeats_within_min_interval_are_suppressed_and_counted() {
+        let mut notifier = quiet_notifier(60);
+        let start = Instant::now();
+
+        let first = notifier.notify_at(event("web", NotifyEvent::Failed), None, start).unwrap();
+        assert_eq!(first.message, "exited with 1");
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), None, start + Duration::from_secs(10)).is_none());
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), None, start + Duration::from_secs(59)).is_none());
+
+        // the first one after the interval reports how many were held back, then the count starts over
+        let next = notifier.notify_at(event("web", NotifyEvent::Failed), None, start + Duration::from_secs(60)).unwrap();
+        assert_eq!(next.message, "exited with 1 (2 similar event(s) suppressed)");
+        let after = notifier.notify_at(event("web", NotifyEvent::Failed), None, start + Duration::from_secs(120)).unwrap();
+        assert_eq!(after.message, "exited with 1");
+    }
+
+    #[test]
+    fn interval_is_per_service_and_event() {
+        let mut notifier = quiet_notifier(60);
+        let now = Instant::now();
+
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), None, now).is_some());
+        assert!(notifier.notify_at(event("web", NotifyEvent::CrashLoop), None, now).is_some());
+        assert!(notifier.notify_at(event("db", NotifyEvent::Failed), None, now).is_some());
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), None, now).is_none());
+    }
+
+    #[test]
+    fn service_config_overrides_the_global_interval() {
+        let mut notifier = quiet_notifier(300);
+        let service = quiet_config(0);
+        let now = Instant::now();
+
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), Some(&service), now).is_some());
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), Some(&service), now).is_some());
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), None, now + Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn nothing_is_sent_without_a_config() {
+        let mut notifier = Notifier::new(None);
+        assert!(notifier.notify_at(event("web", NotifyEvent::Failed), None, Instant::now()).is_none());
+    }
+}
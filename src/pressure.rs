@@ -0,0 +1,142 @@
+use std::fs;
+use sysinfo::System;
+use crate::constants::HISTORY_LEN;
+use crate::utils::CircularBuffer;
+
+/*
+    pressure stall information (linux 4.20+, needs CONFIG_PSI and psi=1 on some distros)
+    /proc/pressure/{cpu,memory,io} look like:
+        some avg10=6.02 avg60=4.74 avg300=3.72 total=80930520
+        full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+    "some" is the share of time at least one task was stalled, "full" all non-idle tasks at once.
+    cpu only has a meaningful "full" line for cgroups, older kernels don't print it at all
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PressureLine {
+    pub avg10: f64, // percent
+    pub avg60: f64,
+    pub avg300: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pressure {
+    pub some: PressureLine,
+    pub full: Option<PressureLine>,
+}
+
+pub struct PressureMonitor {
+    pub cpu: Option<Pressure>, // None when the file is missing (no psi support)
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+    pub load: (f64, f64, f64), // 1, 5 and 15 minutes
+    pub uptime_secs: u64,
+    // load averages * 100 so they fit the u64 sparklines
+    pub load_history: [CircularBuffer<u64>; 3],
+}
+
+impl PressureMonitor {
+    pub fn new() -> Self {
+        Self {
+            cpu: None,
+            memory: None,
+            io: None,
+            load: (0.0, 0.0, 0.0),
+            uptime_secs: 0,
+            load_history: [
+                CircularBuffer::new(HISTORY_LEN),
+                CircularBuffer::new(HISTORY_LEN),
+                CircularBuffer::new(HISTORY_LEN),
+            ],
+        }
+    }
+
+    pub fn refresh(&mut self) {
+        self.cpu = read_pressure("cpu");
+        self.memory = read_pressure("memory");
+        self.io = read_pressure("io");
+
+        let load = System::load_average();
+        self.load = (load.one, load.five, load.fifteen);
+        self.uptime_secs = System::uptime();
+        for (history, value) in self.load_history.iter_mut().zip([load.one, load.five, load.fifteen]) {
+            history.push((value * 100.0) as u64);
+        }
+    }
+}
+
+fn read_pressure(resource: &str) -> Option<Pressure> {
+    parse_pressure(&fs::read_to_string(format!("/proc/pressure/{}", resource)).ok()?)
+}
+
+// None without a "some" line, that's the one every psi kernel prints
+fn parse_pressure(content: &str) -> Option<Pressure> {
+    let mut pressure = Pressure::default();
+    let mut has_some = false;
+
+    for line in content.lines() {
+        let mut parts = line.split_whitespace();
+        let kind = parts.next();
+        let mut values = PressureLine::default();
+        for part in parts {
+            match part.split_once('=') {
+                Some(("avg10", v)) => values.avg10 = v.parse().unwrap_or(0.0),
+                Some(("avg60", v)) => values.avg60 = v.parse().unwrap_or(0.0),
+                Some(("avg300", v)) => values.avg300 = v.parse().unwrap_or(0.0),
+                _ => {}
+            }
+        }
+        match kind {
+            Some("some") => {
+                pressure.some = values;
+                has_some = true;
+            }
+            Some("full") => pressure.full = Some(values),
+            _ => {}
+        }
+    }
+
+    has_some.then_some(pressure)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn some_and_full() {
+        let memory = parse_pressure(
+            "some avg10=6.02 avg60=4.74 avg300=3.72 total=80930520\nfull avg10=1.50 avg60=0.25 avg300=0.05 total=1234\n",
+        ).unwrap();
+        assert_eq!(memory.some.avg10, 6.02);
+        assert_eq!(memory.some.avg60, 4.74);
+        assert_eq!(memory.some.avg300, 3.72);
+        let full = memory.full.unwrap();
+        assert_eq!(full.avg10, 1.50);
+        assert_eq!(full.avg60, 0.25);
+        assert_eq!(full.avg300, 0.05);
+    }
+
+    #[test]
+    fn cpu_without_full() {
+        // system wide cpu pressure has no full line before 5.13
+        let cpu = parse_pressure("some avg10=0.10 avg60=0.20 avg300=0.30 total=42\n").unwrap();
+        assert_eq!(cpu.some.avg300, 0.30);
+        assert!(cpu.full.is_none());
+    }
+
+    #[test]
+    fn missing_or_broken_fields() {
+        // an unreadable or absent average is 0, the rest of the line still counts
+        let pressure = parse_pressure("some avg10=abc avg300=2.5\nfull\n").unwrap();
+        assert_eq!(pressure.some.avg10, 0.0);
+        assert_eq!(pressure.some.avg60, 0.0);
+        assert_eq!(pressure.some.avg300, 2.5);
+        assert_eq!(pressure.full.unwrap().avg10, 0.0);
+    }
+
+    #[test]
+    fn no_some_line() {
+        assert!(parse_pressure("").is_none());
+        assert!(parse_pressure("full avg10=1.00 avg60=1.00 avg300=1.00 total=1\n").is_none());
+    }
+}
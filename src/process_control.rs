@@ -0,0 +1,348 @@
+use std::fs;
+
+/*
+    nice, i/o priority and cpu affinity, straight through the syscalls instead of shelling out to renice/ionice/taskset.
+    all three are per-thread on linux, so setting them on a pid only touches its main thread.
+    like `taskset -a` we apply them to every task under /proc/<pid>/task so the whole process follows.
+    ioprio_get/ioprio_set have no libc wrapper, the value is class << 13 | level:
+        class 1 realtime (root only), 2 best-effort, 3 idle, level 0 (highest) to 7
+        class 0 means "none", the kernel then derives best-effort level (nice + 20) / 5
+ */
+pub const NICE_MIN: i32 = -20;
+pub const NICE_MAX: i32 = 19;
+pub const IOPRIO_LEVEL_MAX: u8 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoClass {
+    None,
+    RealTime,
+    BestEffort,
+    Idle,
+}
+
+impl IoClass {
+    pub const ALL: [IoClass; 4] = [IoClass::None, IoClass::RealTime, IoClass::BestEffort, IoClass::Idle];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IoClass::None => "none (follows nice)",
+            IoClass::RealTime => "realtime",
+            IoClass::BestEffort => "best-effort",
+            IoClass::Idle => "idle",
+        }
+    }
+
+    // idle has no levels, none's level is derived from nice
+    pub fn has_level(&self) -> bool {
+        matches!(self, IoClass::RealTime | IoClass::BestEffort)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn from_raw(class: i32) -> Self {
+        match class {
+            1 => IoClass::RealTime,
+            2 => IoClass::BestEffort,
+            3 => IoClass::Idle,
+            _ => IoClass::None,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn raw(&self) -> i32 {
+        match self {
+            IoClass::None => 0,
+            IoClass::RealTime => 1,
+            IoClass::BestEffort => 2,
+            IoClass::Idle => 3,
+        }
+    }
+}
+
+// everything the control dialog shows and edits for one process
+#[derive(Debug, Clone)]
+pub struct ProcessSettings {
+    pub nice: i32,
+    pub io_class: IoClass,
+    pub io_level: u8,
+    pub affinity: Vec<bool>, // one entry per cpu, true when the process may run there
+}
+
+impl ProcessSettings {
+    pub fn read(pid: u32, num_cpus: usize) -> Result<Self, String> {
+        let nice = get_nice(pid)?;
+        let (io_class, io_level) = match get_io_priority(pid) {
+            // start from the level the kernel is using anyway so switching to best-effort changes nothing by itself
+            Ok((IoClass::None, _)) | Err(_) => (IoClass::None, ((nice - NICE_MIN) / 5) as u8),
+            Ok(priority) => priority,
+        };
+        let affinity = get_affinity(pid, num_cpus).unwrap_or_else(|_| vec![true; num_cpus]);
+        Ok(Self { nice, io_class, io_level, affinity })
+    }
+}
+
+fn os_error(what: &str) -> String {
+    let err = std::io::Error::last_os_error();
+    let reason = match err.raw_os_error() {
+        Some(libc::EPERM) | Some(libc::EACCES) => {
+            "permission denied (raising priority or changing another user's process needs root)".to_string()
+        }
+        Some(libc::ESRCH) => "no such process (it already exited)".to_string(),
+        Some(libc::EINVAL) => "invalid value".to_string(),
+        _ => err.to_string(),
+    };
+    format!("{}: {}", what, reason)
+}
+
+// the main thread first, then the rest, falls back to just the pid without procfs
+fn tasks(pid: u32) -> Vec<u32> {
+    let mut tasks: Vec<u32> = fs::read_dir(format!("/proc/{}/task", pid))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str().and_then(|s| s.parse().ok()))
+                .collect()
+        })
+        .unwrap_or_default();
+    tasks.sort_by_key(|tid| (*tid != pid, *tid));
+    if tasks.is_empty() {
+        tasks.push(pid);
+    }
+    tasks
+}
+
+// applies to every task, a thread exiting halfway through isn't an error
+fn for_each_task(pid: u32, mut apply: impl FnMut(u32) -> Result<(), String>) -> Result<(), String> {
+    for (i, tid) in tasks(pid).into_iter().enumerate() {
+        match apply(tid) {
+            Ok(()) => {}
+            Err(_) if i > 0 && !std::path::Path::new(&format!("/proc/{}/task/{}", pid, tid)).exists() => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+pub fn get_nice(pid: u32) -> Result<i32, String> {
+    // -1 is a valid nice value, errno is the only way to tell it apart from a failure
+    unsafe {
+        *errno_location() = 0;
+        let nice = libc::getpriority(libc::PRIO_PROCESS, pid as libc::id_t);
+        if nice == -1 && *errno_location() != 0 {
+            return Err(os_error("getpriority"));
+        }
+        Ok(nice)
+    }
+}
+
+pub fn set_nice(pid: u32, nice: i32) -> Result<(), String> {
+    let nice = nice.clamp(NICE_MIN, NICE_MAX);
+    for_each_task(pid, |tid| {
+        let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) };
+        if result == 0 { Ok(()) } else { Err(os_error("setpriority")) }
+    })
+}
+
+#[cfg(target_os = "linux")]
+unsafe fn errno_location() -> *mut libc::c_int {
+    unsafe { libc::__errno_location() }
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn errno_location() -> *mut libc::c_int {
+    unsafe { libc::__error() }
+}
+
+#[cfg(target_os = "linux")]
+const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+#[cfg(target_os = "linux")]
+const IOPRIO_CLASS_SHIFT: i32 = 13;
+
+#[cfg(target_os = "linux")]
+pub fn get_io_priority(pid: u32) -> Result<(IoClass, u8), String> {
+    let value = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid as libc::c_long) };
+    if value < 0 {
+        return Err(os_error("ioprio_get"));
+    }
+    let value = value as i32;
+    let class = IoClass::from_raw(value >> IOPRIO_CLASS_SHIFT);
+    let level = (value & ((1 << IOPRIO_CLASS_SHIFT) - 1)) as u8;
+    Ok((class, level.min(IOPRIO_LEVEL_MAX)))
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_io_priority(pid: u32, class: IoClass, level: u8) -> Result<(), String> {
+    let level = if class.has_level() { level.min(IOPRIO_LEVEL_MAX) as i32 } else { 0 };
+    let value = (class.raw() << IOPRIO_CLASS_SHIFT) | level;
+    for_each_task(pid, |tid| {
+        let result = unsafe {
+            libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, tid as libc::c_long, value as libc::c_long)
+        };
+        if result == 0 { Ok(()) } else { Err(os_error("ioprio_set")) }
+    })
+}
+
+#[cfg(target_os = "linux")]
+pub fn get_affinity(pid: u32, num_cpus: usize) -> Result<Vec<bool>, String> {
+    unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        if libc::sched_getaffinity(pid as libc::pid_t, std::mem::size_of::<libc::cpu_set_t>(), &mut set) != 0 {
+            return Err(os_error("sched_getaffinity"));
+        }
+        Ok((0..num_cpus).map(|cpu| libc::CPU_ISSET(cpu, &set)).collect())
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_affinity(pid: u32, affinity: &[bool]) -> Result<(), String> {
+    if !affinity.contains(&true) {
+        return Err("sched_setaffinity: at least one cpu has to stay enabled".to_string());
+    }
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for (cpu, _) in affinity.iter().enumerate().filter(|(_, enabled)| **enabled) {
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+    for_each_task(pid, |tid| {
+        let result = unsafe {
+            libc::sched_setaffinity(tid as libc::pid_t, std::mem::size_of::<libc::cpu_set_t>(), &set)
+        };
+        if result == 0 { Ok(()) } else { Err(os_error("sched_setaffinity")) }
+    })
+}
+
+// macOS has neither i/o priority classes nor hard cpu affinity
+#[cfg(not(target_os = "linux"))]
+pub fn get_io_priority(_pid: u32) -> Result<(IoClass, u8), String> {
+    Err("i/o priority isn't supported on this platform".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_io_priority(_pid: u32, _class: IoClass, _level: u8) -> Result<(), String> {
+    Err("i/o priority isn't supported on this platform".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn get_affinity(_pid: u32, _num_cpus: usize) -> Result<Vec<bool>, String> {
+    Err("cpu affinity isn't supported on this platform".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_affinity(_pid: u32, _affinity: &[bool]) -> Result<(), String> {
+    Err("cpu affinity isn't supported on this platform".to_string())
+}
+
+// state of the `m` dialog, edits stay local until Enter applies whatever differs from what was read
+pub struct ControlDialog {
+    pub pid: u32,
+    pub name: String,
+    pub original: ProcessSettings,
+    pub settings: ProcessSettings,
+    pub field: usize, // 0 nice, 1 i/o class, 2 i/o level, 3 affinity
+    pub cpu_cursor: usize,
+}
+
+pub const CONTROL_FIELDS: usize = 4;
+
+impl ControlDialog {
+    pub fn open(pid: u32, name: String, num_cpus: usize) -> Result<Self, String> {
+        let settings = ProcessSettings::read(pid, num_cpus)?;
+        Ok(Self { pid, name, original: settings.clone(), settings, field: 0, cpu_cursor: 0 })
+    }
+
+    pub fn up(&mut self) {
+        self.field = self.field.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.field = (self.field + 1).min(CONTROL_FIELDS - 1);
+    }
+
+    // left/right: change the value, or move along the cpus on the affinity row
+    pub fn adjust(&mut self, delta: i32) {
+        let settings = &mut self.settings;
+        match self.field {
+            0 => settings.nice = (settings.nice + delta).clamp(NICE_MIN, NICE_MAX),
+            1 => {
+                let index = IoClass::ALL.iter().position(|c| *c == settings.io_class).unwrap_or(0) as i32;
+                let next = (index + delta).rem_euclid(IoClass::ALL.len() as i32);
+                settings.io_class = IoClass::ALL[next as usize];
+            }
+            2 => settings.io_level = (settings.io_level as i32 + delta).clamp(0, IOPRIO_LEVEL_MAX as i32) as u8,
+            _ => {
+                let last = settings.affinity.len().saturating_sub(1) as i32;
+                self.cpu_cursor = (self.cpu_cursor as i32 + delta).clamp(0, last) as usize;
+            }
+        }
+    }
+
+    pub fn toggle_cpu(&mut self) {
+        if self.field != 3 {
+            return;
+        }
+        if let Some(enabled) = self.settings.affinity.get_mut(self.cpu_cursor) {
+            *enabled = !*enabled;
+        }
+    }
+
+    pub fn enable_all_cpus(&mut self) {
+        self.settings.affinity.iter_mut().for_each(|enabled| *enabled = true);
+    }
+
+    fn io_changed(&self) -> bool {
+        let (old, new) = (&self.original, &self.settings);
+        new.io_class != old.io_class || (new.io_class.has_level() && new.io_level != old.io_level)
+    }
+
+    pub fn pending_changes(&self) -> usize {
+        let (old, new) = (&self.original, &self.settings);
+        [new.nice != old.nice, self.io_changed(), new.affinity != old.affinity].iter().filter(|c| **c).count()
+    }
+
+    // (what changed, error) per changed setting, untouched settings aren't written at all
+    pub fn apply(&self) -> Vec<(String, Result<(), String>)> {
+        let (old, new) = (&self.original, &self.settings);
+        let mut results = Vec::new();
+
+        if new.nice != old.nice {
+            results.push((format!("nice {} -> {}", old.nice, new.nice), set_nice(self.pid, new.nice)));
+        }
+        if self.io_changed() {
+            results.push((
+                format!("i/o {} -> {}", describe_io(old.io_class, old.io_level), describe_io(new.io_class, new.io_level)),
+                set_io_priority(self.pid, new.io_class, new.io_level),
+            ));
+        }
+        if new.affinity != old.affinity {
+            results.push((
+                format!("affinity {} -> {}", cpu_list(&old.affinity), cpu_list(&new.affinity)),
+                set_affinity(self.pid, &new.affinity),
+            ));
+        }
+        results
+    }
+}
+
+pub fn describe_io(class: IoClass, level: u8) -> String {
+    if class.has_level() {
+        format!("{}/{}", class.as_str(), level)
+    } else {
+        class.as_str().to_string()
+    }
+}
+
+// taskset style "0-3,6"
+pub fn cpu_list(affinity: &[bool]) -> String {
+    let mut ranges: Vec<String> = Vec::new();
+    let mut start = None;
+    for cpu in 0..=affinity.len() {
+        let enabled = affinity.get(cpu).copied().unwrap_or(false);
+        match (enabled, start) {
+            (true, None) => start = Some(cpu),
+            (false, Some(first)) => {
+                ranges.push(if cpu - 1 == first { first.to_string() } else { format!("{}-{}", first, cpu - 1) });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if ranges.is_empty() { "none".to_string() } else { ranges.join(",") }
+}
@@ -0,0 +1,397 @@
+use std::collections::HashMap;
+use std::fs;
+use sysinfo::{Pid, Process, System};
+use crate::utils::{format_bytes, format_duration, format_local_time};
+
+/*
+    everything we can find out about one process, for the `f` details pane.
+    the portable bits (command line, env, cwd, exe, ids, start time) come from sysinfo,
+    the rest is read straight from procfs:
+        /proc/<pid>/status      uid/gid, state, context switches
+        /proc/<pid>/stat        ppid, nice, priority, last cpu
+        /proc/<pid>/wchan       kernel function a sleeping task waits in
+        /proc/<pid>/fd          open files, socket:[inode] looked up in /proc/net/{tcp,udp,unix}
+        /proc/<pid>/maps        mappings summed up per backing file
+        /proc/<pid>/limits, cgroup, ns/
+    sections that can't be read (another user's process without root, no procfs) just say so
+ */
+const MAX_FDS: usize = 200;
+const MAX_THREADS: usize = 100;
+const MAX_MAPPINGS: usize = 15;
+
+pub struct DetailSection {
+    pub title: &'static str,
+    pub lines: Vec<String>,
+}
+
+pub struct ProcessDetails {
+    pub pid: u32,
+    pub name: String,
+    pub exited: bool,
+    pub sections: Vec<DetailSection>,
+}
+
+impl ProcessDetails {
+    pub fn read(system: &System, pid: u32) -> Option<Self> {
+        let process = system.process(Pid::from_u32(pid))?;
+        let sections = vec![
+            section("Command", command_lines(process)),
+            section("Identity", identity_lines(pid, process)),
+            section("Time", time_lines(process)),
+            section("State", state_lines(pid, process)),
+            section("Threads", thread_lines(pid, system, process)),
+            section("File descriptors", fd_lines(pid)),
+            section("Memory maps", map_lines(pid)),
+            section("Limits", limit_lines(pid)),
+            section("Cgroup", read_lines(&format!("/proc/{}/cgroup", pid))),
+            section("Namespaces", namespace_lines(pid)),
+            section("Environment", process.environ().iter().map(|v| v.to_string_lossy().to_string()).collect()),
+        ];
+        Some(Self { pid, name: process.name().to_string_lossy().to_string(), exited: false, sections })
+    }
+
+    // first line of each section in the flattened view: a title line, the lines, then a blank one
+    pub fn section_offsets(&self) -> Vec<usize> {
+        let mut offsets = Vec::with_capacity(self.sections.len());
+        let mut offset = 0;
+        for section in &self.sections {
+            offsets.push(offset);
+            offset += section.lines.len() + 2;
+        }
+        offsets
+    }
+
+    // index of the section the given line belongs to
+    pub fn section_at(&self, line: usize) -> usize {
+        self.section_offsets().iter().rposition(|offset| *offset <= line).unwrap_or(0)
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.sections.iter().map(|s| s.lines.len() + 2).sum()
+    }
+}
+
+fn section(title: &'static str, lines: Vec<String>) -> DetailSection {
+    if lines.is_empty() {
+        return DetailSection { title, lines: vec!["(unavailable)".to_string()] };
+    }
+    // argv and env values can hold newlines, give each its own line so the scroll math holds
+    let lines = lines.iter().flat_map(|line| line.split('\n')).map(str::to_string).collect();
+    DetailSection { title, lines }
+}
+
+fn read_lines(path: &str) -> Vec<String> {
+    fs::read_to_string(path).map(|s| s.lines().map(str::to_string).collect()).unwrap_or_default()
+}
+
+fn command_lines(process: &Process) -> Vec<String> {
+    let path = |p: Option<&std::path::Path>| p.map_or("-".to_string(), |p| p.display().to_string());
+    let mut lines = vec![
+        format!("exe:  {}", path(process.exe())),
+        format!("cwd:  {}", path(process.cwd())),
+        format!("root: {}", path(process.root())),
+    ];
+    for (i, arg) in process.cmd().iter().enumerate() {
+        lines.push(format!("argv[{}]: {}", i, arg.to_string_lossy()));
+    }
+    lines
+}
+
+fn identity_lines(pid: u32, process: &Process) -> Vec<String> {
+    let users = name_table("/etc/passwd");
+    let groups = name_table("/etc/group");
+    let named = |table: &HashMap<u32, String>, id: &str| {
+        let name = id.parse::<u32>().ok().and_then(|id| table.get(&id)).map_or("?", |n| n.as_str());
+        format!("{}({})", name, id)
+    };
+
+    // real, effective, saved and filesystem ids
+    let status = read_status(pid);
+    let ids = |key: &str, table: &HashMap<u32, String>| {
+        status.get(key).map(|v| {
+            let ids: Vec<String> = v.split_whitespace().map(|id| named(table, id)).collect();
+            format!("{}: {} (real, effective, saved, fs)", key.to_lowercase(), ids.join(" "))
+        })
+    };
+
+    let mut lines = Vec::new();
+    match (ids("Uid", &users), ids("Gid", &groups)) {
+        (Some(uid), Some(gid)) => lines.extend([uid, gid]),
+        _ => {
+            // no procfs, sysinfo only knows the real ids
+            if let Some(uid) = process.user_id() {
+                lines.push(format!("uid: {}", named(&users, &uid.to_string())));
+            }
+            if let Some(gid) = process.group_id() {
+                lines.push(format!("gid: {}", named(&groups, &gid.to_string())));
+            }
+        }
+    }
+    if let Some(supplementary) = status.get("Groups").filter(|g| !g.is_empty()) {
+        let names: Vec<String> = supplementary.split_whitespace().map(|id| named(&groups, id)).collect();
+        lines.push(format!("groups: {}", names.join(" ")));
+    }
+    lines
+}
+
+fn time_lines(process: &Process) -> Vec<String> {
+    vec![
+        format!("started: {}", format_local_time(process.start_time())),
+        format!("elapsed: {}", format_duration(process.run_time())),
+        format!("cpu time: {}", format_duration(process.accumulated_cpu_time() / 1000)),
+    ]
+}
+
+fn state_lines(pid: u32, process: &Process) -> Vec<String> {
+    let mut lines = vec![format!("state: {:?}", process.status())];
+    if let Some(stat) = read_stat(pid) {
+        // fields after the comm, 0-based: 0 state, 1 ppid, 15 priority, 16 nice, 36 processor
+        let field = |i: usize| stat.get(i).map_or("-", |s| s.as_str());
+        lines[0] = format!("state: {} ({:?})", field(0), process.status());
+        lines.push(format!("ppid: {}", field(1)));
+        lines.push(format!("priority: {}  nice: {}", field(15), field(16)));
+        lines.push(format!("last cpu: {}", field(36)));
+    }
+    let wchan = fs::read_to_string(format!("/proc/{}/wchan", pid)).unwrap_or_default();
+    let wchan = wchan.trim();
+    lines.push(format!("wchan: {}", if wchan.is_empty() || wchan == "0" { "-" } else { wchan }));
+
+    let status = read_status(pid);
+    if let (Some(voluntary), Some(involuntary)) = (status.get("voluntary_ctxt_switches"), status.get("nonvoluntary_ctxt_switches")) {
+        lines.push(format!("context switches: {} voluntary, {} involuntary", voluntary, involuntary));
+    }
+    lines
+}
+
+fn thread_lines(pid: u32, system: &System, process: &Process) -> Vec<String> {
+    let Some(tasks) = process.tasks() else {
+        return Vec::new();
+    };
+    let mut tids: Vec<&Pid> = tasks.iter().collect();
+    tids.sort();
+
+    let mut lines = vec![format!("{} thread(s)", tids.len() + 1)];
+    lines.push(format!("{:>8}  {}", pid, process.name().to_string_lossy()));
+    for tid in tids.iter().take(MAX_THREADS) {
+        let name = system.process(**tid).map_or("?".to_string(), |t| t.name().to_string_lossy().to_string());
+        lines.push(format!("{:>8}  {}", tid, name));
+    }
+    if tids.len() > MAX_THREADS {
+        lines.push(format!("... {} more", tids.len() - MAX_THREADS));
+    }
+    lines
+}
+
+fn fd_lines(pid: u32) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(format!("/proc/{}/fd", pid)) else {
+        return Vec::new();
+    };
+    let mut fds: Vec<(u32, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let fd = entry.file_name().to_str()?.parse().ok()?;
+            let target = fs::read_link(entry.path()).ok()?;
+            Some((fd, target.to_string_lossy().to_string()))
+        })
+        .collect();
+    fds.sort();
+
+    let sockets = if fds.iter().any(|(_, t)| t.starts_with("socket:")) { socket_table() } else { HashMap::new() };
+    let socket_count = fds.iter().filter(|(_, t)| t.starts_with("socket:")).count();
+    let mut lines = vec![format!("{} open, {} socket(s)", fds.len(), socket_count)];
+    for (fd, target) in fds.iter().take(MAX_FDS) {
+        let inode = target.strip_prefix("socket:[").and_then(|s| s.strip_suffix(']')).and_then(|s| s.parse::<u64>().ok());
+        let resolved = inode.and_then(|inode| sockets.get(&inode));
+        match resolved {
+            Some(socket) => lines.push(format!("{:>5}  {}  ({})", fd, socket, target)),
+            None => lines.push(format!("{:>5}  {}", fd, target)),
+        }
+    }
+    if fds.len() > MAX_FDS {
+        lines.push(format!("... {} more", fds.len() - MAX_FDS));
+    }
+    lines
+}
+
+// inode -> "tcp 127.0.0.1:8080 -> 10.0.0.2:51234 ESTABLISHED" for every socket the kernel lists
+fn socket_table() -> HashMap<u64, String> {
+    let mut table = HashMap::new();
+    for (proto, v6) in [("tcp", false), ("tcp6", true), ("udp", false), ("udp6", true)] {
+        let Ok(content) = fs::read_to_string(format!("/proc/net/{}", proto)) else {
+            continue;
+        };
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 {
+                continue;
+            }
+            let Ok(inode) = fields[9].parse::<u64>() else {
+                continue;
+            };
+            let local = parse_socket_address(fields[1], v6).unwrap_or_else(|| fields[1].to_string());
+            let remote = parse_socket_address(fields[2], v6).unwrap_or_else(|| fields[2].to_string());
+            let state = if proto.starts_with("tcp") { tcp_state(fields[3]) } else { "" };
+            table.insert(inode, format!("{} {} -> {} {}", proto, local, remote, state).trim_end().to_string());
+        }
+    }
+
+    // Num RefCount Protocol Flags Type St Inode Path
+    if let Ok(content) = fs::read_to_string("/proc/net/unix") {
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let Some(inode) = fields.get(6).and_then(|i| i.parse::<u64>().ok()) else {
+                continue;
+            };
+            let path = fields.get(7).copied().unwrap_or("(unnamed)");
+            table.insert(inode, format!("unix {}", path));
+        }
+    }
+    table
+}
+
+// "0100007F:1F90" (little endian words) -> "127.0.0.1:8080"
+fn parse_socket_address(value: &str, v6: bool) -> Option<String> {
+    let (address, port) = value.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    if !v6 {
+        let raw = u32::from_str_radix(address, 16).ok()?;
+        let ip = std::net::Ipv4Addr::from(raw.to_le_bytes());
+        return Some(format!("{}:{}", ip, port));
+    }
+    if address.len() != 32 {
+        return None;
+    }
+    let mut bytes = [0u8; 16];
+    for (i, word) in (0..4).map(|i| &address[i * 8..i * 8 + 8]).enumerate() {
+        let word = u32::from_str_radix(word, 16).ok()?;
+        bytes[i * 4..i * 4 + 4].copy_from_slice(&word.to_le_bytes());
+    }
+    Some(format!("[{}]:{}", std::net::Ipv6Addr::from(bytes), port))
+}
+
+fn tcp_state(hex: &str) -> &'static str {
+    match hex {
+        "01" => "ESTABLISHED",
+        "02" => "SYN_SENT",
+        "03" => "SYN_RECV",
+        "04" => "FIN_WAIT1",
+        "05" => "FIN_WAIT2",
+        "06" => "TIME_WAIT",
+        "07" => "CLOSE",
+        "08" => "CLOSE_WAIT",
+        "09" => "LAST_ACK",
+        "0A" => "LISTEN",
+        "0B" => "CLOSING",
+        _ => "",
+    }
+}
+
+// virtual size per backing file, biggest first, with anonymous memory and the totals on top
+fn map_lines(pid: u32) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(format!("/proc/{}/maps", pid)) else {
+        return Vec::new();
+    };
+
+    let mut per_file: HashMap<String, u64> = HashMap::new();
+    let mut count = 0;
+    let (mut anonymous, mut file_backed) = (0u64, 0u64);
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some((start, end)) = fields.next().and_then(|range| range.split_once('-')) else {
+            continue;
+        };
+        let (Ok(start), Ok(end)) = (u64::from_str_radix(start, 16), u64::from_str_radix(end, 16)) else {
+            continue;
+        };
+        let size = end.saturating_sub(start);
+        count += 1;
+
+        // perms, offset, dev, inode, then the optional path
+        match fields.nth(4) {
+            Some(path) if path.starts_with('/') => {
+                file_backed += size;
+                *per_file.entry(path.to_string()).or_default() += size;
+            }
+            Some(special) => *per_file.entry(special.to_string()).or_default() += size,
+            None => anonymous += size,
+        }
+    }
+
+    let mut largest: Vec<(String, u64)> = per_file.into_iter().collect();
+    largest.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut lines = vec![
+        format!("{} mappings, {} file backed, {} anonymous", count, format_bytes(file_backed), format_bytes(anonymous)),
+    ];
+    for (path, size) in largest.iter().take(MAX_MAPPINGS) {
+        lines.push(format!("{:>12}  {}", format_bytes(*size), path));
+    }
+    lines
+}
+
+// "Max open files  1024  4096  files" -> aligned "open files  1024 / 4096 files"
+fn limit_lines(pid: u32) -> Vec<String> {
+    let lines = read_lines(&format!("/proc/{}/limits", pid));
+    lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| {
+            // the name column is padded to 26 characters, the rest is whitespace separated
+            let name = line.get(..26)?.trim().trim_start_matches("Max ");
+            let mut values = line.get(26..)?.split_whitespace();
+            let (soft, hard) = (values.next()?, values.next()?);
+            let unit = values.next().unwrap_or("");
+            Some(format!("{:<20} {:>12} / {:<12} {}", name, soft, hard, unit).trim_end().to_string())
+        })
+        .collect()
+}
+
+// a namespace that differs from init's is the process's own (container, sandbox, unshare)
+fn namespace_lines(pid: u32) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(format!("/proc/{}/ns", pid)) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries.flatten().filter_map(|e| e.file_name().to_str().map(str::to_string)).collect();
+    names.sort();
+
+    names
+        .iter()
+        .filter_map(|name| {
+            let link = fs::read_link(format!("/proc/{}/ns/{}", pid, name)).ok()?;
+            let link = link.to_string_lossy().to_string();
+            let own = fs::read_link(format!("/proc/1/ns/{}", name))
+                .is_ok_and(|init| init.to_string_lossy() != link);
+            Some(format!("{:<18} {}{}", name, link, if own { "  (own)" } else { "" }))
+        })
+        .collect()
+}
+
+fn read_status(pid: u32) -> HashMap<String, String> {
+    read_lines(&format!("/proc/{}/status", pid))
+        .into_iter()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+// /proc/<pid>/stat split after the comm, which can hold spaces and parens
+fn read_stat(pid: u32) -> Option<Vec<String>> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let rest = &stat[stat.rfind(')')? + 1..];
+    Some(rest.split_whitespace().map(str::to_string).collect())
+}
+
+// id -> name from /etc/passwd or /etc/group (name:x:id:...)
+fn name_table(path: &str) -> HashMap<u32, String> {
+    read_lines(path)
+        .iter()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+use sysinfo::System;
+use crate::constants::HISTORY_LEN;
+use crate::utils::CircularBuffer;
+
+/*
+    per-process disk i/o rates. sysinfo's Process::disk_usage() already gives bytes since the previous refresh,
+    we just divide by the real time between refreshes and keep a short history for the details sparkline
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessIoStats {
+    pub read_rate: f64, // bytes/s
+    pub write_rate: f64,
+    pub total_read: u64,
+    pub total_written: u64,
+}
+
+impl ProcessIoStats {
+    pub fn total_rate(&self) -> f64 {
+        self.read_rate + self.write_rate
+    }
+}
+
+pub struct ProcessIoUsage {
+    per_pid: HashMap<u32, ProcessIoStats>,
+    read_history: HashMap<u32, CircularBuffer<u64>>,
+    write_history: HashMap<u32, CircularBuffer<u64>>,
+}
+
+impl ProcessIoUsage {
+    pub fn new() -> Self {
+        Self {
+            per_pid: HashMap::new(),
+            read_history: HashMap::new(),
+            write_history: HashMap::new(),
+        }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&ProcessIoStats> {
+        self.per_pid.get(&pid)
+    }
+
+    // read and write history (oldest first) in bytes/s, empty until the process has done any i/o
+    pub fn history(&self, pid: u32) -> (Vec<u64>, Vec<u64>) {
+        (
+            self.read_history.get(&pid).map(|h| h.to_vec()).unwrap_or_default(),
+            self.write_history.get(&pid).map(|h| h.to_vec()).unwrap_or_default(),
+        )
+    }
+
+    // call right after system.refresh_all(), elapsed is the time since the previous refresh
+    pub fn refresh(&mut self, system: &System, elapsed_secs: f64) {
+        let elapsed = elapsed_secs.max(0.001);
+        self.per_pid.clear();
+
+        for (pid, process) in system.processes() {
+            let usage = process.disk_usage();
+            let pid = pid.as_u32();
+            let stats = ProcessIoStats {
+                read_rate: usage.read_bytes as f64 / elapsed,
+                write_rate: usage.written_bytes as f64 / elapsed,
+                total_read: usage.total_read_bytes,
+                total_written: usage.total_written_bytes,
+            };
+
+            // only track history once a process actually touches the disk, most never do
+            if stats.total_rate() > 0.0 || self.read_history.contains_key(&pid) {
+                self.read_history.entry(pid).or_insert_with(|| CircularBuffer::new(HISTORY_LEN)).push(stats.read_rate as u64);
+                self.write_history.entry(pid).or_insert_with(|| CircularBuffer::new(HISTORY_LEN)).push(stats.write_rate as u64);
+            }
+
+            self.per_pid.insert(pid, stats);
+        }
+
+        // forget exited processes
+        self.read_history.retain(|pid, _| self.per_pid.contains_key(pid));
+        self.write_history.retain(|pid, _| self.per_pid.contains_key(pid));
+    }
+}
@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+/*
+    per-process network attribution.
+    the kernel doesn't account traffic per process, but it does per socket, and /proc/<pid>/fd tells us which sockets a process holds:
+    - /proc/<pid>/fd/N -> "socket:[inode]"
+    - /proc/net/{tcp,tcp6,udp,udp6} -> which of those inodes are inet sockets (connection counts)
+    - netlink sock_diag with INET_DIAG_INFO -> tcp_info.bytes_received / bytes_acked per tcp socket (byte rates)
+    UDP sockets have no byte counters anywhere we can reach without eBPF so they only show up in the counts
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcessNetStats {
+    pub tcp: u32,
+    pub udp: u32,
+    pub rx_rate: f64, // bytes/s
+    pub tx_rate: f64,
+}
+
+impl ProcessNetStats {
+    pub fn connections(&self) -> u32 {
+        self.tcp + self.udp
+    }
+}
+
+pub struct ProcessNetUsage {
+    per_pid: HashMap<u32, ProcessNetStats>,
+    prev_socket_bytes: HashMap<u64, (u64, u64)>, // inode -> (rx, tx) at the last sample
+    last_sample: Option<Instant>,
+    pub byte_counters_available: bool, // false when sock_diag isn't usable, rates are then always 0
+}
+
+impl ProcessNetUsage {
+    pub fn new() -> Self {
+        Self {
+            per_pid: HashMap::new(),
+            prev_socket_bytes: HashMap::new(),
+            last_sample: None,
+            byte_counters_available: cfg!(target_os = "linux"),
+        }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&ProcessNetStats> {
+        self.per_pid.get(&pid)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn refresh(&mut self) {
+        let now = Instant::now();
+        let owners = linux::socket_owners();
+
+        let mut per_pid: HashMap<u32, ProcessNetStats> = HashMap::new();
+        for (table, is_tcp) in [("tcp", true), ("tcp6", true), ("udp", false), ("udp6", false)] {
+            for inode in linux::inet_socket_inodes(table) {
+                if let Some(pid) = owners.get(&inode) {
+                    let stats = per_pid.entry(*pid).or_default();
+                    if is_tcp { stats.tcp += 1 } else { stats.udp += 1 }
+                }
+            }
+        }
+
+        match linux::tcp_socket_bytes() {
+            Ok(socket_bytes) => {
+                self.byte_counters_available = true;
+                let elapsed = self.last_sample.map(|t| now.duration_since(t).as_secs_f64());
+
+                if let Some(elapsed) = elapsed.filter(|e| *e > 0.0) {
+                    for (inode, (rx, tx)) in &socket_bytes {
+                        let Some(pid) = owners.get(inode) else {
+                            continue;
+                        };
+                        // a socket we haven't seen before was opened during this interval, all its bytes are new
+                        let (prev_rx, prev_tx) = self.prev_socket_bytes.get(inode).copied().unwrap_or((0, 0));
+                        let stats = per_pid.entry(*pid).or_default();
+                        stats.rx_rate += rx.saturating_sub(prev_rx) as f64 / elapsed;
+                        stats.tx_rate += tx.saturating_sub(prev_tx) as f64 / elapsed;
+                    }
+                }
+
+                self.prev_socket_bytes = socket_bytes;
+            }
+            Err(_) => {
+                self.byte_counters_available = false;
+                self.prev_socket_bytes.clear();
+            }
+        }
+
+        self.per_pid = per_pid;
+        self.last_sample = Some(now);
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn refresh(&mut self) {}
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashMap;
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+
+    // inode -> pid for every socket fd we're allowed to look at (other users' processes are skipped unless root)
+    pub fn socket_owners() -> HashMap<u64, u32> {
+        socket_owners_in(Path::new("/proc"))
+    }
+
+    pub fn socket_owners_in(proc: &Path) -> HashMap<u64, u32> {
+        let mut owners = HashMap::new();
+        let Ok(entries) = fs::read_dir(proc) else {
+            return owners;
+        };
+
+        for entry in entries.flatten() {
+            let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse::<u32>().ok()) else {
+                continue;
+            };
+            let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+                continue;
+            };
+            for fd in fds.flatten() {
+                let Ok(target) = fs::read_link(fd.path()) else {
+                    continue;
+                };
+                if let Some(inode) = socket_inode(&target.to_string_lossy()) {
+                    owners.insert(inode, pid);
+                }
+            }
+        }
+
+        owners
+    }
+
+    // an fd link target, "socket:[12345]" -> 12345 (pipes, files and anon inodes are None)
+    pub fn socket_inode(target: &str) -> Option<u64> {
+        target.strip_prefix("socket:[")
+            .and_then(|s| s.strip_suffix(']'))
+            .and_then(|s| s.parse::<u64>().ok())
+    }
+
+    pub fn inet_socket_inodes(table: &str) -> Vec<u64> {
+        let Ok(content) = fs::read_to_string(format!("/proc/net/{}", table)) else {
+            return Vec::new();
+        };
+        parse_inet_inodes(&content)
+    }
+
+    // inode column of /proc/net/<table>, header line skipped
+    pub fn parse_inet_inodes(content: &str) -> Vec<u64> {
+        content.lines()
+            .skip(1)
+            .filter_map(|line| line.split_whitespace().nth(9)?.parse::<u64>().ok())
+            .filter(|inode| *inode != 0)
+            .collect()
+    }
+
+    /*
+        NETLINK SOCK_DIAG
+        one dump request per address family, every reply carries an inet_diag_msg followed by rtattrs.
+        the INET_DIAG_INFO attribute is a struct tcp_info, bytes_acked sits at offset 120 and bytes_received at 128 (kernel 4.1+)
+     */
+    const SOCK_DIAG_BY_FAMILY: u16 = 20;
+    const INET_DIAG_INFO: u16 = 2;
+    const NLMSG_ERROR: u16 = 2;
+    const NLMSG_DONE: u16 = 3;
+    const NLMSG_HDR_LEN: usize = 16;
+    const INET_DIAG_MSG_LEN: usize = 72;
+    const TCPI_BYTES_ACKED: usize = 120;
+    const TCPI_BYTES_RECEIVED: usize = 128;
+
+    struct NetlinkSocket(i32);
+
+    impl Drop for NetlinkSocket {
+        fn drop(&mut self) {
+            unsafe {
+                libc::close(self.0);
+            }
+        }
+    }
+
+    pub fn tcp_socket_bytes() -> io::Result<HashMap<u64, (u64, u64)>> {
+        let fd = unsafe { libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_SOCK_DIAG) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let socket = NetlinkSocket(fd);
+
+        let mut bytes = HashMap::new();
+        for family in [libc::AF_INET as u8, libc::AF_INET6 as u8] {
+            send_dump_request(&socket, family)?;
+            read_dump(&socket, &mut bytes)?;
+        }
+        Ok(bytes)
+    }
+
+    fn send_dump_request(socket: &NetlinkSocket, family: u8) -> io::Result<()> {
+        let mut request = [0u8; NLMSG_HDR_LEN + 56];
+        let len = request.len() as u32;
+        let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+
+        // nlmsghdr
+        request[0..4].copy_from_slice(&len.to_ne_bytes());
+        request[4..6].copy_from_slice(&SOCK_DIAG_BY_FAMILY.to_ne_bytes());
+        request[6..8].copy_from_slice(&flags.to_ne_bytes());
+        // inet_diag_req_v2: family, protocol, ext bitmask, pad, states
+        request[16] = family;
+        request[17] = libc::IPPROTO_TCP as u8;
+        request[18] = 1 << (INET_DIAG_INFO - 1);
+        request[20..24].copy_from_slice(&u32::MAX.to_ne_bytes()); // every tcp state
+
+        let sent = unsafe { libc::send(socket.0, request.as_ptr() as *const libc::c_void, request.len(), 0) };
+        if sent < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+
+    fn read_dump(socket: &NetlinkSocket, bytes: &mut HashMap<u64, (u64, u64)>) -> io::Result<()> {
+        let mut buf = vec![0u8; 32 * 1024];
+
+        loop {
+            let received = unsafe { libc::recv(socket.0, buf.as_mut_ptr() as *mut libc::c_void, buf.len(), 0) };
+            if received < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            if parse_dump(&buf[..received as usize], bytes)? {
+                return Ok(());
+            }
+        }
+    }
+
+    // one recv worth of netlink messages, true once the dump is over (NLMSG_DONE or something we can't follow)
+    pub fn parse_dump(data: &[u8], bytes: &mut HashMap<u64, (u64, u64)>) -> io::Result<bool> {
+        let mut offset = 0;
+        while offset + NLMSG_HDR_LEN <= data.len() {
+            let msg_len = read_u32(data, offset) as usize;
+            let msg_type = read_u16(data, offset + 4);
+            if msg_len < NLMSG_HDR_LEN || offset + msg_len > data.len() {
+                return Ok(true);
+            }
+
+            match msg_type {
+                NLMSG_DONE => return Ok(true),
+                NLMSG_ERROR => return Err(io::Error::other("sock_diag request rejected")),
+                _ => parse_diag_msg(&data[offset + NLMSG_HDR_LEN..offset + msg_len], bytes),
+            }
+
+            offset += align4(msg_len);
+        }
+        Ok(false)
+    }
+
+    fn parse_diag_msg(msg: &[u8], bytes: &mut HashMap<u64, (u64, u64)>) {
+        if msg.len() < INET_DIAG_MSG_LEN {
+            return;
+        }
+        let inode = read_u32(msg, 68) as u64;
+
+        let mut offset = INET_DIAG_MSG_LEN;
+        while offset + 4 <= msg.len() {
+            let attr_len = read_u16(msg, offset) as usize;
+            let attr_type = read_u16(msg, offset + 2);
+            if attr_len < 4 || offset + attr_len > msg.len() {
+                break;
+            }
+
+            if attr_type == INET_DIAG_INFO {
+                let info = &msg[offset + 4..offset + attr_len];
+                if info.len() >= TCPI_BYTES_RECEIVED + 8 {
+                    let tx = read_u64(info, TCPI_BYTES_ACKED);
+                    let rx = read_u64(info, TCPI_BYTES_RECEIVED);
+                    bytes.insert(inode, (rx, tx));
+                }
+            }
+
+            offset += align4(attr_len);
+        }
+    }
+
+    fn align4(len: usize) -> usize {
+        (len + 3) & !3
+    }
+
+    fn read_u16(data: &[u8], at: usize) -> u16 {
+        u16::from_ne_bytes([data[at], data[at + 1]])
+    }
+
+    fn read_u32(data: &[u8], at: usize) -> u32 {
+        u32::from_ne_bytes(data[at..at + 4].try_into().unwrap_or_default())
+    }
+
+    fn read_u64(data: &[u8], at: usize) -> u64 {
+        u64::from_ne_bytes(data[at..at + 8].try_into().unwrap_or_default())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::linux::*;
+    use std::collections::HashMap;
+    use std::fs;
+    use std::os::unix::fs::symlink;
+    use std::path::PathBuf;
+
+    // captured from a live host, the TIME_WAIT entry has no inode any more
+    const TCP: &str = "\
+  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 3500007F:0035 00000000:0000 0A 00000000:00000000 00:00000000 00000000   101        0 20468 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 24385 1 0000000000000000 100 0 0 10 0
+   2: 0F02000A:D5B0 8A1CA8C0:01BB 06 00000000:00000000 03:00000DA7 00000000     0        0 0 3 0000000000000000
+";
+
+    const TCP6: &str = "\
+  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 00000000000000000000000000000000:0016 00000000000000000000000000000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 19842 1 0000000000000000 100 0 0 10 0
+";
+
+    const UDP: &str = "\
+   sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  373: 3500007F:0035 00000000:0000 07 00000000:00000000 00:00000000 00000000   101        0 20467 2 0000000000000000 0
+  529: 00000000:14E9 00000000:0000 07 00000000:00000000 00:00000000 00000000   105        0 21830 2 0000000000000000 0
+";
+
+    #[test]
+    fn proc_net_tables() {
+        assert_eq!(parse_inet_inodes(TCP), vec![20468, 24385]);
+        assert_eq!(parse_inet_inodes(TCP6), vec![19842]);
+        assert_eq!(parse_inet_inodes(UDP), vec![20467, 21830]);
+        // header only, and a cut off line
+        assert!(parse_inet_inodes(TCP.lines().next().unwrap()).is_empty());
+        assert!(parse_inet_inodes("header\n   0: 3500007F:0035 00000000:0000 0A\n").is_empty());
+    }
+
+    #[test]
+    fn fd_link_targets() {
+        assert_eq!(socket_inode("socket:[20468]"), Some(20468));
+        assert_eq!(socket_inode("pipe:[20468]"), None);
+        assert_eq!(socket_inode("anon_inode:[eventfd]"), None);
+        assert_eq!(socket_inode("/dev/null"), None);
+        assert_eq!(socket_inode("socket:[]"), None);
+    }
+
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let root = std::env::temp_dir().join(format!("r-top-net-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(&root).unwrap();
+            Fixture(root)
+        }
+
+        // the links don't have to resolve, only their targets are read
+        fn fd(&self, pid: &str, fd: u32, target: &str) {
+            let dir = self.0.join(pid).join("fd");
+            fs::create_dir_all(&dir).unwrap();
+            symlink(target, dir.join(fd.to_string())).unwrap();
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn fd_owner_mapping() {
+        let proc = Fixture::new("owners");
+        proc.fd("1234", 3, "socket:[20468]");
+        proc.fd("1234", 4, "pipe:[999]");
+        proc.fd("1234", 5, "/dev/null");
+        proc.fd("1234", 6, "socket:[21830]");
+        proc.fd("5678", 0, "socket:[24385]");
+        proc.fd("self", 3, "socket:[11111]");
+        fs::create_dir_all(proc.0.join("9999")).unwrap(); // exited or not ours, no fd dir to read
+
+        let owners = socket_owners_in(&proc.0);
+        let expected: HashMap<u64, u32> = [(20468, 1234), (21830, 1234), (24385, 5678)].into_iter().collect();
+        assert_eq!(owners, expected);
+    }
+
+    const HDR: usize = 16;
+    const DIAG_MSG: usize = 72;
+
+    fn nlmsg(kind: u16, payload: &[u8]) -> Vec<u8> {
+        let mut msg = Vec::new();
+        msg.extend_from_slice(&((HDR + payload.len()) as u32).to_ne_bytes());
+        msg.extend_from_slice(&kind.to_ne_bytes());
+        msg.extend_from_slice(&[0u8; 10]); // flags, seq, port id
+        msg.extend_from_slice(payload);
+        while msg.len() % 4 != 0 {
+            msg.push(0);
+        }
+        msg
+    }
+
+    fn rtattr(kind: u16, data: &[u8]) -> Vec<u8> {
+        let mut attr = Vec::new();
+        attr.extend_from_slice(&((4 + data.len()) as u16).to_ne_bytes());
+        attr.extend_from_slice(&kind.to_ne_bytes());
+        attr.extend_from_slice(data);
+        while attr.len() % 4 != 0 {
+            attr.push(0);
+        }
+        attr
+    }
+
+    // inet_diag_msg for `inode` followed by its attributes, tcp_info sized like a 5.x kernel's
+    fn diag_msg(inode: u32, rx: u64, tx: u64) -> Vec<u8> {
+        let mut msg = vec![0u8; DIAG_MSG];
+        msg[0] = libc::AF_INET as u8;
+        msg[68..72].copy_from_slice(&inode.to_ne_bytes());
+
+        let mut info = vec![0u8; 232];
+        info[120..128].copy_from_slice(&tx.to_ne_bytes());
+        info[128..136].copy_from_slice(&rx.to_ne_bytes());
+        msg.extend(rtattr(1, &[0u8; 16])); // INET_DIAG_MEMINFO, skipped
+        msg.extend(rtattr(5, &[1])); // an odd length one, padded
+        msg.extend(rtattr(2, &info));
+        msg
+    }
+
+    #[test]
+    fn sock_diag_dump_is_decoded() {
+        let mut data = nlmsg(20, &diag_msg(20468, 4096, 1024));
+        data.extend(nlmsg(20, &diag_msg(24385, 7, 0)));
+        data.extend(nlmsg(3, &0i32.to_ne_bytes()));
+
+        let mut bytes = HashMap::new();
+        assert!(parse_dump(&data, &mut bytes).unwrap());
+        assert_eq!(bytes.get(&20468), Some(&(4096, 1024)));
+        assert_eq!(bytes.get(&24385), Some(&(7, 0)));
+        assert_eq!(bytes.len(), 2);
+    }
+
+    #[test]
+    fn sock_diag_dump_spans_several_reads() {
+        let mut bytes = HashMap::new();
+        assert!(!parse_dump(&nlmsg(20, &diag_msg(1, 10, 20)), &mut bytes).unwrap());
+        assert!(parse_dump(&nlmsg(3, &0i32.to_ne_bytes()), &mut bytes).unwrap());
+        assert_eq!(bytes.get(&1), Some(&(10, 20)));
+    }
+
+    #[test]
+    fn sock_diag_old_kernel_and_broken_messages() {
+        let mut bytes = HashMap::new();
+
+        // tcp_info from before bytes_received existed, the socket is left out rather than read past the end
+        let mut old = vec![0u8; DIAG_MSG];
+        old[68..72].copy_from_slice(&5u32.to_ne_bytes());
+        old.extend(rtattr(2, &[0u8; 104]));
+        assert!(!parse_dump(&nlmsg(20, &old), &mut bytes).unwrap());
+
+        // a header claiming more than was received ends the dump
+        let mut cut = nlmsg(20, &diag_msg(6, 1, 1));
+        cut.truncate(cut.len() - 8);
+        assert!(parse_dump(&cut, &mut bytes).unwrap());
+        assert!(bytes.is_empty());
+
+        assert!(parse_dump(&nlmsg(2, &(-1i32).to_ne_bytes()), &mut bytes).is_err());
+    }
+}
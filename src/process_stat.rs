@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::fs;
+use sysinfo::{System, Users};
+
+/*
+    the per-process bits sysinfo doesn't give us, for the configurable columns:
+        /proc/<pid>/stat    state letter, priority and nice (fields 3, 18 and 19)
+        /proc/<pid>/statm   shared pages (third field), what top calls SHR
+    read for every process each refresh so those columns can be sorted on, it's two small reads per pid,
+    skipped entirely while none of them is visible.
+    user names come from sysinfo so they also work without procfs
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ProcStat {
+    pub state: char,
+    pub priority: i64,
+    pub nice: i64,
+    pub shared: u64, // bytes
+}
+
+pub struct ProcessStatMonitor {
+    stats: HashMap<u32, ProcStat>,
+    users: HashMap<u32, String>,
+    user_list: Users,
+    page_size: u64,
+}
+
+impl ProcessStatMonitor {
+    pub fn new() -> Self {
+        Self {
+            stats: HashMap::new(),
+            users: HashMap::new(),
+            user_list: Users::new(),
+            page_size: unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1) as u64,
+        }
+    }
+
+    // read_stats is false when none of those columns is on screen, then only the user names are kept up to date
+    pub fn refresh(&mut self, system: &System, read_stats: bool) {
+        self.stats.clear();
+        if read_stats {
+            for pid in system.processes().keys() {
+                if let Some(stat) = read_stat(pid.as_u32(), self.page_size) {
+                    self.stats.insert(pid.as_u32(), stat);
+                }
+            }
+        }
+
+        // accounts rarely change, only reload when we meet a uid we don't know
+        let unknown = system.processes().values().filter_map(|p| p.user_id()).any(|uid| !self.users.contains_key(&**uid));
+        if unknown || self.users.is_empty() {
+            self.user_list.refresh();
+            self.users = self.user_list.list().iter().map(|u| (**u.id(), u.name().to_string())).collect();
+        }
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&ProcStat> {
+        self.stats.get(&pid)
+    }
+
+    // the name, or the bare uid for accounts that don't exist (containers, deleted users)
+    pub fn user_name(&self, uid: u32) -> String {
+        self.users.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+    }
+}
+
+fn read_stat(pid: u32, page_size: u64) -> Option<ProcStat> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // the name can contain spaces and parens, count fields from after the last ')'
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    let number = |i: usize| fields.get(i).and_then(|v| v.parse::<i64>().ok()).unwrap_or(0);
+
+    let shared_pages = fs::read_to_string(format!("/proc/{}/statm", pid))
+        .ok()
+        .and_then(|statm| statm.split_whitespace().nth(2).and_then(|v| v.parse::<u64>().ok()))
+        .unwrap_or(0);
+
+    Some(ProcStat {
+        state: fields.first()?.chars().next()?,
+        priority: number(15),
+        nice: number(16),
+        shared: shared_pages * page_size,
+    })
+}
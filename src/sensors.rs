@@ -0,0 +1,144 @@
+use std::fs;
+use std::path::Path;
+use sysinfo::{Components, System};
+
+/*
+    temperatures, fans and per-core clocks.
+    - temperatures come from sysinfo's Components (hwmon on linux, SMC on macOS), which also knows the critical trip point
+    - fans aren't covered by sysinfo, on linux they're the fanN_input files under /sys/class/hwmon/hwmonN
+    - the current clock per core is cpufreq's scaling_cur_freq (kHz), falling back to sysinfo's /proc/cpuinfo value
+      which is all VMs and some ARM boards give us
+ */
+const DEFAULT_WARNING_CELSIUS: f32 = 80.0;
+const WARNING_BELOW_CRITICAL: f32 = 10.0;
+
+pub struct TemperatureReading {
+    pub label: String,
+    pub celsius: Option<f32>, // None when the sensor can't be read right now
+    pub highest: Option<f32>, // highest value seen since r-top started
+    pub critical: Option<f32>,
+}
+
+impl TemperatureReading {
+    // warn a bit before the critical trip point, or at a generic 80°C when the sensor doesn't report one
+    pub fn warning_threshold(&self) -> f32 {
+        self.critical.map_or(DEFAULT_WARNING_CELSIUS, |c| c - WARNING_BELOW_CRITICAL)
+    }
+
+    pub fn is_critical(&self) -> bool {
+        matches!((self.celsius, self.critical), (Some(t), Some(c)) if t >= c)
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.celsius.is_some_and(|t| t >= self.warning_threshold())
+    }
+}
+
+pub struct FanReading {
+    pub label: String,
+    pub rpm: u64,
+    pub min_rpm: Option<u64>,
+}
+
+impl FanReading {
+    // a stopped fan is normal for some setups (zero rpm mode), only complain when it's below its own minimum
+    pub fn is_warning(&self) -> bool {
+        self.min_rpm.is_some_and(|min| min > 0 && self.rpm < min)
+    }
+}
+
+pub struct SensorMonitor {
+    components: Components,
+    pub temperatures: Vec<TemperatureReading>,
+    pub fans: Vec<FanReading>,
+    pub core_frequencies: Vec<u64>, // MHz, same order as system.cpus()
+}
+
+impl SensorMonitor {
+    pub fn new() -> Self {
+        Self {
+            components: Components::new_with_refreshed_list(),
+            temperatures: Vec::new(),
+            fans: Vec::new(),
+            core_frequencies: Vec::new(),
+        }
+    }
+
+    // call right after system.refresh_all()
+    pub fn refresh(&mut self, system: &System) {
+        self.components.refresh(true);
+        self.temperatures = self.components.list()
+            .iter()
+            .map(|component| TemperatureReading {
+                label: component.label().to_string(),
+                celsius: component.temperature(),
+                highest: component.max(),
+                critical: component.critical(),
+            })
+            .collect();
+
+        self.fans = read_hwmon_fans();
+
+        self.core_frequencies = system.cpus()
+            .iter()
+            .enumerate()
+            .map(|(i, cpu)| read_cpufreq_mhz(i).unwrap_or(cpu.frequency()))
+            .collect();
+    }
+
+    pub fn hottest(&self) -> Option<&TemperatureReading> {
+        self.temperatures.iter()
+            .filter(|t| t.celsius.is_some())
+            .max_by(|a, b| a.celsius.partial_cmp(&b.celsius).unwrap_or(std::cmp::Ordering::Equal))
+    }
+}
+
+fn read_cpufreq_mhz(core: usize) -> Option<u64> {
+    let path = format!("/sys/devices/system/cpu/cpu{}/cpufreq/scaling_cur_freq", core);
+    let khz = fs::read_to_string(path).ok()?.trim().parse::<u64>().ok()?;
+    Some(khz / 1000)
+}
+
+// every fanN_input of every hwmon chip, labelled "<chip> <fanN_label or fanN>"
+fn read_hwmon_fans() -> Vec<FanReading> {
+    let mut fans = Vec::new();
+    let Ok(chips) = fs::read_dir("/sys/class/hwmon") else {
+        return fans;
+    };
+
+    let mut chips: Vec<_> = chips.flatten().map(|entry| entry.path()).collect();
+    chips.sort();
+
+    for chip in chips {
+        let chip_name = read_trimmed(&chip.join("name")).unwrap_or_else(|| "hwmon".to_string());
+        let Ok(entries) = fs::read_dir(&chip) else {
+            continue;
+        };
+
+        let mut inputs: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .filter(|name| name.starts_with("fan") && name.ends_with("_input"))
+            .collect();
+        inputs.sort();
+
+        for input in inputs {
+            let prefix = input.trim_end_matches("_input");
+            let Some(rpm) = read_trimmed(&chip.join(&input)).and_then(|v| v.parse::<u64>().ok()) else {
+                continue;
+            };
+            let label = read_trimmed(&chip.join(format!("{}_label", prefix))).unwrap_or_else(|| prefix.to_string());
+            fans.push(FanReading {
+                label: format!("{} {}", chip_name, label),
+                rpm,
+                min_rpm: read_trimmed(&chip.join(format!("{}_min", prefix))).and_then(|v| v.parse().ok()),
+            });
+        }
+    }
+
+    fans
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
@@ -0,0 +1,200 @@
+use std::fs;
+use std::time::{Duration, Instant};
+use libc::c_int;
+
+/*
+    signals offered by the `k` menu, SIGTERM first since that's what we send by default.
+    after a terminating signal we keep an eye on the pid for a few seconds and report whether it actually went away,
+    a zombie counts as gone (it's dead, the parent just hasn't reaped it yet)
+ */
+const FOLLOWUP_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub struct SignalInfo {
+    pub number: c_int,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub terminates: bool, // whether we wait around to see the process exit
+}
+
+pub const SIGNALS: [SignalInfo; 10] = [
+    SignalInfo { number: libc::SIGTERM, name: "SIGTERM", description: "ask to terminate (default)", terminates: true },
+    SignalInfo { number: libc::SIGKILL, name: "SIGKILL", description: "kill immediately, can't be caught", terminates: true },
+    SignalInfo { number: libc::SIGHUP, name: "SIGHUP", description: "hangup, many daemons reload config", terminates: false },
+    SignalInfo { number: libc::SIGINT, name: "SIGINT", description: "interrupt, same as ctrl+c", terminates: true },
+    SignalInfo { number: libc::SIGQUIT, name: "SIGQUIT", description: "quit and dump core", terminates: true },
+    SignalInfo { number: libc::SIGSTOP, name: "SIGSTOP", description: "stop (freeze), can't be caught", terminates: false },
+    SignalInfo { number: libc::SIGCONT, name: "SIGCONT", description: "continue a stopped process", terminates: false },
+    SignalInfo { number: libc::SIGTSTP, name: "SIGTSTP", description: "terminal stop, same as ctrl+z", terminates: false },
+    SignalInfo { number: libc::SIGUSR1, name: "SIGUSR1", description: "user defined 1", terminates: false },
+    SignalInfo { number: libc::SIGUSR2, name: "SIGUSR2", description: "user defined 2", terminates: false },
+];
+
+// one of the sets `k` can aim at (the row, its subtree, the tagged set, the search matches), in signalling order
+pub struct SignalTargets {
+    pub label: String,
+    pub targets: Vec<(u32, String)>,
+    // stop everything first, signal, then continue, so no process reacts (or respawns a child) before the rest got it too
+    pub at_once: bool,
+    pub excluded: Vec<(u32, String)>, // r-top and its parents, left out of multi-process sets
+}
+
+impl SignalTargets {
+    /*
+        a subtree or search can easily contain r-top itself or the shell/terminal it runs in.
+        stopping those freezes r-top before it gets to send SIGCONT, and killing them takes r-top down halfway through,
+        so sets of more than one process skip them. a single row is still yours to shoot
+     */
+    pub fn new(label: String, mut targets: Vec<(u32, String)>) -> Self {
+        let mut excluded = Vec::new();
+        if targets.len() > 1 {
+            let lineage = own_lineage();
+            (excluded, targets) = targets.into_iter().partition(|(pid, _)| lineage.contains(pid));
+        }
+        Self { label, targets, at_once: false, excluded }
+    }
+
+    pub fn at_once(mut self) -> Self {
+        self.at_once = true;
+        self
+    }
+
+    // "name (pid)" for a single process, the label otherwise
+    pub fn describe(&self) -> String {
+        match self.targets.as_slice() {
+            [(pid, name)] => format!("{} ({})", name, pid),
+            targets => format!("{} ({} processes)", self.label, targets.len()),
+        }
+    }
+}
+
+// kill(2) with the errno turned into something readable
+pub fn send_signal(pid: u32, signal: c_int) -> Result<(), String> {
+    let result = unsafe { libc::kill(pid as libc::pid_t, signal) };
+    if result == 0 {
+        return Ok(());
+    }
+
+    let err = std::io::Error::last_os_error();
+    Err(match err.raw_os_error() {
+        Some(libc::EPERM) => "permission denied (owned by another user, try running as root)".to_string(),
+        Some(libc::ESRCH) => "no such process (it already exited)".to_string(),
+        Some(libc::EINVAL) => "invalid signal".to_string(),
+        _ => err.to_string(),
+    })
+}
+
+// the state letter from /proc/<pid>/stat (R, S, D, T, Z...)
+pub fn process_state(pid: u32) -> Option<char> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // the name can contain spaces and parens, the state is the first field after the last ')'
+    let i = stat.rfind(')')?;
+    stat[i + 1..].split_whitespace().next()?.chars().next()
+}
+
+// r-top's pid followed by its parent, grandparent and so on up to init
+fn own_lineage() -> Vec<u32> {
+    let mut lineage = vec![std::process::id()];
+    while let Some(parent) = parent_pid(*lineage.last().unwrap_or(&0)) {
+        if parent == 0 || lineage.contains(&parent) {
+            break;
+        }
+        lineage.push(parent);
+    }
+    lineage
+}
+
+// fourth field of /proc/<pid>/stat, the second one after the name
+fn parent_pid(pid: u32) -> Option<u32> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    let i = stat.rfind(')')?;
+    stat[i + 1..].split_whitespace().nth(1)?.parse().ok()
+}
+
+// Z and X mean it's dead even though the entry is still there
+pub fn is_running(pid: u32) -> bool {
+    if !std::path::Path::new("/proc/self/stat").exists() {
+        // no procfs, signal 0 only checks that the pid exists
+        return unsafe { libc::kill(pid as libc::pid_t, 0) } == 0;
+    }
+    !matches!(process_state(pid), Some('Z') | Some('X') | None)
+}
+
+// send to every target, stopping them all first when at_once is set. failures come back as "name (pid): reason"
+pub fn send_to_all(targets: Vec<(u32, String)>, signal: &SignalInfo, at_once: bool) -> (Vec<(u32, String)>, Vec<String>) {
+    let freeze = at_once && ![libc::SIGSTOP, libc::SIGCONT, libc::SIGTSTP, libc::SIGKILL].contains(&signal.number);
+    let mut frozen = Vec::new();
+    if freeze {
+        for (pid, _) in &targets {
+            // leave processes that were already stopped the way we found them
+            if process_state(*pid) != Some('T') && send_signal(*pid, libc::SIGSTOP).is_ok() {
+                frozen.push(*pid);
+            }
+        }
+    }
+
+    let mut sent = Vec::new();
+    let mut failures = Vec::new();
+    for (pid, name) in targets {
+        match send_signal(pid, signal.number) {
+            Ok(()) => sent.push((pid, name)),
+            Err(e) => failures.push(format!("{} ({}): {}", name, pid, e)),
+        }
+    }
+
+    for pid in frozen {
+        let _ = send_signal(pid, libc::SIGCONT);
+    }
+    (sent, failures)
+}
+
+// `F`: SIGSTOP the targets, or SIGCONT them when every one of them is stopped already
+pub fn freeze_or_resume(targets: Vec<(u32, String)>) -> (&'static SignalInfo, Vec<(u32, String)>, Vec<String>) {
+    let resume = !targets.is_empty() && targets.iter().all(|(pid, _)| process_state(*pid) == Some('T'));
+    let number = if resume { libc::SIGCONT } else { libc::SIGSTOP };
+    let signal = SIGNALS.iter().find(|s| s.number == number).unwrap_or(&SIGNALS[0]);
+    let (sent, failures) = send_to_all(targets, signal, false);
+    (signal, sent, failures)
+}
+
+// one signal sent to one or more (pid, name) targets, the tagged set sends them all at once
+pub struct SignalFollowup {
+    pub targets: Vec<(u32, String)>,
+    pub signal_name: &'static str,
+    pub sent_at: Instant,
+}
+
+impl SignalFollowup {
+    // Some(message) once there's something to report, None while we're still waiting
+    pub fn check(&self) -> Option<String> {
+        let running: Vec<&(u32, String)> = self.targets.iter().filter(|(pid, _)| is_running(*pid)).collect();
+        if !running.is_empty() && self.sent_at.elapsed() < FOLLOWUP_TIMEOUT {
+            return None;
+        }
+
+        if let [(pid, name)] = self.targets.as_slice() {
+            return Some(if running.is_empty() {
+                format!("{} ({}) exited after {}", name, pid, self.signal_name)
+            } else {
+                format!(
+                    "{} ({}) is still running {}s after {}, it may be catching or ignoring it (SIGKILL can't be caught)",
+                    name,
+                    pid,
+                    FOLLOWUP_TIMEOUT.as_secs(),
+                    self.signal_name
+                )
+            });
+        }
+
+        let mut message = format!(
+            "{} of {} processes exited after {}",
+            self.targets.len() - running.len(),
+            self.targets.len(),
+            self.signal_name
+        );
+        if !running.is_empty() {
+            let names: Vec<String> = running.iter().map(|(pid, name)| format!("{} ({})", name, pid)).collect();
+            message.push_str(&format!("\nstill running: {}", names.join(", ")));
+        }
+        Some(message)
+    }
+}
@@ -1,38 +1,63 @@
-use sysinfo::{System, Process, Pid};
-use crate::types::SortCategory;
+use sysinfo::{System, Process, Pid, ProcessStatus};
+use crate::columns::{ColumnContext, SecondaryKey, SortOrder, SortValue};
 use crate::constants::HISTORY_LEN;
 use crate::utils::CircularBuffer;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use crate::app_state::*;
+use crate::cgroups::ProcessCgroup;
 use std::fs;
+use std::time::{Duration, Instant};
 
 #[inline]
 pub fn bytes_to_gib(b: u64) -> f64 {
     b as f64 / (1024.0 * 1024.0 * 1024.0)
 }
 
-// Add this struct to cache sorted processes
+/*
+    sorted pid order, rebuilt once per refresh (the values only change when sysinfo refreshes) instead of every frame.
+    if a full sort ever gets slow (tens of thousands of processes) we only redo it every few refreshes,
+    between those the order is just the previous one with new values, rows don't shuffle in between
+ */
+const CHEAP_SORT: Duration = Duration::from_millis(5);
+const STALE_REFRESHES: u64 = 5;
+
 pub struct ProcessCache {
     cached_processes: Vec<Pid>,
-    last_sort_category: SortCategory,
+    last_order: Option<SortOrder>,
     cache_valid: bool,
     process_count: usize,
+    generation: u64, // bumped on every system refresh
+    sorted_generation: u64,
+    last_sort_time: Duration,
 }
 
 impl ProcessCache {
     pub fn new() -> Self {
         Self {
             cached_processes: Vec::new(),
-            last_sort_category: SortCategory::CpuPerCore,
+            last_order: None,
             cache_valid: false,
             process_count: 0,
+            generation: 0,
+            sorted_generation: 0,
+            last_sort_time: Duration::ZERO,
         }
     }
 
     pub fn invalidate(&mut self) {
         self.cache_valid = false;
     }
+
+    // new values are in, re-sort on the next call if that's cheap enough
+    pub fn refreshed(&mut self) {
+        self.generation += 1;
+    }
+
+    fn is_stale(&self) -> bool {
+        let behind = self.generation - self.sorted_generation;
+        behind > 0 && (self.last_sort_time < CHEAP_SORT || behind >= STALE_REFRESHES)
+    }
 }
 
 /* 
@@ -42,8 +67,7 @@ impl ProcessCache {
 
 #[cfg(target_os = "linux")]
 pub fn memory_used_gib(system: &sysinfo::System) -> f64 {
-    let used_bytes = (system.used_memory() as u64) * 1024;
-    bytes_to_gib(used_bytes)
+    bytes_to_gib(system.used_memory())
 }
 
 #[cfg(target_os = "macos")]
@@ -83,8 +107,7 @@ pub fn memory_used_gib(_: &sysinfo::System) -> f64 {
 
 #[cfg(not(any(target_os = "linux", target_os = "macos")))]
 pub fn memory_used_gib(system: &sysinfo::System) -> f64 {
-    let used_bytes = (system.used_memory() as u64) * 1024;
-    bytes_to_gib(used_bytes)
+    bytes_to_gib(system.used_memory())
 }
 pub fn update_cpu_history(cpu_history: &mut Vec<CircularBuffer<f32>>, system: &System) {
     for (i, cpu) in system.cpus().iter().enumerate() {
@@ -100,94 +123,45 @@ pub fn update_cpu_history(cpu_history: &mut Vec<CircularBuffer<f32>>, system: &S
 */
 pub fn sort_processes_cached<'a>(
     system: &'a System, 
-    sort_category: &SortCategory,
+    order: SortOrder,
     cache: &mut ProcessCache,
-    search_active: &bool
+    context: &ColumnContext,
 ) -> Vec<&'a Process> {
     let current_process_count = system.processes().len();
     
     // Check if we need to invalidate cache
     let needs_resort = !cache.cache_valid 
-        || !matches!((cache.last_sort_category, sort_category), 
-            (SortCategory::CpuPerCore, SortCategory::CpuPerCore) |
-            (SortCategory::CpuAverage, SortCategory::CpuAverage) |
-            (SortCategory::Memory, SortCategory::Memory) |
-            (SortCategory::Network, SortCategory::Network))
-        || cache.process_count != current_process_count;
+        || cache.last_order != Some(order)
+        || cache.process_count != current_process_count
+        || cache.is_stale();
 
     if needs_resort {
-        // Full resort needed
-        let num_cores = system.cpus().len() as f32;
-        let mut process_pairs: Vec<(Pid, f64)> = system.processes()
+        let started = Instant::now();
+        // the secondary key is only worth building when it's the name
+        let mut process_pairs: Vec<(Pid, SortValue, String)> = system.processes()
             .iter()
             .map(|(pid, process)| {
-                let sort_value = match sort_category {
-                    SortCategory::CpuPerCore | SortCategory::CpuAverage => {
-                        (process.cpu_usage() / num_cores) as f64
-                    }
-                    SortCategory::Memory => process.memory() as f64,
-                    SortCategory::Network => 0.0, // No specific sorting for network
+                let name = match order.secondary {
+                    SecondaryKey::Name => process.name().to_string_lossy().to_lowercase(),
+                    SecondaryKey::Pid => String::new(),
                 };
-                (*pid, sort_value)
+                (*pid, context.sort_value(order.column, process), name)
             })
             .collect();
 
-        // Sort by the calculated values (descending order)
-        if !matches!(sort_category, SortCategory::Network) {
-            process_pairs.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-        }
+        // column in the chosen direction, then the secondary key and the pid ascending so equal rows never swap
+        process_pairs.sort_by(|a, b| {
+            let primary = if order.descending { b.1.cmp(&a.1) } else { a.1.cmp(&b.1) };
+            primary.then_with(|| a.2.cmp(&b.2)).then(a.0.cmp(&b.0))
+        });
 
         // Update cache
-        cache.cached_processes = process_pairs.into_iter().map(|(pid, _)| pid).collect();
-        cache.last_sort_category = *sort_category;
+        cache.cached_processes = process_pairs.into_iter().map(|(pid, _, _)| pid).collect();
+        cache.last_order = Some(order);
         cache.cache_valid = true;
         cache.process_count = current_process_count;
-    } else {
-        // Cache is valid, but we might need to update sort values for dynamic sorting
-        // For CPU and Memory, values change frequently, so we do a lightweight update
-        if matches!(sort_category, SortCategory::CpuPerCore | SortCategory::CpuAverage | SortCategory::Memory) {
-            let num_cores = system.cpus().len() as f32;
-            
-            // Create a map for quick lookup of current values
-            let current_values: HashMap<Pid, f64> = system.processes()
-                .iter()
-                .map(|(pid, process)| {
-                    let sort_value = match sort_category {
-                        SortCategory::CpuPerCore | SortCategory::CpuAverage => {
-                            (process.cpu_usage() / num_cores) as f64
-                        }
-                        SortCategory::Memory => process.memory() as f64,
-                        SortCategory::Network => 0.0,
-                    };
-                    (*pid, sort_value)
-                })
-                .collect();
-
-            // Re-sort only if there are significant changes
-            let mut needs_full_resort = false;
-            
-            // Check if top 10 processes have changed significantly
-            for (i, &pid) in cache.cached_processes.iter().take(10).enumerate() {
-                if let Some(&current_value) = current_values.get(&pid) {
-                    // If a top process has dropped significantly, resort
-                    if i < 5 && current_value < 1.0 {
-                        needs_full_resort = true;
-                        break;
-                    }
-                } else {
-                    // Process no longer exists
-                    needs_full_resort = true;
-                    break;
-                }
-            }
-            
-            if !search_active{
-                if needs_full_resort {
-                    cache.invalidate();
-                    return sort_processes_cached(system, sort_category, cache, search_active);
-                }
-            }
-        }
+        cache.sorted_generation = cache.generation;
+        cache.last_sort_time = started.elapsed();
     }
 
     // Return processes in cached order, filtering out non-existent processes
@@ -197,27 +171,15 @@ pub fn sort_processes_cached<'a>(
         .collect()
 }
 
-// keep the original function for backward compatibility, but mark it as deprecated
-#[deprecated(note = "deprecated! use cached version")]
-pub fn sort_processes<'a>(system: &'a System, sort_category: &SortCategory) -> Vec<&'a Process> {
-    let num_cores = system.cpus().len() as f32;
-    let mut processes: Vec<_> = system.processes().values().collect();
-
-    match sort_category {
-        SortCategory::CpuPerCore | SortCategory::CpuAverage => {
-            processes.sort_by(|a, b| {
-                let a_usage = a.cpu_usage() / num_cores;
-                let b_usage = b.cpu_usage() / num_cores;
-                b_usage.partial_cmp(&a_usage).unwrap()
-            });
-        }
-        SortCategory::Memory => {
-            processes.sort_by(|a, b| b.memory().cmp(&a.memory()));
-        }
-        SortCategory::Network => {} // No specific sorting for network
-    }
-
-    processes
+// sort_processes_cached with everything it needs taken from the app state
+pub fn sorted_processes<'a>(system: &'a System, app_state: &mut AppState) -> Vec<&'a Process> {
+    let context = ColumnContext::new(system, &app_state.net_usage, &app_state.io_usage, &app_state.process_stats, &app_state.cgroups);
+    let order = SortOrder {
+        column: app_state.sort_column,
+        descending: app_state.sort_descending,
+        secondary: app_state.sort_secondary,
+    };
+    sort_processes_cached(system, order, &mut app_state.process_cache, &context)
 }
 
 pub fn calculate_avg_cpu_history(cpu_history: &Vec<CircularBuffer<f32>>) -> Vec<u64> {
@@ -382,6 +344,39 @@ fn build_tree_recursive(
     }
 }
 
+// the process and all its descendants as (pid, name, depth), parents before their children.
+// threads are left out, signalling one is the same as signalling its process
+pub fn process_subtree(system: &System, root: Pid) -> Vec<(u32, String, usize)> {
+    let mut children: HashMap<Pid, Vec<Pid>> = HashMap::new();
+    for (pid, process) in system.processes() {
+        if process.thread_kind().is_some() {
+            continue;
+        }
+        if let Some(parent) = process.parent() {
+            children.entry(parent).or_default().push(*pid);
+        }
+    }
+
+    let mut subtree = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack = vec![(root, 0)];
+    while let Some((pid, depth)) = stack.pop() {
+        let Some(process) = system.process(pid) else {
+            continue;
+        };
+        if !seen.insert(pid) {
+            continue;
+        }
+        subtree.push((pid.as_u32(), process.name().to_string_lossy().into_owned(), depth));
+
+        // reversed so the lowest pid comes off the stack first
+        let mut kids = children.get(&pid).cloned().unwrap_or_default();
+        kids.sort_by(|a, b| b.cmp(a));
+        stack.extend(kids.into_iter().map(|kid| (kid, depth + 1)));
+    }
+    subtree
+}
+
 // Helper function to get tree statistics
 pub fn get_tree_stats(tree_items: &[TreeItem]) -> (usize, usize, usize) {
     let total_processes = tree_items.len();
@@ -404,192 +399,84 @@ pub fn filter_processes_cached<'a>(
     }
 
     if !app_state.search_cache_valid {
-        app_state.filtered_processes.clear();
-        let search_type = app_state.get_search_type();
-        let search_value = app_state.get_search_value().to_string();
-
-
-        match search_type {
-            SearchType::Name => {
-                let query = search_value.to_lowercase();
-                for (index, process) in processes.iter().enumerate() {
-                    let process_name = process.name().to_string_lossy().to_lowercase();
-                    if process_name.contains(&query) {
-                        app_state.filtered_processes.push(index);
-                    }
-                }
-            }
-            SearchType::Pid => {
-                let clean_value = search_value.trim();
-                
-                if !clean_value.is_empty() {
-                    // Try exact PID match first
-                    if let Ok(target_pid) = clean_value.parse::<u32>() {
-                        for (index, process) in processes.iter().enumerate() {
-                            if process.pid().as_u32() == target_pid {
-                                app_state.filtered_processes.push(index);
-                                break;
-                            }
-                        }
-                    } else {
-                        // If not a complete number, try partial matching
-                        // But only if it contains only digits
-                        if clean_value.chars().all(|c| c.is_ascii_digit()) {
-                            for (index, process) in processes.iter().enumerate() {
-                                let pid_str = process.pid().to_string();
-                                if pid_str.starts_with(clean_value) {
-                                    app_state.filtered_processes.push(index);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let pids: HashSet<u32> = search_matches(app_state, processes).iter().map(|process| process.pid().as_u32()).collect();
+        app_state.filtered_processes = pids;
         app_state.search_cache_valid = true;
     }
 
-    app_state.filtered_processes
+    // in the current order, whatever it was when the matches were collected
+    processes
         .iter()
-        .filter_map(|&index| processes.get(index))
+        .filter(|process| app_state.filtered_processes.contains(&process.pid().as_u32()))
         .copied()
         .collect()
 }
 
-pub fn get_actual_process_index(app_state: &AppState, filtered_index: usize) -> Option<usize> {
-    if app_state.search_active && !app_state.is_search_empty() {
-        app_state.filtered_processes.get(filtered_index).copied()
-    } else {
-        Some(filtered_index)
-    }
-}
-
-pub fn get_filtered_process_count(app_state: &AppState, total_processes: usize) -> usize {
-    if app_state.search_active && !app_state.is_search_empty() {
-        app_state.filtered_processes.len()
-    } else {
-        total_processes
-    }
-}
-
-pub fn sort_and_filter_processes_cached<'a>(
-    system: &'a System,
-    app_state: &mut AppState,
-) -> Vec<&'a Process> {
-    let sorted_processes = sort_processes_cached(system, &app_state.sort_category, &mut app_state.process_cache, &app_state.search_active);
-
-    filter_processes_cached(system, &sorted_processes, app_state)
+// the search matched against every process right now, without the cache. for anything that acts on
+// "all matches" (signals, tagging), the cached set can lag behind the list on screen
+pub fn search_matches<'a>(app_state: &AppState, processes: &[&'a Process]) -> Vec<&'a Process> {
+    let matcher = SearchMatcher::new(app_state.get_search_type(), app_state.get_search_value());
+    processes
+        .iter()
+        .filter(|process| {
+            let pid = process.pid().as_u32();
+            let name = process.name().to_string_lossy();
+            matcher.matches(pid, &name, process.status() == ProcessStatus::Stop, app_state.cgroups.get(pid))
+        })
+        .copied()
+        .collect()
 }
 
-// GPU Information Structure
-#[derive(Clone, Debug)]
-pub struct GpuInfo {
-    pub name: String,
-    pub driver_version: String,
-    pub memory_total: String,
-    pub memory_used: String,
-    pub temperature: String,
-    pub utilization: String,
+// one search query, checked a process at a time
+pub struct SearchMatcher {
+    kind: SearchType,
+    query: String,
 }
 
-// Detect and get GPU information
-pub fn get_gpu_info() -> Vec<GpuInfo> {
-    let mut gpus = Vec::new();
-
-    // Try to detect NVIDIA GPUs via nvidia-smi
-    if let Ok(output) = std::process::Command::new("nvidia-smi")
-        .arg("--query-gpu=name,driver_version,memory.total,memory.used,temperature.gpu,utilization.gpu")
-        .arg("--format=csv,noheader,nounits")
-        .output()
-    {
-        if output.status.success() {
-            if let Ok(stdout) = String::from_utf8(output.stdout) {
-                for line in stdout.lines() {
-                    let parts: Vec<&str> = line.split(',').map(|s| s.trim()).collect();
-                    if parts.len() >= 6 {
-                        gpus.push(GpuInfo {
-                            name: parts[0].to_string(),
-                            driver_version: parts[1].to_string(),
-                            memory_total: format!("{} MB", parts[2]),
-                            memory_used: format!("{} MB", parts[3]),
-                            temperature: format!("{}°C", parts[4]),
-                            utilization: format!("{}%", parts[5]),
-                        });
-                    }
-                }
-            }
-        }
+impl SearchMatcher {
+    pub fn new(kind: SearchType, value: &str) -> Self {
+        let query = match kind {
+            SearchType::Name => value.to_lowercase(),
+            _ => value.trim().to_lowercase(),
+        };
+        Self { kind, query }
     }
 
-    // Try to detect AMD GPUs via rocm-smi or radeontop
-    if gpus.is_empty() {
-        if let Ok(output) = std::process::Command::new("rocm-smi")
-            .arg("--showproductname")
-            .output()
-        {
-            if output.status.success() {
-                if let Ok(stdout) = String::from_utf8(output.stdout) {
-                    for line in stdout.lines() {
-                        if line.contains("GPU") {
-                            gpus.push(GpuInfo {
-                                name: line.trim().to_string(),
-                                driver_version: "N/A".to_string(),
-                                memory_total: "N/A".to_string(),
-                                memory_used: "N/A".to_string(),
-                                temperature: "N/A".to_string(),
-                                utilization: "N/A".to_string(),
-                            });
-                        }
+    pub fn matches(&self, pid: u32, name: &str, stopped: bool, cgroup: Option<&ProcessCgroup>) -> bool {
+        match self.kind {
+            SearchType::Name => name.to_lowercase().contains(&self.query),
+            SearchType::Cgroup => {
+                // "/..." is a cgroup subtree, anything else a container name/id or unit name
+                let subtree = format!("{}/", self.query.trim_end_matches('/'));
+                cgroup.is_some_and(|cgroup| {
+                    if self.query.starts_with('/') {
+                        cgroup.path == self.query || cgroup.path.starts_with(&subtree)
+                    } else {
+                        cgroup.group.to_lowercase().contains(&self.query)
+                            || cgroup.container_id.as_ref().is_some_and(|id| id.starts_with(&self.query))
                     }
-                }
+                })
             }
-        }
-    }
-
-    // Try reading from /sys for basic GPU detection (Linux)
-    #[cfg(target_os = "linux")]
-    if gpus.is_empty() {
-        if let Ok(entries) = fs::read_dir("/sys/class/drm") {
-            let mut card_found = false;
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if let Some(name) = path.file_name() {
-                    if name.to_string_lossy().starts_with("card") 
-                        && !name.to_string_lossy().contains('-') {
-                        if let Ok(vendor_path) = fs::read_to_string(path.join("device/vendor")) {
-                            card_found = true;
-                            let gpu_name = match vendor_path.trim() {
-                                "0x10de" => "NVIDIA GPU",
-                                "0x1002" => "AMD GPU",
-                                "0x8086" => "Intel GPU",
-                                _ => "Unknown GPU",
-                            };
-                            gpus.push(GpuInfo {
-                                name: gpu_name.to_string(),
-                                driver_version: "N/A".to_string(),
-                                memory_total: "N/A".to_string(),
-                                memory_used: "N/A".to_string(),
-                                temperature: "N/A".to_string(),
-                                utilization: "N/A".to_string(),
-                            });
-                        }
-                    }
+            SearchType::Frozen => stopped && name.to_lowercase().contains(&self.query),
+            SearchType::Pid => {
+                if self.query.is_empty() {
+                    return false;
+                }
+                // exact pid when it parses, a prefix when it's digits too long for one
+                match self.query.parse::<u32>() {
+                    Ok(target_pid) => pid == target_pid,
+                    Err(_) => self.query.chars().all(|c| c.is_ascii_digit()) && pid.to_string().starts_with(&self.query),
                 }
             }
         }
     }
+}
 
-    // Fallback: No GPU detected
-    if gpus.is_empty() {
-        gpus.push(GpuInfo {
-            name: "No GPU detected".to_string(),
-            driver_version: "N/A".to_string(),
-            memory_total: "N/A".to_string(),
-            memory_used: "N/A".to_string(),
-            temperature: "N/A".to_string(),
-            utilization: "N/A".to_string(),
-        });
-    }
+pub fn sort_and_filter_processes_cached<'a>(
+    system: &'a System,
+    app_state: &mut AppState,
+) -> Vec<&'a Process> {
+    let sorted_processes = sorted_processes(system, app_state);
 
-    gpus
-}
\ No newline at end of file
+    filter_processes_cached(system, &sorted_processes, app_state)
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::Instant;
+
+/*
+    per-thread view of one process, from /proc/<pid>/task/<tid>/stat.
+    cpu% is utime+stime ticks between two refreshes, like the process list it's per core (a busy thread shows ~100%),
+    so the first reading after opening has nothing to compare against and shows "-"
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreadSort {
+    Cpu,
+    Tid,
+    Name,
+    State,
+    LastCpu,
+}
+
+impl ThreadSort {
+    pub fn previous(&self) -> Self {
+        match self {
+            ThreadSort::Cpu => ThreadSort::LastCpu,
+            ThreadSort::Tid => ThreadSort::Cpu,
+            ThreadSort::Name => ThreadSort::Tid,
+            ThreadSort::State => ThreadSort::Name,
+            ThreadSort::LastCpu => ThreadSort::State,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ThreadSort::Cpu => ThreadSort::Tid,
+            ThreadSort::Tid => ThreadSort::Name,
+            ThreadSort::Name => ThreadSort::State,
+            ThreadSort::State => ThreadSort::LastCpu,
+            ThreadSort::LastCpu => ThreadSort::Cpu,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThreadSort::Cpu => "CPU",
+            ThreadSort::Tid => "TID",
+            ThreadSort::Name => "Name",
+            ThreadSort::State => "State",
+            ThreadSort::LastCpu => "Last CPU",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub name: String,
+    pub state: char,
+    pub last_cpu: u32,
+    pub cpu: Option<f32>,
+    pub cpu_ticks: u64,
+}
+
+pub struct ThreadView {
+    pub pid: u32,
+    pub name: String,
+    pub threads: Vec<ThreadInfo>,
+    pub sort: ThreadSort,
+    pub selected: usize,
+    pub exited: bool,
+    ticks_per_sec: f64,
+    last_refresh: Instant,
+}
+
+impl ThreadView {
+    pub fn open(pid: u32, name: String) -> Result<Self, String> {
+        let mut view = Self {
+            pid,
+            name,
+            threads: Vec::new(),
+            sort: ThreadSort::Cpu,
+            selected: 0,
+            exited: false,
+            ticks_per_sec: unsafe { libc::sysconf(libc::_SC_CLK_TCK) }.max(1) as f64,
+            last_refresh: Instant::now(),
+        };
+        view.threads = read_threads(pid).ok_or_else(|| format!("can't read /proc/{}/task", pid))?;
+        view.sort_threads();
+        Ok(view)
+    }
+
+    pub fn refresh(&mut self) {
+        if self.exited {
+            return;
+        }
+        let Some(mut threads) = read_threads(self.pid) else {
+            // keep the last reading on screen, the title says it's gone
+            self.exited = true;
+            return;
+        };
+
+        let elapsed = self.last_refresh.elapsed().as_secs_f64();
+        self.last_refresh = Instant::now();
+        let previous: HashMap<u32, u64> = self.threads.iter().map(|t| (t.tid, t.cpu_ticks)).collect();
+        for thread in &mut threads {
+            // threads that started since the last refresh get their first reading next time
+            thread.cpu = previous.get(&thread.tid).filter(|_| elapsed > 0.0).map(|before| {
+                thread.cpu_ticks.saturating_sub(*before) as f64 / self.ticks_per_sec / elapsed * 100.0
+            }).map(|cpu| cpu as f32);
+        }
+
+        // stay on the same thread when the order changes
+        let selected_tid = self.threads.get(self.selected).map(|t| t.tid);
+        self.threads = threads;
+        self.sort_threads();
+        self.selected = selected_tid
+            .and_then(|tid| self.threads.iter().position(|t| t.tid == tid))
+            .unwrap_or(self.selected)
+            .min(self.threads.len().saturating_sub(1));
+    }
+
+    pub fn set_sort(&mut self, sort: ThreadSort) {
+        let selected_tid = self.threads.get(self.selected).map(|t| t.tid);
+        self.sort = sort;
+        self.sort_threads();
+        if let Some(index) = selected_tid.and_then(|tid| self.threads.iter().position(|t| t.tid == tid)) {
+            self.selected = index;
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        if self.selected + 1 < self.threads.len() {
+            self.selected += 1;
+        }
+    }
+
+    // cpu and state descending (busy/odd states first), the rest ascending, tid breaks ties
+    fn sort_threads(&mut self) {
+        let sort = self.sort;
+        self.threads.sort_by(|a, b| {
+            let order = match sort {
+                ThreadSort::Cpu => b.cpu.unwrap_or(0.0).total_cmp(&a.cpu.unwrap_or(0.0)),
+                ThreadSort::Tid => std::cmp::Ordering::Equal,
+                ThreadSort::Name => a.name.cmp(&b.name),
+                ThreadSort::State => state_rank(a.state).cmp(&state_rank(b.state)),
+                ThreadSort::LastCpu => a.last_cpu.cmp(&b.last_cpu),
+            };
+            order.then(a.tid.cmp(&b.tid))
+        });
+    }
+}
+
+// running first, then uninterruptible (usually what you're hunting), then the rest
+fn state_rank(state: char) -> u8 {
+    match state {
+        'R' => 0,
+        'D' => 1,
+        'T' | 't' => 2,
+        'Z' => 3,
+        'S' => 4,
+        _ => 5,
+    }
+}
+
+fn read_threads(pid: u32) -> Option<Vec<ThreadInfo>> {
+    let entries = fs::read_dir(format!("/proc/{}/task", pid)).ok()?;
+    let threads = entries
+        .flatten()
+        .filter_map(|entry| {
+            let tid = entry.file_name().to_str()?.parse().ok()?;
+            let stat = fs::read_to_string(entry.path().join("stat")).ok()?;
+            // the comm sits in parens and can hold spaces or parens itself
+            let name = stat[stat.find('(')? + 1..stat.rfind(')')?].to_string();
+            let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+            // after the comm: 0 state, 11 utime, 12 stime, 36 processor
+            let ticks = |i: usize| fields.get(i).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+            Some(ThreadInfo {
+                tid,
+                name,
+                state: fields.first()?.chars().next()?,
+                last_cpu: ticks(36) as u32,
+                cpu: None,
+                cpu_ticks: ticks(11) + ticks(12),
+            })
+        })
+        .collect();
+    Some(threads)
+}
@@ -3,18 +3,25 @@ use std::thread::current;
 use std::{time::Duration};
 use ratatui::{prelude::*, symbols::bar::Set, widgets::*, style::*};
 // NOTE: no explicit ratatui::text imports needed; we'll write into the buffer directly
-use sysinfo::{System, Networks, Disks, Process};
+use sysinfo::{System, Process, ProcessStatus};
 use tachyonfx::{fx};
-use crate::constants::*;
-use crate::utils::{format_bytes, CircularBuffer};
+use crate::utils::{format_bytes, format_duration, format_rate, CircularBuffer};
+use crate::daemon::ServiceStatus;
+use crate::memory_stats::ProcessMemory;
+use crate::cpu_times::{CpuBreakdown, SEGMENT_LABELS};
+use crate::theme::Theme;
+use crate::cgroups::GroupKind;
+use crate::signals::SIGNALS;
+use crate::process_control::{cpu_list, ControlDialog};
+use crate::process_details::ProcessDetails;
+use crate::threads::ThreadSort;
+use crate::columns::{ColumnContext, ColumnKind, ColumnSetting};
 use crate::app_state::{AppState, SearchType};
-use crate::system_info::{sort_and_filter_processes_cached, get_actual_process_index, get_filtered_process_count,calculate_avg_cpu_history, get_busiest_core_info, build_process_tree, get_tree_stats, memory_used_gib};
+use crate::system_info::{sort_and_filter_processes_cached, calculate_avg_cpu_history, get_busiest_core_info, build_process_tree, get_tree_stats, bytes_to_gib};
 
 pub fn render_ui(
     frame: &mut ratatui::Frame,
     system: &System,
-    networks: &Networks,
-    disks: &Disks,
     processes: &Vec<&Process>,
     cpu_history: &Vec<CircularBuffer<f32>>,
     app_state: &mut AppState,
@@ -25,9 +32,9 @@ pub fn render_ui(
         .margin(1)
         .constraints([
             Constraint::Length(system.cpus().len() as u16 + 4),
-            Constraint::Length(3),
-            Constraint::Length(3),
-            Constraint::Length(3),
+            Constraint::Length(if app_state.pressure_view { 6 } else { 3 }),
+            Constraint::Length(4),
+            Constraint::Length(7),
             Constraint::Min(5),
         ])
         .split(area);
@@ -37,9 +44,9 @@ pub fn render_ui(
      */
 
     render_cpu_section(frame, system, cpu_history, app_state, layout[0]);
-    render_cpu_average(frame, system, disks, app_state, layout[1]);
-    render_memory(frame, system, app_state, layout[2]);
-    render_network(frame, networks, app_state, layout[3]);
+    render_cpu_average(frame, system, app_state, layout[1]);
+    render_memory(frame, app_state, layout[2]);
+    render_network(frame, app_state, layout[3]);
     render_processes_optimized(frame, system, processes, app_state, layout[4]);
 
     // Process effects
@@ -126,9 +133,20 @@ $$ |               \$$$$  |\$$$$$$  |$$$$$$$  |
         render_pause_menu(app_state, area, buf, after_title_y);
     }
 
-    // Render popup last so it overlays everything
-    if app_state.popup_visible {
-        render_popup(frame, app_state, area);
+    if app_state.signal_menu_visible {
+        render_signal_menu(frame, app_state, area);
+    }
+
+    if let Some(dialog) = &app_state.control_dialog {
+        render_control_dialog(frame, dialog, area);
+    }
+
+    if app_state.columns_panel_visible {
+        render_columns_panel(frame, app_state, area);
+    }
+
+    if let Some(details) = &app_state.details {
+        render_details_pane(frame, details, app_state.details_scroll, area);
     }
 
     // Render theme panel over everything when visible
@@ -145,6 +163,25 @@ $$ |               \$$$$  |\$$$$$$  |$$$$$$$  |
     if app_state.daemon_panel_visible {
         render_daemon_settings_panel(frame, app_state, area);
     }
+
+    // Render the live service manager over everything when visible (integrated mode)
+    if app_state.service_manager_visible {
+        app_state.update_service_snapshots_if_needed();
+        render_service_manager_panel(frame, system, app_state, area);
+    }
+
+    if app_state.disk_panel_visible {
+        render_disk_panel(frame, app_state, area);
+    }
+
+    if app_state.sensors_panel_visible {
+        render_sensors_panel(frame, app_state, area);
+    }
+
+    // Render popup last so it overlays everything
+    if app_state.popup_visible {
+        render_popup(frame, app_state, area);
+    }
 }
 fn render_pause_menu(app_state: &AppState, area: Rect, buf: &mut ratatui::buffer::Buffer, after_title_y: u16) {
     let menu_options = [
@@ -234,12 +271,17 @@ fn render_popup(frame: &mut ratatui::Frame, app_state: &AppState, area: Rect) {
         }
     }
     
-    // Create the popup block
+    // Create the popup block, notices (signal results) aren't errors so they get the calmer look
+    let (title, color) = if app_state.popup_is_error {
+        (" ⚠ Error ", Color::Red)
+    } else {
+        (" Notice ", Color::Cyan)
+    };
     let block = Block::default()
-        .title(" ⚠ Error ")
-        .title_style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+        .title(title)
+        .title_style(Style::default().fg(color).add_modifier(Modifier::BOLD))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::Red))
+        .border_style(Style::default().fg(color))
         .style(Style::default().bg(Color::Black));
     
     let inner_area = block.inner(popup_area);
@@ -270,6 +312,331 @@ fn render_popup(frame: &mut ratatui::Frame, app_state: &AppState, area: Rect) {
     frame.render_widget(instructions, chunks[1]);
 }
 
+fn render_signal_menu(frame: &mut ratatui::Frame, app_state: &AppState, area: Rect) {
+    let Some(choice) = app_state.signal_targets() else {
+        return;
+    };
+    let target = choice.describe();
+
+    // the confirmation lists the pids that are about to get it, in the order they'll be signalled
+    const PREVIEW_LINES: u16 = 4;
+    let panel_width = area.width.saturating_sub(12).min(76);
+    let skipped_line = if choice.excluded.is_empty() { 0 } else { 1 };
+    let panel_height = SIGNALS.len() as u16 + 7 + if app_state.signal_confirm { PREVIEW_LINES + skipped_line } else { 0 };
+    let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
+    let panel_area = Rect::new(x, y, panel_width, panel_height.min(area.height));
+
+    let block = Block::default()
+        .title(" Send signal ")
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(Clear, panel_area);
+    let inner = block.inner(panel_area);
+    frame.render_widget(block, panel_area);
+
+    let choices = app_state.signal_choices.len();
+    let mut target_spans = vec![
+        Span::styled(" Target: ", Style::default().fg(Color::Gray)),
+        Span::styled(target.clone(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+    ];
+    if choices > 1 {
+        target_spans.push(Span::styled(
+            format!("  ←/→ {}/{}", app_state.signal_choice + 1, choices),
+            Style::default().fg(Color::LightCyan),
+        ));
+    }
+    let mut lines = vec![Line::from(target_spans), Line::from("")];
+
+    lines.extend(SIGNALS.iter()
+        .enumerate()
+        .map(|(i, signal)| {
+            let selected = i == app_state.signal_selected;
+            let style = if selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            Line::from(vec![
+                Span::styled(if selected { " > " } else { "   " }, style),
+                Span::styled(format!("{:<3} {:<9}", signal.number, signal.name), style),
+                Span::styled(signal.description, Style::default().fg(Color::Gray)),
+            ])
+        }));
+
+    lines.push(Line::from(""));
+    let selected = &SIGNALS[app_state.signal_selected.min(SIGNALS.len() - 1)];
+    if app_state.signal_confirm {
+        lines.push(Line::from(Span::styled(
+            format!(" Send {} to {}? y/Enter: send  n/Esc: back", selected.name, target),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+
+        let width = inner.width.saturating_sub(2) as usize;
+        let mut preview: Vec<String> = vec![String::new()];
+        for (i, (pid, name)) in choice.targets.iter().enumerate() {
+            let entry = format!("{} {}", pid, name);
+            let current = preview.last_mut().unwrap();
+            // count chars not bytes, process names can be non-ascii
+            if current.chars().count() + entry.chars().count() + 2 > width {
+                if preview.len() as u16 == PREVIEW_LINES {
+                    let more = format!("... +{} more", choice.targets.len() - i);
+                    let last = preview.last_mut().unwrap();
+                    let keep = width.saturating_sub(more.len());
+                    if let Some((cut, _)) = last.char_indices().nth(keep) {
+                        last.truncate(cut);
+                    }
+                    last.push_str(&more);
+                    break;
+                }
+                preview.push(String::new());
+            }
+            let current = preview.last_mut().unwrap();
+            if !current.is_empty() {
+                current.push_str(", ");
+            }
+            current.push_str(&entry);
+        }
+        lines.extend(preview.into_iter().map(|line| {
+            Line::from(Span::styled(format!(" {}", line), Style::default().fg(Color::Gray)))
+        }));
+        if !choice.excluded.is_empty() {
+            let skipped: Vec<String> = choice.excluded.iter().map(|(pid, name)| format!("{} {}", pid, name)).collect();
+            lines.push(Line::from(Span::styled(
+                format!(" skipped (r-top and its parents): {}", skipped.join(", ")),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+    } else {
+        lines.push(Line::from(Span::styled(
+            " ↑/↓ signal  ←/→ target  Enter: send  Esc: cancel",
+            Style::default().fg(Color::LightCyan),
+        )));
+    }
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
+fn render_columns_panel(frame: &mut ratatui::Frame, app_state: &AppState, area: Rect) {
+    let columns = &app_state.user_settings.columns;
+    let panel_width = area.width.saturating_sub(12).min(72);
+    let panel_height = (columns.len() as u16 + 5).min(area.height);
+    let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
+    let panel_area = Rect::new(x, y, panel_width, panel_height);
+
+    let block = Block::default()
+        .title(" Columns ")
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(Clear, panel_area);
+    let inner = block.inner(panel_area);
+    frame.render_widget(block, panel_area);
+
+    // shown columns in table order, hidden ones greyed out
+    let mut lines: Vec<Line> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let selected = i == app_state.columns_selected;
+            let style = if selected {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else if column.visible {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            let sort = if column.kind == app_state.sort_column { "  (sort)" } else { "" };
+            Line::from(Span::styled(
+                format!(
+                    "{}[{}] {:<9} {:>3}{}  {}{}",
+                    if selected { "> " } else { "  " },
+                    if column.visible { "x" } else { " " },
+                    column.kind.label(),
+                    column.width,
+                    if column.kind.flexible() { "+" } else { " " },
+                    column.kind.description(),
+                    sort
+                ),
+                style,
+            ))
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " Space: show/hide  ←/→ width  [/]: move  s: sort  Esc: save & close",
+        Style::default().fg(Color::LightCyan),
+    )));
+
+    // keep the selection on screen when the terminal is too short for the whole list
+    let scroll = app_state.columns_selected.saturating_sub(inner.height.saturating_sub(3) as usize);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll as u16, 0)), inner);
+}
+
+fn render_details_pane(frame: &mut ratatui::Frame, details: &ProcessDetails, scroll: usize, area: Rect) {
+    let panel_width = area.width.saturating_sub(8);
+    let panel_height = area.height.saturating_sub(4);
+    let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
+    let panel_area = Rect::new(x, y, panel_width, panel_height);
+
+    let title = if details.exited {
+        format!(" Details: {} ({}) [exited] ", details.name, details.pid)
+    } else {
+        format!(" Details: {} ({}) ", details.name, details.pid)
+    };
+    let block = Block::default()
+        .title(title)
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(Clear, panel_area);
+    let inner = block.inner(panel_area);
+    frame.render_widget(block, panel_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+
+    // section tabs, the one we're scrolled into is highlighted
+    let current = details.section_at(scroll);
+    let mut tabs = Vec::new();
+    for (i, section) in details.sections.iter().enumerate() {
+        let style = if i == current {
+            Style::default().fg(Color::Black).bg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        tabs.push(Span::styled(format!(" {} ", section.title), style));
+        tabs.push(Span::raw(" "));
+    }
+    frame.render_widget(Paragraph::new(Line::from(tabs)).wrap(Wrap { trim: false }), chunks[0]);
+
+    // flatten the sections and start at the scroll line, long lines (argv, env) wrap
+    let mut lines = Vec::with_capacity(details.line_count());
+    for section in &details.sections {
+        lines.push(Line::from(Span::styled(
+            format!("── {} ", section.title),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        )));
+        for line in &section.lines {
+            lines.push(Line::from(Span::styled(format!("  {}", line), Style::default().fg(Color::White))));
+        }
+        lines.push(Line::from(""));
+    }
+    let visible: Vec<Line> = lines.into_iter().skip(scroll).collect();
+    frame.render_widget(Paragraph::new(visible).wrap(Wrap { trim: false }), chunks[1]);
+
+    frame.render_widget(
+        Paragraph::new(Line::from(Span::styled(
+            format!(
+                " ↑/↓ PgUp/PgDn scroll  Tab/←/→ section  Home/End  f/Esc: close   line {}/{}",
+                scroll + 1,
+                details.line_count()
+            ),
+            Style::default().fg(Color::LightCyan),
+        ))),
+        chunks[2],
+    );
+}
+
+fn render_control_dialog(frame: &mut ratatui::Frame, dialog: &ControlDialog, area: Rect) {
+    let panel_width = area.width.saturating_sub(12).min(70);
+    let cpus_per_line = (panel_width.saturating_sub(4) / 4).max(1) as usize;
+    let cpu_lines = dialog.settings.affinity.len().div_ceil(cpus_per_line).max(1) as u16;
+    let panel_height = cpu_lines + 9;
+    let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
+    let panel_area = Rect::new(x, y, panel_width, panel_height.min(area.height));
+
+    let block = Block::default()
+        .title(format!(" Process control: {} ({}) ", dialog.name, dialog.pid))
+        .title_style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Yellow))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(Clear, panel_area);
+    let inner = block.inner(panel_area);
+    frame.render_widget(block, panel_area);
+
+    let field_style = |field: usize| {
+        if dialog.field == field {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::White)
+        }
+    };
+    // changed values are shown next to what the process has right now
+    let value_line = |field: usize, label: &str, value: String, was: String| {
+        let marker = if dialog.field == field { " > " } else { "   " };
+        let mut spans = vec![
+            Span::styled(format!("{}{:<14}", marker, label), field_style(field)),
+            Span::styled(format!("◀ {} ▶", value), field_style(field)),
+        ];
+        if value != was {
+            spans.push(Span::styled(format!("  (now {})", was), Style::default().fg(Color::Gray)));
+        }
+        Line::from(spans)
+    };
+
+    let (settings, original) = (&dialog.settings, &dialog.original);
+    let io_level = if settings.io_class.has_level() { settings.io_level.to_string() } else { "-".to_string() };
+    let old_io_level = if original.io_class.has_level() { original.io_level.to_string() } else { "-".to_string() };
+    let mut lines = vec![
+        value_line(0, "Nice", settings.nice.to_string(), original.nice.to_string()),
+        value_line(1, "I/O class", settings.io_class.as_str().to_string(), original.io_class.as_str().to_string()),
+        value_line(2, "I/O priority", io_level, old_io_level),
+        Line::from(vec![
+            Span::styled(format!("{}{:<14}", if dialog.field == 3 { " > " } else { "   " }, "CPU affinity"), field_style(3)),
+            Span::styled(cpu_list(&settings.affinity), field_style(3)),
+        ]),
+    ];
+
+    // one cell per cpu, filled when allowed, the cursor is underlined while the affinity row is picked
+    for chunk in settings.affinity.chunks(cpus_per_line).enumerate() {
+        let (line_index, cpus) = chunk;
+        let mut spans = vec![Span::raw("   ")];
+        for (i, enabled) in cpus.iter().enumerate() {
+            let cpu = line_index * cpus_per_line + i;
+            let mut style = if *enabled {
+                Style::default().fg(Color::Black).bg(Color::LightGreen)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            if dialog.field == 3 && cpu == dialog.cpu_cursor {
+                style = style.add_modifier(Modifier::UNDERLINED | Modifier::BOLD);
+            }
+            spans.push(Span::styled(format!("{:>3}", cpu), style));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::from(""));
+    let pending = dialog.pending_changes();
+    lines.push(Line::from(Span::styled(
+        if pending == 0 { " nothing changed".to_string() } else { format!(" {} change(s) pending", pending) },
+        Style::default().fg(Color::Gray),
+    )));
+    lines.push(Line::from(Span::styled(
+        " ↑/↓ pick  ←/→ change  Space: toggle cpu  a: all  Enter: apply  Esc: cancel",
+        Style::default().fg(Color::LightCyan),
+    )));
+
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), inner);
+}
+
 fn render_cpu_section(
     frame: &mut ratatui::Frame,
     system: &System,
@@ -277,8 +644,6 @@ fn render_cpu_section(
     app_state: &mut AppState,
     area: Rect,
 ) {
-    // Update GPU cache before any borrowing
-    app_state.update_gpu_cache_if_needed();
     let theme = app_state.theme_manager.current_theme();
     /*
     FOR SOME REASON! before the refactor 70/30 was FINE! it displayed all the things in CPU info but now its not?? I don't want to tweak this b.s again because
@@ -293,12 +658,24 @@ fn render_cpu_section(
     // Track the CPU/GPU usage panel area for effects
     app_state.cpu_usage_area = cpu_chunks[0];
 
-    let block_title = if app_state.gpu_usage_view { " GPU Usage (v: CPU) " } else { " CPU Usage (v: GPU) " };
+    let view_title = if app_state.gpu_usage_view { " GPU Usage (v: CPU) " } else { " CPU Usage (v: GPU) " };
+
+    // hottest sensor in the title, coloured once it gets close to its critical point
+    let hottest = app_state.sensors.hottest();
+    let block_title = match hottest.and_then(|t| t.celsius) {
+        Some(celsius) => format!("{}| {:.0}°C | e: Sensors ", view_title, celsius),
+        None => format!("{}| e: Sensors ", view_title),
+    };
+    let title_color = match hottest {
+        Some(t) if t.is_critical() => theme.error_text,
+        Some(t) if t.is_warning() => theme.warning_text,
+        _ => theme.primary_text,
+    };
 
     // Draw CPU cores or GPU usage based on toggle
     let bordered_block = Block::default()
         .title(block_title)
-        .title_style(Style::default().fg(theme.primary_text))
+        .title_style(Style::default().fg(title_color))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.primary_border));
 
@@ -342,13 +719,24 @@ fn render_cpu_section(
 
                 let split = Layout::default()
                     .direction(Direction::Horizontal)
-                    .constraints([Constraint::Length(12), Constraint::Min(10)])
+                    .constraints([Constraint::Length(17), Constraint::Min(10)])
                     .split(area);
 
-                let label = Paragraph::new(format!("Core {:>2}", i))
+                // current clock next to the core number, from cpufreq when there is one
+                let label_text = match app_state.sensors.core_frequencies.get(i) {
+                    Some(mhz) if *mhz > 0 => format!("Core {:>2} {:>4.2}GHz", i, *mhz as f64 / 1000.0),
+                    _ => format!("Core {:>2}", i),
+                };
+                let label = Paragraph::new(label_text)
                     .style(Style::default().fg(theme.primary_text));
                 frame.render_widget(label, split[0]);
 
+                // split by where the time went when /proc/stat has it, plain usage gauge otherwise
+                if let Some(breakdown) = app_state.cpu_times.cores.get(i) {
+                    render_stacked_bar(frame.buffer_mut(), split[1], breakdown, theme);
+                    continue;
+                }
+
                 let gauge = Gauge::default()
                     .gauge_style(Style::default().fg(color))
                     .ratio(ratio as f64)
@@ -357,7 +745,7 @@ fn render_cpu_section(
             }
         }
     } else {
-        let gpus = &app_state.gpu_info_cache;
+        let gpus = &app_state.gpu.gpus;
         let gpu_count = gpus.len();
 
         if gpu_count == 0 {
@@ -385,7 +773,7 @@ fn render_cpu_section(
 
                 for (idx, area) in (start..end).zip(rows.into_iter()) {
                     let gpu = &gpus[idx];
-                    let usage_val = gpu.utilization.trim_end_matches('%').parse::<f64>().unwrap_or(0.0);
+                    let usage_val = gpu.busy_percent.unwrap_or(0.0);
                     let ratio = (usage_val / 100.0).clamp(0.0, 1.0);
 
                     let color = if usage_val > 80.0 {
@@ -407,10 +795,15 @@ fn render_cpu_section(
                         .style(Style::default().fg(theme.primary_text));
                     frame.render_widget(label, split[0]);
 
+                    // i915/xe don't report a busy percentage in sysfs, don't pretend it's 0
+                    let gauge_label = match gpu.busy_percent {
+                        Some(busy) => format!("{:>5.1}%", busy),
+                        None => "n/a".to_string(),
+                    };
                     let gauge = Gauge::default()
                         .gauge_style(Style::default().fg(color))
                         .ratio(ratio)
-                        .label(format!("{:>5.1}%", usage_val));
+                        .label(gauge_label);
                     frame.render_widget(gauge, split[1]);
                 }
             }
@@ -436,47 +829,57 @@ fn render_cpu_section(
         theme.cpu_low
     };
 
-    let graph = Sparkline::default()
-        .block(
-            Block::default()
-                .title(format!(
-                    " CPU Avg Usage (0–100%) - {}ms | Set Refresh: +/- ",
-                    app_state.refresh_interval.as_millis()
-                ))
-                .title_style(Style::default().fg(theme.primary_text))
-                .borders(Borders::ALL),
-        )
-        .style(Style::default().fg(graph_color))
-        .data(&avg_history)
-        .max(100)
-        .bar_set(Set::default());
+    let graph_block = Block::default()
+        .title(format!(
+            " CPU Avg Usage (0–100%) - {}ms | Set Refresh: +/- ",
+            app_state.refresh_interval.as_millis()
+        ))
+        .title_style(Style::default().fg(theme.primary_text))
+        .borders(Borders::ALL);
 
-    frame.render_widget(graph, right_chunks[0]);
+    if app_state.cpu_times.available {
+        let graph_area = graph_block.inner(right_chunks[0]);
+        frame.render_widget(graph_block, right_chunks[0]);
+        render_stacked_history(frame.buffer_mut(), graph_area, &app_state.cpu_times.history.to_vec(), theme);
+    } else {
+        let graph = Sparkline::default()
+            .block(graph_block)
+            .style(Style::default().fg(graph_color))
+            .data(&avg_history)
+            .max(100)
+            .bar_set(Set::default());
+
+        frame.render_widget(graph, right_chunks[0]);
+    }
 
     // GPU Info (use cached data - already updated at function start)
-    let gpu_index = app_state.current_gpu_index.min(app_state.gpu_info_cache.len().saturating_sub(1));
-    let gpu = &app_state.gpu_info_cache[gpu_index];
+    let gpus = &app_state.gpu.gpus;
+    let gpu_index = app_state.current_gpu_index.min(gpus.len().saturating_sub(1));
 
-    let gpu_title = if app_state.gpu_info_cache.len() > 1 {
-        format!(" GPU Info ({}/{}) - Use g/G to cycle ", gpu_index + 1, app_state.gpu_info_cache.len())
+    let gpu_title = if gpus.len() > 1 {
+        format!(" GPU Info ({}/{}) - Use g/G to cycle ", gpu_index + 1, gpus.len())
     } else {
         " GPU Info ".to_string()
     };
 
-    let gpu_info_text = format!(
-        "Model: {}\n\
-        Driver: {}\n\
-        Memory Total: {}\n\
-        Memory Used:  {}\n\
-        Temperature:  {}\n\
-        Utilization:  {}",
-        gpu.name,
-        gpu.driver_version,
-        gpu.memory_total,
-        gpu.memory_used,
-        gpu.temperature,
-        gpu.utilization
-    );
+    let na = || "N/A".to_string();
+    let gpu_info_text = match gpus.get(gpu_index) {
+        Some(gpu) => format!(
+            "Model: {}\n\
+            Driver: {}\n\
+            Memory Total: {}\n\
+            Memory Used:  {}\n\
+            Temperature:  {}\n\
+            Utilization:  {}",
+            gpu.name,
+            gpu.driver.clone().unwrap_or_else(na),
+            gpu.memory_total.map_or_else(na, format_bytes),
+            gpu.memory_used.map_or_else(na, format_bytes),
+            gpu.temperature.map_or_else(na, |t| format!("{:.0}°C", t)),
+            gpu.busy_percent.map_or_else(na, |b| format!("{:.0}%", b)),
+        ),
+        None => "No GPU detected".to_string(),
+    };
 
     let gpu_info_paragraph = Paragraph::new(gpu_info_text)
         .style(Style::default().fg(theme.secondary_text))
@@ -491,10 +894,73 @@ fn render_cpu_section(
     frame.render_widget(gpu_info_paragraph, right_chunks[1]);
 }
 
+// colours for CpuBreakdown::segments(), user first
+fn cpu_segment_colors(theme: &Theme) -> [Color; 5] {
+    [theme.cpu_low, theme.cpu_medium, theme.cpu_high, theme.cpu_critical, theme.warning_text]
+}
+
+// one horizontal bar split into user/system/irq/steal/iowait, rounded per cell
+fn render_stacked_bar(buf: &mut ratatui::buffer::Buffer, area: Rect, breakdown: &CpuBreakdown, theme: &Theme) {
+    let label = format!(" {:>5.1}%", breakdown.busy());
+    let bar_width = area.width.saturating_sub(label.len() as u16) as f64;
+    let colors = cpu_segment_colors(theme);
+
+    let mut x = area.x;
+    let mut filled = 0.0;
+    for (value, color) in breakdown.segments().iter().zip(colors) {
+        filled += value;
+        let end = area.x + ((filled / 100.0).clamp(0.0, 1.0) * bar_width).round() as u16;
+        while x < end {
+            buf[(x, area.y)].set_symbol("█").set_fg(color);
+            x += 1;
+        }
+    }
+    buf.set_string(area.x + bar_width as u16, area.y, label, Style::default().fg(theme.primary_text));
+}
+
+// stacked column per sample, newest on the right. each cell takes the colour of the segment at its middle
+fn render_stacked_history(buf: &mut ratatui::buffer::Buffer, area: Rect, history: &[CpuBreakdown], theme: &Theme) {
+    const PARTIAL: [&str; 8] = [" ", "▁", "▂", "▃", "▄", "▅", "▆", "▇"];
+    let colors = cpu_segment_colors(theme);
+    let height = area.height as f64;
+    let visible = &history[history.len().saturating_sub(area.width as usize)..];
+    let start_x = area.x + area.width - visible.len() as u16;
+
+    for (i, sample) in visible.iter().enumerate() {
+        let x = start_x + i as u16;
+        let segments = sample.segments();
+        let top = segments.iter().sum::<f64>().min(100.0);
+
+        let color_at = |level: f64| {
+            let mut acc = 0.0;
+            for (value, color) in segments.iter().zip(colors) {
+                acc += value;
+                if level <= acc {
+                    return color;
+                }
+            }
+            colors[colors.len() - 1]
+        };
+
+        for row in 0..area.height {
+            let cell_bottom = row as f64 * 100.0 / height;
+            let cell_top = (row + 1) as f64 * 100.0 / height;
+            let y = area.y + area.height - 1 - row;
+            if top >= cell_top {
+                buf[(x, y)].set_symbol("█").set_fg(color_at((cell_bottom + cell_top) / 2.0));
+            } else if top > cell_bottom {
+                let eighths = ((top - cell_bottom) / (cell_top - cell_bottom) * 8.0) as usize;
+                if eighths > 0 {
+                    buf[(x, y)].set_symbol(PARTIAL[eighths.min(7)]).set_fg(color_at(top));
+                }
+            }
+        }
+    }
+}
+
 fn render_cpu_average(
     frame: &mut ratatui::Frame, 
     system: &System, 
-    disks: &Disks,
     app_state: &mut AppState,
     area: Rect
 ) {
@@ -516,11 +982,22 @@ fn render_cpu_average(
         busiest_core_idx, busiest_core_usage, top_process_pid, top_process_name
     );
 
-    let avg_text = Paragraph::new(format!("{:<10}{}", left, right))
+    // usr/sys/irq/steal/iow split, each label in its bar colour so it doubles as the legend
+    let mut spans = vec![Span::raw(format!("{:<10}", left))];
+    if app_state.cpu_times.available {
+        let breakdown = app_state.cpu_times.total;
+        for ((label, value), color) in SEGMENT_LABELS.iter().zip(breakdown.segments()).zip(cpu_segment_colors(theme)) {
+            spans.push(Span::styled(format!("{} {:.1}% ", label, value), Style::default().fg(color)));
+        }
+        spans.push(Span::raw("| "));
+    }
+    spans.push(Span::raw(right));
+
+    let avg_text = Paragraph::new(Line::from(spans))
         .style(Style::default().fg(theme.primary_text))
         .block(
             Block::default()
-                .title(" CPU Average ")
+                .title(" CPU Average | l: Pressure ")
                 .title_style(Style::default().fg(theme.primary_text))
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(avg_color)),
@@ -532,13 +1009,19 @@ fn render_cpu_average(
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
         .split(area);
 
-    frame.render_widget(avg_text, avg_chunks[0]);
+    if app_state.pressure_view {
+        render_pressure(frame, system, app_state, avg_chunks[0]);
+    } else {
+        frame.render_widget(avg_text, avg_chunks[0]);
+    }
+    app_state.cpu_average_area = avg_chunks[0];
 
-    // Disk gauge in the CPU section (30% space)
-    let disks_list = disks.list();
-    let current_disk = disks_list.get(app_state.current_disk_index).unwrap_or(&disks_list[0]);
-    let name = current_disk.name().to_string_lossy();
-    let title = format!("Disk: {} | Switch Disk: u/i ", name);
+    // Disk gauge in the CPU section (30% space), d opens the full per-disk panel
+    let current_disk = app_state.disk_monitor.get(app_state.current_disk_index);
+    let title = match current_disk {
+        Some(disk) => format!("Disk: {} | u/i | d: details ", disk.name),
+        None => "Disk: none | d: details ".to_string(),
+    };
 
     let disk_block = Block::default()
         .title(title)
@@ -546,32 +1029,131 @@ fn render_cpu_average(
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.secondary_border));
 
-    let usage = current_disk.total_space().saturating_sub(current_disk.available_space()) as f64
-        / current_disk.total_space().max(1) as f64;
+    let usage = current_disk.map(|d| d.usage()).unwrap_or(0.0);
+    let label = match current_disk {
+        Some(disk) if disk.device.is_some() => format!(
+            "{:.1}% | R {} W {}",
+            usage * 100.0, format_rate(disk.read_rate), format_rate(disk.write_rate)
+        ),
+        _ => format!("{:.1}%", usage * 100.0),
+    };
 
     let disk_gauge = Gauge::default()
         .block(Block::default().borders(Borders::NONE))
         .gauge_style(Style::default().fg(theme.gauge_primary).bg(theme.gauge_background))
-        .ratio(usage)
-        .label(format!("{:.1}%", usage * 100.0));
+        .ratio(usage.clamp(0.0, 1.0))
+        .label(label);
 
+    // one line high even when the pressure view makes the row taller
     let disk_inner = disk_block.inner(avg_chunks[1]);
+    let disk_inner = Rect { height: disk_inner.height.min(1), ..disk_inner };
     app_state.disk_area = avg_chunks[1];
     frame.render_widget(disk_block, avg_chunks[1]);
     frame.render_widget(disk_gauge, disk_inner);
 }
 
+// psi some/full averages per resource, load averages with their history and uptime
+fn render_pressure(frame: &mut ratatui::Frame, system: &System, app_state: &AppState, area: Rect) {
+    let theme = app_state.theme_manager.current_theme();
+    let pressure = &app_state.pressure;
+
+    let block = Block::default()
+        .title(format!(" Pressure | Uptime {} | l: CPU Average ", format_duration(pressure.uptime_secs)))
+        .title_style(Style::default().fg(theme.primary_text))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.primary_border));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(inner);
+
+    // anything stalled for more than a tenth of the time is worth a look
+    let color_for = |avg10: f64| {
+        if avg10 > 40.0 {
+            theme.cpu_critical
+        } else if avg10 > 10.0 {
+            theme.cpu_high
+        } else {
+            theme.primary_text
+        }
+    };
+    let format_line = |line: &crate::pressure::PressureLine| {
+        format!("{:>6.2} {:>6.2} {:>6.2}", line.avg10, line.avg60, line.avg300)
+    };
+
+    let mut lines = vec![Line::from(Span::styled(
+        "       some avg10/60/300     full avg10/60/300",
+        Style::default().fg(theme.secondary_text),
+    ))];
+    for (label, resource) in [("CPU", &pressure.cpu), ("MEM", &pressure.memory), ("IO ", &pressure.io)] {
+        let line = match resource {
+            Some(p) => {
+                let full = p.full.as_ref().map_or("     -      -      -".to_string(), format_line);
+                let worst = p.some.avg10.max(p.full.map_or(0.0, |f| f.avg10));
+                Line::from(vec![
+                    Span::styled(format!("{}  ", label), Style::default().fg(theme.secondary_text)),
+                    Span::styled(format!("{}  {}", format_line(&p.some), full), Style::default().fg(color_for(worst))),
+                ])
+            }
+            None => Line::from(Span::styled(format!("{}  not available (no psi support)", label), Style::default().fg(theme.secondary_text))),
+        };
+        lines.push(line);
+    }
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Length(1), Constraint::Length(1)])
+        .split(chunks[1]);
+
+    let (one, five, fifteen) = pressure.load;
+    let cores = system.cpus().len().max(1) as f64;
+    let load_color = if one > cores { theme.cpu_high } else { theme.primary_text };
+    frame.render_widget(
+        Paragraph::new(format!("Load {:.2} {:.2} {:.2} ({} cores)", one, five, fifteen, cores as usize))
+            .style(Style::default().fg(load_color)),
+        rows[0],
+    );
+
+    // same scale for all three so they can be compared, and a load of 1.0 never looks like a spike
+    let max = pressure.load_history.iter().flat_map(|h| h.to_vec()).max().unwrap_or(0).max(100);
+    for (i, label) in ["1m ", "5m ", "15m"].iter().enumerate() {
+        let parts = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(4), Constraint::Min(1)])
+            .split(rows[i + 1]);
+        frame.render_widget(Paragraph::new(*label).style(Style::default().fg(theme.secondary_text)), parts[0]);
+
+        let data = pressure.load_history[i].to_vec();
+        let visible = &data[data.len().saturating_sub(parts[1].width as usize)..];
+        let sparkline = Sparkline::default()
+            .style(Style::default().fg(theme.cpu_low))
+            .data(visible)
+            .max(max)
+            .bar_set(Set::default());
+        frame.render_widget(sparkline, parts[1]);
+    }
+}
+
 fn render_memory(
     frame: &mut ratatui::Frame,
-    system: &System,
     app_state: &AppState,
     area: Rect,
 ) {
     let theme = app_state.theme_manager.current_theme();
-    // Memory gauge taking up 100% of the area (no spacing)
-    let used_gib = memory_used_gib(system);       
-    let total_gib = (system.total_memory() as f64) / 1024.0 / 1024.0; 
-    let ratio = (used_gib / total_gib).clamp(0.0, 1.0);
+    let monitor = &app_state.memory_monitor;
+    let mem = monitor.stats;
+
+    // 70% for ram, 30% for swap, like the cpu/disk split above
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+        .split(area);
+
+    let ratio = (mem.used as f64 / mem.total.max(1) as f64).clamp(0.0, 1.0);
     let mem_color = if ratio > 0.9 {
         theme.memory_critical
     } else if ratio > 0.7 {
@@ -580,72 +1162,140 @@ fn render_memory(
         theme.memory_normal
     };
 
+    let mem_block = Block::default()
+        .title(" Memory Usage ")
+        .title_style(Style::default().fg(theme.primary_text))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.secondary_border));
+    let mem_inner = mem_block.inner(chunks[0]);
+    frame.render_widget(mem_block, chunks[0]);
+
+    let mem_rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(mem_inner);
+
     let memory_gauge = Gauge::default()
-        .block(
-            Block::default()
-                .title(" Memory Usage ")
-                .title_style(Style::default().fg(theme.primary_text))
-                .borders(Borders::ALL),
-        )
-        .set_style(Style::default().fg(theme.secondary_border))
         .gauge_style(Style::default().fg(mem_color).bg(theme.gauge_background))
         .ratio(ratio)
-        .label(format!("{:.2} / {:.2} GiB", used_gib / 1024.0, total_gib / 1024.0));
+        .label(format!("{:.2} / {:.2} GiB", bytes_to_gib(mem.used), bytes_to_gib(mem.total)));
+    frame.render_widget(memory_gauge, mem_rows[0]);
+
+    let breakdown = if monitor.detailed {
+        format!(
+            "Used {} | Cached {} | Buffers {} | Shared {} | Free {} | Available {}",
+            format_bytes(mem.used), format_bytes(mem.cached), format_bytes(mem.buffers),
+            format_bytes(mem.shared), format_bytes(mem.free), format_bytes(mem.available),
+        )
+    } else {
+        format!("Used {} | Free {} | Available {}", format_bytes(mem.used), format_bytes(mem.free), format_bytes(mem.available))
+    };
+    frame.render_widget(Paragraph::new(breakdown).style(Style::default().fg(theme.secondary_text)), mem_rows[1]);
+
+    let swap_ratio = (mem.swap_used as f64 / mem.swap_total.max(1) as f64).clamp(0.0, 1.0);
+    // swapping right now matters more than how full swap is
+    let swapping = mem.swap_in_rate + mem.swap_out_rate > 0.0;
+    let swap_block = Block::default()
+        .title(" Swap ")
+        .title_style(Style::default().fg(theme.primary_text))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(if swapping { theme.warning_text } else { theme.secondary_border }));
+    let swap_inner = swap_block.inner(chunks[1]);
+    frame.render_widget(swap_block, chunks[1]);
+
+    let swap_rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(swap_inner);
+
+    let swap_label = if mem.swap_total == 0 {
+        "no swap".to_string()
+    } else {
+        format!("{} / {}", format_bytes(mem.swap_used), format_bytes(mem.swap_total))
+    };
+    let swap_gauge = Gauge::default()
+        .gauge_style(Style::default().fg(theme.gauge_primary).bg(theme.gauge_background))
+        .ratio(swap_ratio)
+        .label(swap_label);
+    frame.render_widget(swap_gauge, swap_rows[0]);
 
-    frame.render_widget(memory_gauge, area);
+    let swap_rates = Paragraph::new(format!("In {} | Out {}", format_rate(mem.swap_in_rate), format_rate(mem.swap_out_rate)))
+        .style(Style::default().fg(if swapping { theme.warning_text } else { theme.secondary_text }));
+    frame.render_widget(swap_rates, swap_rows[1]);
 }
 
 fn render_network(
     frame: &mut ratatui::Frame,
-    networks: &Networks,
     app_state: &mut AppState,
     area: Rect,
 ) {
     let theme = app_state.theme_manager.current_theme();
-    let eth0 = networks.get("eth0");
-    let lo = networks.get("lo");
-    let net = if app_state.current_interface == "eth0" { eth0 } else { lo };
+    let monitor = &app_state.net_monitor;
+    let position = monitor.position(&app_state.current_interface).map_or(0, |p| p + 1);
 
-    let (rx, tx) = match net {
-        Some(n) => (n.received(), n.transmitted()),
-        None => (0, 0),
+    let block = Block::default()
+        .title(format!(
+            " Network | {} ({}/{}) | Switch: b/n ",
+            app_state.current_interface, position, monitor.len()
+        ))
+        .title_style(Style::default().fg(theme.primary_text))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.network_border));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let Some(iface) = monitor.get(&app_state.current_interface) else {
+        let waiting = Paragraph::new("Waiting for interface data...")
+            .style(Style::default().fg(theme.secondary_text));
+        frame.render_widget(waiting, inner);
+        app_state.net_area = area;
+        return;
     };
 
-    let (_delta_rx, _delta_tx, total_delta) = unsafe {
-        let delta_rx = rx.saturating_sub(PREV_RX);
-        let delta_tx = tx.saturating_sub(PREV_TX);
-        PREV_RX = rx;
-        PREV_TX = tx;
-        let total_delta = delta_rx + delta_tx;
-        (delta_rx, delta_tx, total_delta)
-    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1), Constraint::Min(1)])
+        .split(inner);
+
+    let rates = Paragraph::new(format!(
+        "▽ RX: {} (session {}) | △ TX: {} (session {}) | Since boot: {} / {}",
+        format_rate(iface.rx_rate),
+        format_bytes(iface.session_rx),
+        format_rate(iface.tx_rate),
+        format_bytes(iface.session_tx),
+        format_bytes(iface.total_rx),
+        format_bytes(iface.total_tx),
+    ))
+    .style(Style::default().fg(theme.primary_text));
 
-    unsafe {
-        SESSION_TOTAL_BYTES += total_delta;
-    }
+    let addresses = if iface.addresses.is_empty() { "none".to_string() } else { iface.addresses.join(", ") };
+    let error_style = if iface.errors_rx + iface.errors_tx > 0 { theme.warning_text } else { theme.secondary_text };
+    let details = Paragraph::new(Line::from(vec![
+        Span::styled(format!("Packets: {} / {} | ", iface.packets_rx, iface.packets_tx), Style::default().fg(theme.secondary_text)),
+        Span::styled(format!("Errors: {} / {}", iface.errors_rx, iface.errors_tx), Style::default().fg(error_style)),
+        Span::styled(format!(" | IP: {} | MAC: {}", addresses, iface.mac), Style::default().fg(theme.secondary_text)),
+    ]));
 
-    let total_mib = (rx + tx) as f64 / 1024.0 / 1024.0;
-    let session_mib = unsafe { SESSION_TOTAL_BYTES as f64 / 1024.0 / 1024.0 };
+    frame.render_widget(rates, rows[0]);
+    frame.render_widget(details, rows[1]);
 
-    let net_text = Paragraph::new(format!(
-        "{} | ▽ RX: {} | △ TX: {} | ▷ RX+TX: {:.2} MiB | Session: {:.2} MiB",
-        app_state.current_interface,
-        format_bytes(rx),
-        format_bytes(tx),
-        total_mib,
-        session_mib
-    ))
-    .style(Style::default().fg(theme.primary_text))
-    .block(
-        Block::default()
-            .title(" Network Usage | Switch: b/n ")
-            .title_style(Style::default().fg(theme.primary_text))
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.network_border)),
-    );
+    let graphs = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(rows[2]);
+
+    for (history, chunk, color) in [(&iface.rx_history, graphs[0], theme.cpu_low), (&iface.tx_history, graphs[1], theme.cpu_medium)] {
+        // newest sample on the right, so only keep what fits
+        let data = history.to_vec();
+        let visible = &data[data.len().saturating_sub(chunk.width as usize)..];
+        let sparkline = Sparkline::default()
+            .style(Style::default().fg(color))
+            .data(visible)
+            .bar_set(Set::default());
+        frame.render_widget(sparkline, chunk);
+    }
 
     app_state.net_area = area;
-    frame.render_widget(net_text, area);
 }
 
 fn render_processes_optimized(
@@ -673,26 +1323,133 @@ fn render_processes_optimized(
     
     let filtered_processes = sort_and_filter_processes_cached(system, app_state);
 
-    app_state.visible_rows = area.height.saturating_sub(3) as usize;
+    // the info view gets an i/o history strip for the selected process under the table
+    let (process_area, io_area) = if app_state.show_info {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(5), Constraint::Length(5)])
+            .split(process_area);
+        (chunks[0], Some(chunks[1]))
+    } else {
+        (process_area, None)
+    };
 
-    let max_processes = filtered_processes.len();
-    if app_state.selected_process >= max_processes && max_processes > 0 {
-        app_state.selected_process = max_processes - 1;
-    }
+    let io_height = io_area.map_or(0, |a| a.height);
+    app_state.visible_rows = area.height.saturating_sub(3 + io_height) as usize;
+
+    app_state.sync_selection(system, &filtered_processes);
     
     let show_tree = app_state.show_info && app_state.show_tree_view;
 
-    if show_tree{
+    if app_state.thread_view.is_some() {
+        render_thread_view(frame, app_state, process_area);
+    } else if app_state.group_view {
+        render_cgroup_groups(frame, app_state, process_area);
+    } else if show_tree {
         render_tree_view(frame, system, app_state, process_area);
     } else {
         render_flat_view(frame, system, &filtered_processes, app_state, process_area);
     }
+
+    if let Some(io_area) = io_area {
+        let selected_pid = if show_tree {
+            app_state.get_selected_tree_item().map(|item| item.pid.as_u32())
+        } else {
+            filtered_processes.get(app_state.selected_process).map(|proc| proc.pid().as_u32())
+        };
+        let memory = selected_pid.and_then(|pid| app_state.process_memory.get(pid, app_state.refresh_interval));
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Length(34), Constraint::Min(20)])
+            .split(io_area);
+        let gpu_memory = selected_pid.and_then(|pid| app_state.gpu.get(pid)).map(|g| g.memory);
+        render_process_memory(frame, app_state, memory, gpu_memory, chunks[0]);
+        render_process_io_history(frame, app_state, selected_pid, chunks[1]);
+    }
 }
 
-fn render_tree_view(
+// rss/pss/uss/swap of the selected process, see memory_stats for what each one means
+fn render_process_memory(
     frame: &mut ratatui::Frame,
-    system: &System,
-    app_state: &mut AppState,
+    app_state: &AppState,
+    memory: Option<ProcessMemory>,
+    gpu_memory: Option<u64>,
+    area: Rect,
+) {
+    let theme = app_state.theme_manager.current_theme();
+    let lines = match memory {
+        Some(mem) => vec![
+            Line::from(format!("RSS {:>11}  PSS {:>11}", format_bytes(mem.rss), format_bytes(mem.pss))),
+            Line::from(format!("USS {:>11}  Swap {:>10}", format_bytes(mem.uss), format_bytes(mem.swap))),
+            Line::from(Span::styled(
+                match gpu_memory {
+                    Some(gpu) => format!("Shared {}  GPU {}", format_bytes(mem.rss.saturating_sub(mem.uss)), format_bytes(gpu)),
+                    None => format!("Shared {}", format_bytes(mem.rss.saturating_sub(mem.uss))),
+                },
+                Style::default().fg(theme.secondary_text),
+            )),
+        ],
+        None => vec![Line::from(Span::styled("smaps_rollup not readable", Style::default().fg(theme.secondary_text)))],
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.primary_text))
+        .block(
+            Block::default()
+                .title(" Process Memory ")
+                .title_style(Style::default().fg(theme.primary_text))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.secondary_border)),
+        );
+    frame.render_widget(paragraph, area);
+}
+
+fn render_process_io_history(
+    frame: &mut ratatui::Frame,
+    app_state: &AppState,
+    pid: Option<u32>,
+    area: Rect,
+) {
+    let theme = app_state.theme_manager.current_theme();
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let (reads, writes) = pid.map(|p| app_state.io_usage.history(p)).unwrap_or_default();
+    let stats = pid.and_then(|p| app_state.io_usage.get(p)).copied().unwrap_or_default();
+    let pid_label = pid.map_or("-".to_string(), |p| p.to_string());
+
+    let panels = [
+        (format!(" Disk Read (PID {}) {} | total {} ", pid_label, format_rate(stats.read_rate), format_bytes(stats.total_read)), reads, theme.cpu_low),
+        (format!(" Disk Write (PID {}) {} | total {} ", pid_label, format_rate(stats.write_rate), format_bytes(stats.total_written)), writes, theme.cpu_high),
+    ];
+
+    for ((title, history, color), chunk) in panels.into_iter().zip(chunks.iter()) {
+        // newest sample on the right, so only keep what fits
+        let width = chunk.width.saturating_sub(2) as usize;
+        let visible = &history[history.len().saturating_sub(width)..];
+
+        let sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .title(title)
+                    .title_style(Style::default().fg(theme.primary_text))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.secondary_border)),
+            )
+            .style(Style::default().fg(color))
+            .data(visible)
+            .bar_set(Set::default());
+
+        frame.render_widget(sparkline, *chunk);
+    }
+}
+
+fn render_tree_view(
+    frame: &mut ratatui::Frame,
+    system: &System,
+    app_state: &mut AppState,
     area: Rect,
 ) {
     let tree_items = build_process_tree(system, app_state);
@@ -703,6 +1460,9 @@ fn render_tree_view(
     let visible_rows = app_state.visible_rows;
     let tree_selected_index = app_state.tree_selected_index;
     
+    let columns = app_state.visible_columns();
+    let context = ColumnContext::new(system, &app_state.net_usage, &app_state.io_usage, &app_state.process_stats, &app_state.cgroups);
+
     // tree rows for visible items
     let mut rows: Vec<Row> = Vec::new();
     let visible_start = tree_selected_index.saturating_sub(visible_rows / 2);
@@ -728,8 +1488,14 @@ fn render_tree_view(
         let name_with_tree = format!("{}{}{}", indent, expansion_indicator, item.name);
         
       
+        let tagged = app_state.is_tagged(item.pid.as_u32());
+        let frozen = system.process(item.pid).is_some_and(|p| p.status() == ProcessStatus::Stop);
         let color = if is_selected {
             theme.process_selected
+        } else if tagged {
+            theme.warning_text
+        } else if frozen {
+            theme.process_info
         } else if item.cpu_usage > 50.0 {
             theme.process_high_cpu
         } else if item.level > 0 {
@@ -738,41 +1504,33 @@ fn render_tree_view(
             theme.process_normal
         };
 
-        let row = Row::new(vec![
-            item.pid.to_string(),
-            name_with_tree,
-            format!("{:.2}%", item.cpu_usage),
-            format!("{:.2} MB", item.memory as f64 / 1024.0 / 1024.0),
-        ])
-        .style(Style::default().fg(color));
+        let Some(process) = system.process(item.pid) else {
+            continue;
+        };
+        let row = Row::new(process_cells(&columns, &context, process, tagged, frozen, Some(name_with_tree)))
+            .style(Style::default().fg(color));
 
         rows.push(row);
     }
 
     let (total_processes, expanded_nodes, max_depth) = get_tree_stats(&tree_items);
     
-    let header = Row::new(vec!["PID", "Process Tree", "CPU", "Memory"])
+    // siblings are always ordered by cpu in the tree, whatever the flat list sorts on
+    let header = column_header(&columns, ColumnKind::Cpu, true, "Process Tree")
         .style(Style::default().fg(theme.secondary_text));
 
-    let table = Table::new(
-        rows,
-        &[
-            Constraint::Length(8),
-            Constraint::Percentage(60),
-            Constraint::Length(10),
-            Constraint::Length(15),
-        ],
-    )
+    let table = Table::new(rows, column_constraints(&columns))
     .header(header)
     .style(Style::default().fg(theme.highlight_text))
     .block(
         Block::default()
             .title(format!(
-                " Process Tree ({}/{} processes, {} expanded, depth {}) - Tab: Switch View | ←→: Expand/Collapse | Enter: Toggle | k: Kill ",
+                " Process Tree ({}/{} processes, {} expanded, depth {}){} - Tab: Switch View | ←→: Expand/Collapse | Enter: Toggle | k: Kill ",
                 tree_items.len(),
                 total_processes,
                 expanded_nodes,
-                max_depth + 1
+                max_depth + 1,
+                tag_info(app_state)
             ))
             .title_style(Style::default().fg(theme.primary_text))
             .borders(Borders::ALL),
@@ -790,7 +1548,7 @@ fn render_search_bar(
     let theme = app_state.theme_manager.current_theme();
 
     let (search_text, title) = if app_state.search_query.is_empty() {
-        ("Type to search processes... (use 'pid:1234' for PID search)".to_string(),
+        ("Type to search processes... (use 'pid:1234' for PID search, 'ct:name' for container/cgroup)".to_string(),
         " Search Processes (ESC to exit, / to toggle) ")
     } else {
         let search_type = app_state.get_search_type();
@@ -798,10 +1556,16 @@ fn render_search_bar(
 
         match search_type {
             SearchType::Pid => {
-                (format!("pid:{}", search_value), " PID Search (ESC to exit) ")
+                (format!("pid:{}", search_value), " PID Search ")
             }
             SearchType::Name => {
-                (app_state.search_query.clone(), " Name Search (ESC to exit) ")
+                (app_state.search_query.clone(), " Name Search ")
+            }
+            SearchType::Cgroup => {
+                (format!("ct:{}", search_value), " Container/Cgroup Search ")
+            }
+            SearchType::Frozen => {
+                (format!("frozen:{}", search_value), " Frozen Processes ")
             }
         }
         
@@ -813,6 +1577,15 @@ fn render_search_bar(
         Style::default().fg(theme.primary_text)
     };
 
+    // once Enter locks the filter the keys act on the rows again
+    let title = if app_state.search_query.is_empty() {
+        title.to_string()
+    } else if app_state.search_editing {
+        format!("{}(Enter: done, ESC to exit) ", title)
+    } else {
+        format!("{}(locked, /: edit, ESC to exit) ", title)
+    };
+
     let search_widget = Paragraph::new(search_text)
         .style(search_style) 
         .block(
@@ -852,6 +1625,8 @@ fn render_flat_view(
         if app_state.cached_rows.capacity() < app_state.visible_rows {
             app_state.cached_rows.reserve(app_state.visible_rows);
         }
+        let columns = app_state.visible_columns();
+        let context = ColumnContext::new(system, &app_state.net_usage, &app_state.io_usage, &app_state.process_stats, &app_state.cgroups);
         
         for (i, proc) in processes
             .iter()
@@ -881,13 +1656,16 @@ fn render_flat_view(
                 }
             }
 
-            let row = Row::new(vec![
-                proc.pid().to_string(),
-                name_str,
-                format!("{:.2}%", usage),
-                format!("{:.2} MB", proc.memory() as f64 / 1024.0 / 1024.0),
-            ])
-            .style(Style::default().fg(color));
+            let tagged = app_state.is_tagged(proc.pid().as_u32());
+            let frozen = proc.status() == ProcessStatus::Stop;
+            if tagged && actual_index != app_state.selected_process {
+                color = theme.warning_text;
+            } else if frozen && actual_index != app_state.selected_process {
+                color = theme.process_info;
+            }
+
+            let row = Row::new(process_cells(&columns, &context, proc, tagged, frozen, None))
+                .style(Style::default().fg(color));
 
             app_state.cached_rows.push(row);
         }
@@ -905,13 +1683,24 @@ fn render_flat_view(
                         .join(" ");
 
                     let thread_count = proc.tasks().map_or(0, |tasks| tasks.len());
+                    let sockets = app_state.net_usage.get(proc.pid().as_u32()).copied().unwrap_or_default();
+                    let io = app_state.io_usage.get(proc.pid().as_u32()).copied().unwrap_or_default();
+                    let gpu = app_state.gpu.get(proc.pid().as_u32())
+                        .map_or("GPU: -".to_string(), |g| format!("GPU: {:.1}%", g.busy_percent));
                     
-                    let info_row = Row::new(vec![
+                    let cgroup_path = app_state.cgroups.get(proc.pid().as_u32()).map_or("-".to_string(), |c| c.path.clone());
+                    let info_row = Row::new(fit_cells(vec![
                         format!("Args: {:?}", args),
+                        format!("Cgroup: {}", cgroup_path),
                         format!("Threads: {}", thread_count),
                         format!("Core: {}", proc.cpu_usage() as usize % system.cpus().len()),
                         format!("Status: {:?}", proc.status()),
-                    ])
+                        format!("TCP: {}", sockets.tcp),
+                        format!("UDP: {}", sockets.udp),
+                        gpu,
+                        format!("R: {}", format_bytes(io.total_read)),
+                        format!("W: {}", format_bytes(io.total_written)),
+                    ], columns.len()))
                     .style(Style::default().fg(theme.process_info));
                     
                     app_state.cached_rows.insert(insert_index + 1, info_row);
@@ -926,7 +1715,8 @@ fn render_flat_view(
         app_state.rows_cache_valid = true;
     }
 
-    let header = Row::new(vec!["PID", "Name", "CPU", "Memory"])
+    let columns = app_state.visible_columns();
+    let header = column_header(&columns, app_state.sort_column, app_state.sort_descending, "Name")
         .style(Style::default().fg(theme.secondary_text));
 
     let title_extra = if app_state.show_info { " | Tab: Tree View" } else { "" };
@@ -938,18 +1728,181 @@ fn render_flat_view(
         match search_type {
             SearchType::Pid => format!(" | PID Search: {} result(s)", results_count),
             SearchType::Name => format!(" | Name Search: {}/{}", results_count, app_state.last_process_count),
+            SearchType::Cgroup => format!(" | Cgroup Search: {} result(s)", results_count),
+            SearchType::Frozen => format!(" | Frozen: {} process(es)", results_count),
         }
     } else {
         String::new()
     };
 
+    let table = Table::new(app_state.cached_rows.clone(), column_constraints(&columns))
+    .header(header)
+    .style(Style::default().fg(theme.highlight_text))
+    .block(
+        Block::default()
+            .title(format!(
+                " Top Processes - /: Search{}{}{}{} | Enter: Info{} | c: Cgroups | C: Columns | o/p: Nice | k: kill | {} ",
+                search_info,
+                tag_info(app_state),
+                follow_info(app_state),
+                if app_state.search_active{" | ESC: Exit Search"} else {""},
+                title_extra,
+                sort_info(app_state)
+            ))
+            .title_style(Style::default().fg(theme.primary_text))
+            .borders(Borders::ALL),
+    );
+
+    app_state.info_area = area;
+    frame.render_widget(table, area);
+}
+
+// tagged rows get a mark in front of the pid so they stand out even while selected
+// ● for tagged, ‖ for stopped (SIGSTOP, ctrl+z), both fit in the two columns before the pid
+fn pid_cell(pid: u32, tagged: bool, frozen: bool) -> String {
+    format!("{}{}{}", if tagged { "●" } else { " " }, if frozen { "‖" } else { " " }, pid)
+}
+
+fn tag_info(app_state: &AppState) -> String {
+    if app_state.tagged.is_empty() {
+        " | Space/*: Tag".to_string()
+    } else {
+        format!(" | {} tagged (U: clear)", app_state.tagged.len())
+    }
+}
+
+// processes aggregated per container / systemd unit, Enter filters the process list down to one group
+fn render_cgroup_groups(
+    frame: &mut ratatui::Frame,
+    app_state: &mut AppState,
+    area: Rect,
+) {
+    let theme = app_state.theme_manager.current_theme();
+    let summaries = &app_state.cgroup_summaries;
+    let visible_rows = area.height.saturating_sub(3) as usize;
+    let start = app_state.group_selected.saturating_sub(visible_rows.saturating_sub(1));
+
+    let rows: Vec<Row> = summaries
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible_rows)
+        .map(|(i, summary)| {
+            let color = if i == app_state.group_selected {
+                theme.process_selected
+            } else if summary.cpu > 50.0 {
+                theme.process_high_cpu
+            } else if summary.kind == GroupKind::Container {
+                theme.highlight_text
+            } else {
+                theme.process_normal
+            };
+            Row::new(vec![
+                summary.group.clone(),
+                summary.kind.as_str().to_string(),
+                summary.processes.to_string(),
+                format!("{:.2}%", summary.cpu),
+                format!("{:.2} MB", summary.memory as f64 / 1024.0 / 1024.0),
+                summary.path.clone(),
+            ])
+            .style(Style::default().fg(color))
+        })
+        .collect();
+
+    let header = Row::new(vec!["Group", "Kind", "Procs", "CPU", "Memory", "Cgroup"])
+        .style(Style::default().fg(theme.secondary_text));
+
+    let containers = summaries.iter().filter(|s| s.kind == GroupKind::Container).count();
+    let table = Table::new(
+        rows,
+        &[
+            Constraint::Percentage(25),
+            Constraint::Length(10),
+            Constraint::Length(6),
+            Constraint::Length(10),
+            Constraint::Length(14),
+            Constraint::Min(20),
+        ],
+    )
+    .header(header)
+    .style(Style::default().fg(theme.highlight_text))
+    .block(
+        Block::default()
+            .title(format!(
+                " Cgroups ({} groups, {} containers) - ↑↓: Select | Enter: Show Processes | c/Esc: Back ",
+                summaries.len(),
+                containers
+            ))
+            .title_style(Style::default().fg(theme.primary_text))
+            .borders(Borders::ALL),
+    );
+
+    app_state.info_area = area;
+    frame.render_widget(table, area);
+}
+
+fn render_thread_view(
+    frame: &mut ratatui::Frame,
+    app_state: &mut AppState,
+    area: Rect,
+) {
+    let theme = app_state.theme_manager.current_theme();
+    let Some(view) = &app_state.thread_view else {
+        return;
+    };
+    let visible_rows = area.height.saturating_sub(3) as usize;
+    let start = view.selected.saturating_sub(visible_rows.saturating_sub(1));
+
+    let rows: Vec<Row> = view
+        .threads
+        .iter()
+        .enumerate()
+        .skip(start)
+        .take(visible_rows)
+        .map(|(i, thread)| {
+            let cpu = thread.cpu.unwrap_or(0.0);
+            let color = if i == view.selected {
+                theme.process_selected
+            } else if cpu > 50.0 {
+                theme.process_high_cpu
+            } else if thread.tid == view.pid {
+                theme.highlight_text
+            } else {
+                theme.process_normal
+            };
+            Row::new(vec![
+                thread.tid.to_string(),
+                thread.cpu.map_or("-".to_string(), |cpu| format!("{:.2}%", cpu)),
+                thread.state.to_string(),
+                thread.last_cpu.to_string(),
+                thread.name.clone(),
+            ])
+            .style(Style::default().fg(color))
+        })
+        .collect();
+
+    // the sort column gets an arrow, same as the process list shows its sort category in the title
+    let header_cell = |label: &'static str, sort: ThreadSort| {
+        if view.sort == sort { format!("{} ▼", label) } else { label.to_string() }
+    };
+    let header = Row::new(vec![
+        header_cell("TID", ThreadSort::Tid),
+        header_cell("CPU", ThreadSort::Cpu),
+        header_cell("State", ThreadSort::State),
+        header_cell("Last CPU", ThreadSort::LastCpu),
+        header_cell("Name", ThreadSort::Name),
+    ])
+    .style(Style::default().fg(theme.secondary_text));
+
+    let running = view.threads.iter().filter(|t| t.state == 'R').count();
     let table = Table::new(
-        app_state.cached_rows.clone(),
+        rows,
         &[
-            Constraint::Percentage(50),
-            Constraint::Percentage(30),
             Constraint::Length(10),
-            Constraint::Length(20),
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Length(10),
+            Constraint::Min(20),
         ],
     )
     .header(header)
@@ -957,11 +1910,13 @@ fn render_flat_view(
     .block(
         Block::default()
             .title(format!(
-                " Top Processes - /: Search{}{} | Enter: Info{} | o/p: Nice | k: kill | {}",
-                search_info,
-                if app_state.search_active{" | ESC: Exit Search"} else {""},
-                title_extra,
-                app_state.sort_category.as_str()
+                " Threads of {} ({}){} - {} threads, {} running | Sort: {} | ↑↓: Select | ←→: Sort | T/Esc: Back ",
+                view.name,
+                view.pid,
+                if view.exited { " [exited]" } else { "" },
+                view.threads.len(),
+                running,
+                view.sort.as_str()
             ))
             .title_style(Style::default().fg(theme.primary_text))
             .borders(Borders::ALL),
@@ -971,6 +1926,76 @@ fn render_flat_view(
     frame.render_widget(table, area);
 }
 
+// one cell per visible column, the pid gets the tag/frozen marks and the tree passes its indented name
+fn process_cells(
+    columns: &[ColumnSetting],
+    context: &ColumnContext,
+    process: &Process,
+    tagged: bool,
+    frozen: bool,
+    name: Option<String>,
+) -> Vec<String> {
+    let mut name = name;
+    columns
+        .iter()
+        .map(|column| match column.kind {
+            ColumnKind::Pid => pid_cell(process.pid().as_u32(), tagged, frozen),
+            ColumnKind::Name if name.is_some() => name.take().unwrap_or_default(),
+            kind => context.cell(kind, process),
+        })
+        .collect()
+}
+
+fn follow_info(app_state: &AppState) -> String {
+    match app_state.selected_pid.filter(|_| app_state.follow_selected) {
+        Some(pid) => format!(" | Following {} ({}), w: Stop", app_state.selected_name, pid),
+        None => String::new(),
+    }
+}
+
+// e.g. "CPU (per Core %) ▼, then PID", R flips the arrow and S the tie breaker
+fn sort_info(app_state: &AppState) -> String {
+    format!(
+        "{} {}, then {}",
+        app_state.sort_column.description(),
+        if app_state.sort_descending { "▼" } else { "▲" },
+        app_state.sort_secondary.as_str()
+    )
+}
+
+// the sort column gets an arrow pointing the way it's sorted
+fn column_header(columns: &[ColumnSetting], sort_column: ColumnKind, descending: bool, name_label: &str) -> Row<'static> {
+    Row::new(columns.iter().map(|column| {
+        let label = match column.kind {
+            ColumnKind::Pid => "  PID".to_string(),
+            ColumnKind::Name => name_label.to_string(),
+            kind => kind.label().to_string(),
+        };
+        if column.kind == sort_column {
+            format!("{} {}", label, if descending { "▼" } else { "▲" })
+        } else {
+            label
+        }
+    }).collect::<Vec<String>>())
+}
+
+fn column_constraints(columns: &[ColumnSetting]) -> Vec<Constraint> {
+    columns
+        .iter()
+        .map(|column| if column.kind.flexible() { Constraint::Min(column.width) } else { Constraint::Length(column.width) })
+        .collect()
+}
+
+// the info row has more to say than there may be columns, the leftovers share the last cell
+fn fit_cells(mut cells: Vec<String>, count: usize) -> Vec<String> {
+    if count > 0 && cells.len() > count {
+        let rest = cells.split_off(count - 1).join("  ");
+        cells.push(rest);
+    }
+    cells.resize(count, String::new());
+    cells
+}
+
 #[deprecated(note = "deprecated! use render_processes_optimized() instead!")]
 fn render_processes(
     frame: &mut ratatui::Frame,
@@ -1065,8 +2090,8 @@ fn render_processes(
             -> WORKS!
          */
             .title(format!(
-                " Top Processes - Enter: Info | o/p: Nice | k: kill | {}",
-                app_state.sort_category.as_str()
+                " Top Processes - Enter: Info | o/p: Nice | k: kill | {} ",
+                sort_info(app_state)
             ))
             .title_style(Style::default().fg(theme.primary_text))
             .borders(Borders::ALL),
@@ -1221,7 +2246,8 @@ fn render_settings_panel(frame: &mut ratatui::Frame, app_state: &AppState, area:
 
 fn render_daemon_settings_panel(frame: &mut ratatui::Frame, app_state: &AppState, area: Rect) {
     let panel_width = area.width.saturating_sub(12).min(70);
-    let panel_height = (app_state.available_services.len() as u16) + 8;
+    let group_rows = if app_state.available_groups.is_empty() { 0 } else { app_state.available_groups.len() as u16 + 2 };
+    let panel_height = (app_state.available_services.len() as u16) + group_rows + 9;
     let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
     let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
     let panel_area = Rect::new(x, y, panel_width, panel_height);
@@ -1245,25 +2271,40 @@ fn render_daemon_settings_panel(frame: &mut ratatui::Frame, app_state: &AppState
         }
     }
 
-    // Build options list: available services + Save + Reset
-    let mut options = vec![
-        format!("Select service to auto-run:"),
+    // Build options list: available services + groups + Save + Reset
+    // each line carries the daemon_selected_index it represents (None = not selectable)
+    let mut options: Vec<(String, Option<usize>)> = vec![
+        ("Select service to auto-run:".to_string(), None),
     ];
-    
-    for service in &app_state.available_services {
+
+    for (i, service) in app_state.available_services.iter().enumerate() {
         let selected_marker = if service == &app_state.daemon_settings.enabled_service {
             "● ".to_string()
         } else {
             "○ ".to_string()
         };
-        options.push(format!("  {}{}", selected_marker, service));
+        options.push((format!("  {}{}", selected_marker, service), Some(i)));
     }
-    
-    options.push("".to_string()); // Blank line
-    options.push("Save settings".to_string());
-    options.push("Reset defaults".to_string());
 
-    for (i, line) in options.iter().enumerate() {
+    let groups_start = app_state.available_services.len();
+    if !app_state.available_groups.is_empty() {
+        options.push(("".to_string(), None));
+        options.push(("Groups (s: start  x: stop  r: restart):".to_string(), None));
+        for (i, group) in app_state.available_groups.iter().enumerate() {
+            options.push((format!("  ▣ {}", group), Some(groups_start + i)));
+        }
+    }
+
+    let save_index = groups_start + app_state.available_groups.len();
+    options.push(("".to_string(), None)); // Blank line
+    options.push(("Save settings".to_string(), Some(save_index)));
+    options.push(("Reset defaults".to_string(), Some(save_index + 1)));
+    if !app_state.daemon_status_message.is_empty() {
+        options.push(("".to_string(), None));
+        options.push((app_state.daemon_status_message.clone(), None));
+    }
+
+    for (i, (line, index)) in options.iter().enumerate() {
         let y = inner.y + i as u16;
         if y >= inner.y + inner.height { break; }
         
@@ -1271,19 +2312,9 @@ fn render_daemon_settings_panel(frame: &mut ratatui::Frame, app_state: &AppState
         let x_start = if inner.width > line_width { inner.x + (inner.width - line_width) / 2 } else { inner.x };
         
         // Determine if this line is selected
-        let is_sel = if i == 0 {
-            false // Header not selectable
-        } else if i >= 1 && i <= app_state.available_services.len() {
-            app_state.daemon_selected_index == i - 1
-        } else if i == app_state.available_services.len() + 2 {
-            app_state.daemon_selected_index == app_state.available_services.len()
-        } else if i == app_state.available_services.len() + 3 {
-            app_state.daemon_selected_index == app_state.available_services.len() + 1
-        } else {
-            false
-        };
+        let is_sel = *index == Some(app_state.daemon_selected_index);
         
-        let style = if i == 0 {
+        let style = if index.is_none() {
             Style::default().fg(Color::Cyan).bg(Color::Black)
         } else if is_sel {
             Style::default().fg(Color::Yellow).bg(Color::Black).add_modifier(Modifier::BOLD)
@@ -1313,4 +2344,336 @@ fn render_daemon_settings_panel(frame: &mut ratatui::Frame, app_state: &AppState
         cell.set_style(Style::default().fg(Color::LightCyan).bg(Color::Black));
         xx = xx.saturating_add(1);
     }
-}
\ No newline at end of file
+}
+
+fn render_disk_panel(frame: &mut ratatui::Frame, app_state: &AppState, area: Rect) {
+    let theme = app_state.theme_manager.current_theme();
+    let monitor = &app_state.disk_monitor;
+    let panel_width = area.width.saturating_sub(8).min(130);
+    let panel_height = (monitor.len() as u16 + 13).min(area.height);
+    let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
+    let panel_area = Rect::new(x, y, panel_width, panel_height);
+
+    let block = Block::default()
+        .title(format!(" Disks ({}) ", monitor.len()))
+        .title_style(Style::default().fg(theme.primary_text).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.secondary_border))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(Clear, panel_area);
+    frame.render_widget(&block, panel_area);
+    let inner = block.inner(panel_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1), Constraint::Length(6), Constraint::Length(1)])
+        .split(inner);
+
+    let rows: Vec<Row> = monitor.list()
+        .iter()
+        .enumerate()
+        .map(|(i, disk)| {
+            // no diskstats device (overlay, tmpfs...) means there's nothing to measure, not zero traffic
+            let (read, write, iops) = if disk.device.is_some() {
+                (
+                    format_rate(disk.read_rate),
+                    format_rate(disk.write_rate),
+                    format!("{:.0}/{:.0}", disk.read_iops, disk.write_iops),
+                )
+            } else {
+                ("-".to_string(), "-".to_string(), "-".to_string())
+            };
+
+            let style = if i == app_state.current_disk_index {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else if disk.usage() > 0.9 || disk.inode_usage().is_some_and(|u| u > 0.9) {
+                Style::default().fg(theme.warning_text)
+            } else {
+                Style::default().fg(theme.primary_text)
+            };
+
+            Row::new(vec![
+                disk.name.clone(),
+                disk.mount_point.clone(),
+                disk.file_system.clone(),
+                format!("{:.1}% of {}", disk.usage() * 100.0, format_bytes(disk.total_space)),
+                disk.inode_usage().map_or("-".to_string(), |u| format!("{:.1}%", u * 100.0)),
+                if disk.read_only { "ro" } else { "rw" }.to_string(),
+                read,
+                write,
+                iops,
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let header = Row::new(vec!["Device", "Mount", "FS", "Used", "Inodes", "Mode", "Read", "Write", "IOPS r/w"])
+        .style(Style::default().fg(theme.secondary_text).add_modifier(Modifier::BOLD));
+
+    let table = Table::new(
+        rows,
+        &[
+            Constraint::Min(14),
+            Constraint::Min(14),
+            Constraint::Length(8),
+            Constraint::Length(21),
+            Constraint::Length(7),
+            Constraint::Length(5),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Length(11),
+        ],
+    )
+    .header(header);
+    frame.render_widget(table, chunks[0]);
+
+    if !monitor.counters_available {
+        let note = Paragraph::new("/proc/diskstats not available, throughput and IOPS can't be measured")
+            .style(Style::default().fg(theme.warning_text));
+        frame.render_widget(note, chunks[1]);
+    }
+
+    // history of the selected disk: read, write and total iops side by side
+    if let Some(disk) = monitor.get(app_state.current_disk_index) {
+        let graphs = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(34), Constraint::Percentage(33), Constraint::Percentage(33)])
+            .split(chunks[2]);
+
+        let series = [
+            (format!(" Read {} ", format_rate(disk.read_rate)), &disk.read_history, graphs[0], theme.cpu_low),
+            (format!(" Write {} ", format_rate(disk.write_rate)), &disk.write_history, graphs[1], theme.cpu_medium),
+            (format!(" IOPS {:.0} ", disk.read_iops + disk.write_iops), &disk.iops_history, graphs[2], theme.cpu_high),
+        ];
+        for (title, history, chunk, color) in series {
+            let graph_block = Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.secondary_border));
+            let graph_area = graph_block.inner(chunk);
+            frame.render_widget(graph_block, chunk);
+
+            let data = history.to_vec();
+            let visible = &data[data.len().saturating_sub(graph_area.width as usize)..];
+            let sparkline = Sparkline::default()
+                .style(Style::default().fg(color))
+                .data(visible)
+                .bar_set(Set::default());
+            frame.render_widget(sparkline, graph_area);
+        }
+    }
+
+    let instr = Paragraph::new("u/i: select disk  d/Esc: close")
+        .style(Style::default().fg(theme.secondary_text))
+        .alignment(Alignment::Center);
+    frame.render_widget(instr, chunks[3]);
+}
+
+fn render_sensors_panel(frame: &mut ratatui::Frame, app_state: &AppState, area: Rect) {
+    let theme = app_state.theme_manager.current_theme();
+    let sensors = &app_state.sensors;
+    let panel_width = area.width.saturating_sub(8).min(90);
+    let panel_height = (sensors.temperatures.len().max(1) as u16 + sensors.fans.len().max(1) as u16 + 10).min(area.height);
+    let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
+    let panel_area = Rect::new(x, y, panel_width, panel_height);
+
+    let block = Block::default()
+        .title(" Sensors ")
+        .title_style(Style::default().fg(theme.primary_text).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.secondary_border))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(Clear, panel_area);
+    frame.render_widget(&block, panel_area);
+    let inner = block.inner(panel_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(sensors.temperatures.len().max(1) as u16 + 1),
+            Constraint::Length(1),
+            Constraint::Length(sensors.fans.len().max(1) as u16 + 1),
+            Constraint::Length(1),
+            Constraint::Length(2),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(inner);
+
+    let celsius = |value: Option<f32>| value.map_or("-".to_string(), |v| format!("{:.1}°C", v));
+
+    if sensors.temperatures.is_empty() {
+        frame.render_widget(Paragraph::new("No temperature sensors found").style(Style::default().fg(theme.secondary_text)), chunks[0]);
+    } else {
+        let rows: Vec<Row> = sensors.temperatures
+            .iter()
+            .map(|temp| {
+                let color = if temp.is_critical() {
+                    theme.error_text
+                } else if temp.is_warning() {
+                    theme.warning_text
+                } else {
+                    theme.primary_text
+                };
+                Row::new(vec![
+                    temp.label.clone(),
+                    celsius(temp.celsius),
+                    celsius(temp.highest),
+                    format!("{:.0}°C", temp.warning_threshold()),
+                    celsius(temp.critical),
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            &[Constraint::Min(24), Constraint::Length(10), Constraint::Length(10), Constraint::Length(10), Constraint::Length(10)],
+        )
+        .header(Row::new(vec!["Temperature", "Current", "Highest", "Warning", "Critical"]).style(Style::default().fg(theme.secondary_text).add_modifier(Modifier::BOLD)));
+        frame.render_widget(table, chunks[0]);
+    }
+
+    if sensors.fans.is_empty() {
+        frame.render_widget(Paragraph::new("No fans reported by hwmon").style(Style::default().fg(theme.secondary_text)), chunks[2]);
+    } else {
+        let rows: Vec<Row> = sensors.fans
+            .iter()
+            .map(|fan| {
+                let color = if fan.is_warning() { theme.warning_text } else { theme.primary_text };
+                Row::new(vec![
+                    fan.label.clone(),
+                    format!("{} RPM", fan.rpm),
+                    fan.min_rpm.map_or("-".to_string(), |m| format!("{} RPM", m)),
+                ])
+                .style(Style::default().fg(color))
+            })
+            .collect();
+
+        let table = Table::new(rows, &[Constraint::Min(24), Constraint::Length(12), Constraint::Length(12)])
+            .header(Row::new(vec!["Fan", "Speed", "Minimum"]).style(Style::default().fg(theme.secondary_text).add_modifier(Modifier::BOLD)));
+        frame.render_widget(table, chunks[2]);
+    }
+
+    // per-core clocks are next to the core gauges, this is just the spread
+    let freqs = &sensors.core_frequencies;
+    let freq_text = match (freqs.iter().min(), freqs.iter().max()) {
+        (Some(min), Some(max)) => format!(
+            "CPU clock: min {} MHz | avg {} MHz | max {} MHz",
+            min, freqs.iter().sum::<u64>() / freqs.len() as u64, max
+        ),
+        _ => "CPU clock: unknown".to_string(),
+    };
+    frame.render_widget(Paragraph::new(freq_text).style(Style::default().fg(theme.primary_text)), chunks[4]);
+
+    let instr = Paragraph::new("e/Esc: close")
+        .style(Style::default().fg(theme.secondary_text))
+        .alignment(Alignment::Center);
+    frame.render_widget(instr, chunks[6]);
+}
+
+fn render_service_manager_panel(frame: &mut ratatui::Frame, system: &System, app_state: &AppState, area: Rect) {
+    let theme = app_state.theme_manager.current_theme();
+    let panel_width = area.width.saturating_sub(8).min(110);
+    let panel_height = (app_state.service_snapshots.len() as u16 + 8).min(area.height);
+    let x = area.x + (area.width.saturating_sub(panel_width)) / 2;
+    let y = area.y + (area.height.saturating_sub(panel_height)) / 2;
+    let panel_area = Rect::new(x, y, panel_width, panel_height);
+
+    let block = Block::default()
+        .title(format!(" Service Manager ({} services) ", app_state.service_snapshots.len()))
+        .title_style(Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Black));
+
+    frame.render_widget(Clear, panel_area);
+    frame.render_widget(&block, panel_area);
+    let inner = block.inner(panel_area);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1), Constraint::Length(1)])
+        .split(inner);
+
+    let num_cores = system.cpus().len().max(1) as f32;
+    let rows: Vec<Row> = app_state.service_snapshots
+        .iter()
+        .enumerate()
+        .map(|(i, service)| {
+            // cpu/mem come from the same process data the main table uses
+            let process = service.pid.and_then(|pid| system.process(sysinfo::Pid::from_u32(pid)));
+            let (cpu, mem) = match process {
+                Some(p) => (format!("{:.1}%", p.cpu_usage() / num_cores), format_bytes(p.memory())),
+                None => ("-".to_string(), "-".to_string()),
+            };
+
+            let health = match service.last_health_ok {
+                Some(true) => "ok".to_string(),
+                Some(false) => format!("fail ({})", service.health_failures),
+                None => "-".to_string(),
+            };
+
+            let status_color = match service.status {
+                ServiceStatus::Running => theme.cpu_low,
+                ServiceStatus::Starting | ServiceStatus::Stopping => theme.warning_text,
+                ServiceStatus::Failed | ServiceStatus::Unhealthy => theme.error_text,
+                ServiceStatus::Stopped => theme.secondary_text,
+            };
+
+            let style = if i == app_state.service_selected_index {
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(status_color)
+            };
+
+            Row::new(vec![
+                service.name.clone(),
+                service.status.as_str().to_string(),
+                service.pid.map_or("-".to_string(), |p| p.to_string()),
+                service.uptime_secs.map_or("-".to_string(), format_duration),
+                service.restart_count.to_string(),
+                health,
+                cpu,
+                mem,
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let header = Row::new(vec!["Service", "Status", "PID", "Uptime", "Restarts", "Health", "CPU", "Memory"])
+        .style(Style::default().fg(Color::Cyan));
+
+    let table = Table::new(
+        rows,
+        &[
+            Constraint::Min(16),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(7),
+            Constraint::Length(11),
+        ],
+    )
+    .header(header)
+    .style(Style::default().fg(Color::White).bg(Color::Black));
+
+    frame.render_widget(table, chunks[0]);
+
+    let status_line = match &app_state.service_snapshot_error {
+        Some(e) => Paragraph::new(format!("Supervisor unreachable: {}", e)).style(Style::default().fg(Color::Red)),
+        None => Paragraph::new(app_state.daemon_status_message.as_str()).style(Style::default().fg(Color::Cyan)),
+    };
+    frame.render_widget(status_line.alignment(Alignment::Center), chunks[1]);
+
+    let instr = Paragraph::new("↑/↓ move  s: start  x: stop  r: restart  Tab: groups/settings  Esc: close")
+        .style(Style::default().fg(Color::LightCyan))
+        .alignment(Alignment::Center);
+    frame.render_widget(instr, chunks[2]);
+}